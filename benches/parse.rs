@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use odata_params::filters::{borrowed, parse_str};
+use std::hint::black_box;
+
+/// Filters as sent by API clients, from simple lookups to generated ones.
+const FILTERS: &[(&str, &str)] = &[
+    ("lookup", "customerId eq 5b8e3a34-1f2d-4c6b-9f1e-2a7d8c9e0f11"),
+    (
+        "search",
+        "contains(tolower(displayName), 'smith') and status ne 'archived' and createdAt ge 2024-01-01T00:00:00Z",
+    ),
+    (
+        "range",
+        "(price ge 10.5 and price le 99.99) or (discount gt 0 and category/name eq 'clearance')",
+    ),
+    (
+        "in_list",
+        "region in ('north-america', 'south-america', 'europe', 'middle-east', 'africa', 'asia-pacific')",
+    ),
+    (
+        "lambda",
+        "tags/any(tag: tag eq 'priority' or tag eq 'escalated') and assignees/all(user: user/active eq true)",
+    ),
+    (
+        "escapes",
+        r"lastName eq 'O\'Brien' or lastName eq 'D\'Angelo' or notes eq 'line one\nline two'",
+    ),
+];
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    for (name, filter) in FILTERS {
+        group.throughput(Throughput::Bytes(filter.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("owned", name), filter, |b, filter| {
+            b.iter(|| parse_str(black_box(filter)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("borrowed", name), filter, |b, filter| {
+            b.iter(|| borrowed::parse_str(black_box(filter)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
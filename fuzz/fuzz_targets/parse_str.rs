@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use odata_params::chrono::SecondsFormat;
+use odata_params::filters::{
+    parse_str, parse_str_with, to_query_string_with, FormatOptions, ParseOptions, QuoteStyle,
+};
+
+fuzz_target!(|filter: &str| {
+    let Ok(expr) = parse_str_with(filter, &ParseOptions::untrusted()) else {
+        return;
+    };
+
+    let options = FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    };
+    if let Ok(written) = to_query_string_with(&expr, &options) {
+        assert!(
+            parse_str(&written).is_ok(),
+            "{filter:?} was written as {written:?}"
+        );
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use odata_params::chrono::SecondsFormat;
+use odata_params::filters::{parse_str, to_query_string_with, Expr, FormatOptions, QuoteStyle};
+
+fuzz_target!(|expr: Expr| {
+    let options = FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    };
+    let written = to_query_string_with(&expr, &options).unwrap();
+
+    assert_eq!(parse_str(&written), Ok(expr), "{written}");
+});
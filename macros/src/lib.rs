@@ -0,0 +1,175 @@
+//! Macros checking OData v4 expressions at compile time for the
+//! `odata-params` crate.
+
+use odata_params::chrono::{Datelike, Timelike};
+use odata_params::filters::{parse_str, CompareOperator, Expr, LambdaOperator, Value};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Parses a `$filter` expression at compile time into an
+/// `odata_params::filters::Expr`.
+///
+/// Syntax errors are reported as compile errors on the string literal, so a
+/// filter that compiles always builds its tree without failing.
+///
+/// ```
+/// use odata_params::filters::parse_str;
+/// use odata_params_macros::filter;
+///
+/// let expr = filter!("age gt 30 and startswith(name, 'J')");
+///
+/// assert_eq!(
+///     expr,
+///     parse_str("age gt 30 and startswith(name, 'J')").expect("valid filter tree"),
+/// );
+/// ```
+///
+/// ```compile_fail
+/// use odata_params_macros::filter;
+///
+/// let expr = filter!("name eq 'Bob");
+/// ```
+#[proc_macro]
+pub fn filter(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+
+    match parse_str(literal.value()) {
+        Ok(expr) => expr_tokens(&expr).into(),
+        Err(error) => syn::Error::new(literal.span(), format!("invalid filter: {error}"))
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// Generates the code building an expression.
+fn expr_tokens(expr: &Expr) -> TokenStream2 {
+    let boxed = |expr: &Expr| {
+        let expr = expr_tokens(expr);
+        quote!(::std::boxed::Box::new(#expr))
+    };
+    let list = |exprs: &[Expr]| {
+        let exprs = exprs.iter().map(expr_tokens);
+        quote!(::std::vec![#(#exprs),*])
+    };
+
+    match expr {
+        Expr::Or(lhs, rhs) => {
+            let (lhs, rhs) = (boxed(lhs), boxed(rhs));
+            quote!(::odata_params::filters::Expr::Or(#lhs, #rhs))
+        }
+        Expr::And(lhs, rhs) => {
+            let (lhs, rhs) = (boxed(lhs), boxed(rhs));
+            quote!(::odata_params::filters::Expr::And(#lhs, #rhs))
+        }
+        Expr::Not(expr) => {
+            let expr = boxed(expr);
+            quote!(::odata_params::filters::Expr::Not(#expr))
+        }
+        Expr::Compare(lhs, op, rhs) => {
+            let (lhs, op, rhs) = (boxed(lhs), compare_operator_tokens(op), boxed(rhs));
+            quote!(::odata_params::filters::Expr::Compare(#lhs, #op, #rhs))
+        }
+        Expr::In(lhs, values) => {
+            let (lhs, values) = (boxed(lhs), list(values));
+            quote!(::odata_params::filters::Expr::In(#lhs, #values))
+        }
+        Expr::Function(name, args) => {
+            let args = list(args);
+            quote!(::odata_params::filters::Expr::Function(::std::string::String::from(#name), #args))
+        }
+        Expr::Lambda(collection, op, variable, body) => {
+            let (collection, body) = (boxed(collection), boxed(body));
+            let op = match op {
+                LambdaOperator::Any => quote!(::odata_params::filters::LambdaOperator::Any),
+                LambdaOperator::All => quote!(::odata_params::filters::LambdaOperator::All),
+            };
+            quote!(::odata_params::filters::Expr::Lambda(
+                #collection,
+                #op,
+                ::std::string::String::from(#variable),
+                #body,
+            ))
+        }
+        Expr::Identifier(name) => {
+            quote!(::odata_params::filters::Expr::Identifier(::std::string::String::from(#name)))
+        }
+        Expr::Alias(name) => {
+            quote!(::odata_params::filters::Expr::Alias(::std::string::String::from(#name)))
+        }
+        Expr::Value(value) => {
+            let value = value_tokens(value);
+            quote!(::odata_params::filters::Expr::Value(#value))
+        }
+    }
+}
+
+fn compare_operator_tokens(op: &CompareOperator) -> TokenStream2 {
+    match op {
+        CompareOperator::Equal => quote!(::odata_params::filters::CompareOperator::Equal),
+        CompareOperator::NotEqual => quote!(::odata_params::filters::CompareOperator::NotEqual),
+        CompareOperator::GreaterThan => {
+            quote!(::odata_params::filters::CompareOperator::GreaterThan)
+        }
+        CompareOperator::GreaterOrEqual => {
+            quote!(::odata_params::filters::CompareOperator::GreaterOrEqual)
+        }
+        CompareOperator::LessThan => quote!(::odata_params::filters::CompareOperator::LessThan),
+        CompareOperator::LessOrEqual => {
+            quote!(::odata_params::filters::CompareOperator::LessOrEqual)
+        }
+        CompareOperator::Has => quote!(::odata_params::filters::CompareOperator::Has),
+    }
+}
+
+/// Generates the code building a value.
+///
+/// Values without a const constructor are rebuilt from their parts, which
+/// were validated by parsing and so cannot fail at runtime.
+fn value_tokens(value: &Value) -> TokenStream2 {
+    match value {
+        Value::Null => quote!(::odata_params::filters::Value::Null),
+        Value::Bool(value) => quote!(::odata_params::filters::Value::Bool(#value)),
+        Value::Number(number) => {
+            let number = number.to_string();
+            quote!(::odata_params::filters::Value::Number(
+                <::odata_params::bigdecimal::BigDecimal as ::std::str::FromStr>::from_str(#number)
+                    .expect("valid number")
+            ))
+        }
+        Value::Uuid(uuid) => {
+            let uuid = uuid.as_u128();
+            quote!(::odata_params::filters::Value::Uuid(
+                ::odata_params::uuid::Uuid::from_u128(#uuid)
+            ))
+        }
+        Value::DateTime(datetime) => {
+            let (seconds, nanoseconds) = (datetime.timestamp(), datetime.timestamp_subsec_nanos());
+            quote!(::odata_params::filters::Value::DateTime(
+                ::odata_params::chrono::DateTime::from_timestamp(#seconds, #nanoseconds)
+                    .expect("valid datetime")
+            ))
+        }
+        Value::Date(date) => {
+            let days = date.num_days_from_ce();
+            quote!(::odata_params::filters::Value::Date(
+                ::odata_params::chrono::NaiveDate::from_num_days_from_ce_opt(#days)
+                    .expect("valid date")
+            ))
+        }
+        Value::Time(time) => {
+            let (seconds, nanoseconds) = (time.num_seconds_from_midnight(), time.nanosecond());
+            quote!(::odata_params::filters::Value::Time(
+                ::odata_params::chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                    #seconds,
+                    #nanoseconds,
+                )
+                .expect("valid time")
+            ))
+        }
+        Value::String(string) => {
+            quote!(::odata_params::filters::Value::String(::std::string::String::from(#string)))
+        }
+    }
+}
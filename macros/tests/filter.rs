@@ -0,0 +1,58 @@
+use odata_params::filters::{parse_str, Expr};
+use odata_params_macros::filter;
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+#[test]
+fn logical_operators() {
+    assert_eq!(
+        filter!("age gt 30 and (name eq 'J' or not active)"),
+        parsed("age gt 30 and (name eq 'J' or not active)")
+    );
+}
+
+#[test]
+fn comparisons_and_functions() {
+    assert_eq!(
+        filter!("startswith(tolower(name), 'j') and price le @max"),
+        parsed("startswith(tolower(name), 'j') and price le @max")
+    );
+    assert_eq!(
+        filter!("color in ('red', 'blue') and style has 'Bold'"),
+        parsed("color in ('red', 'blue') and style has 'Bold'")
+    );
+}
+
+#[test]
+fn lambdas_and_paths() {
+    assert_eq!(
+        filter!("owner/name eq 'Bob' and orders/all(ord: ord/items/any(item: item/qty gt 1))"),
+        parsed("owner/name eq 'Bob' and orders/all(ord: ord/items/any(item: item/qty gt 1))")
+    );
+}
+
+#[test]
+fn values() {
+    assert_eq!(
+        filter!("aa eq null and bb eq true and cc eq 12.50 and dd eq 1000"),
+        parsed("aa eq null and bb eq true and cc eq 12.50 and dd eq 1000")
+    );
+    assert_eq!(
+        filter!("id eq da820b39-5ad2-4441-b664-c902dbd377d8"),
+        parsed("id eq da820b39-5ad2-4441-b664-c902dbd377d8")
+    );
+    assert_eq!(
+        filter!("dd eq 2024-03-01 and tt eq 12:30:15.125"),
+        parsed("dd eq 2024-03-01 and tt eq 12:30:15.125")
+    );
+    assert_eq!(
+        filter!("created gt 2024-03-01T10:00:00.123456+02:00"),
+        parsed("created gt 2024-03-01T10:00:00.123456+02:00")
+    );
+    assert_eq!(
+        filter!("name eq 'O\\'Brien ☃'"),
+        parsed("name eq 'O\\'Brien ☃'")
+    );
+}
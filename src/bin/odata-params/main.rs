@@ -0,0 +1,270 @@
+//! Command-line tool to parse, format, validate and rewrite OData `$filter`
+//! expressions.
+
+mod schema;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use odata_params::filters::{
+    parse_str_with, to_query_string_with, Expr, FormatOptions, KeywordCase, ParseOptions,
+    QuoteStyle, Type,
+};
+use odata_params::json_ast::JsonFilter;
+use odata_params::query::QueryError;
+use std::fmt::{self, Display};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(
+    name = "odata-params",
+    version,
+    about = "Parses, formats, validates and rewrites OData $filter expressions."
+)]
+struct Cli {
+    /// Parse with the limits applied to filters from untrusted clients.
+    #[arg(long, global = true)]
+    untrusted: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints a filter with minimal parentheses.
+    Format {
+        #[command(flatten)]
+        input: Input,
+
+        #[command(flatten)]
+        output: Output,
+    },
+
+    /// Prints the tree of a filter in the versioned JSON format.
+    Ast {
+        #[command(flatten)]
+        input: Input,
+
+        /// Print the JSON on a single line.
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// Validates a filter against the types of a schema file.
+    ///
+    /// The schema is either a JSON type map, or a CSDL document in XML or
+    /// JSON along with the entity set to filter.
+    Validate {
+        #[command(flatten)]
+        input: Input,
+
+        /// JSON type map, or CSDL document ending in `.xml` or `.json`.
+        #[arg(long, short)]
+        schema: PathBuf,
+
+        /// Entity set whose properties are filtered, for CSDL schemas.
+        #[arg(long, short)]
+        entity_set: Option<String>,
+    },
+
+    /// Rewrites a filter into an equivalent form.
+    Translate {
+        #[command(flatten)]
+        input: Input,
+
+        /// The form to rewrite the filter into.
+        #[arg(long, short, value_enum)]
+        to: Form,
+
+        /// Maximum number of clauses of a normal form.
+        #[arg(long, default_value_t = 64)]
+        max_clauses: usize,
+
+        #[command(flatten)]
+        output: Output,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// The filter, read from stdin when missing or `-`.
+    filter: Option<String>,
+}
+
+#[derive(Args)]
+struct Output {
+    /// Break `and` and `or` groups across indented lines.
+    #[arg(long)]
+    pretty: bool,
+
+    /// Write keywords and operators in uppercase.
+    #[arg(long)]
+    upper: bool,
+
+    /// Escape quotes with a backslash, as `parse_str` reads them, instead of
+    /// doubling them.
+    #[arg(long)]
+    backslash_quotes: bool,
+
+    /// Percent-encode the filter for use in a URL.
+    #[arg(long)]
+    encode: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Form {
+    /// Sorted operands and normalized values, stable across processes.
+    Canonical,
+
+    /// Redundant comparisons and groups removed.
+    Simplified,
+
+    /// Conjunctive normal form, an `and` of `or` clauses.
+    Cnf,
+
+    /// Disjunctive normal form, an `or` of `and` clauses.
+    Dnf,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// An error to report, along with the code of the error it comes from.
+struct Failure {
+    code: Option<&'static str>,
+    message: String,
+}
+
+impl Failure {
+    fn new(message: impl Display) -> Self {
+        Self {
+            code: None,
+            message: message.to_string(),
+        }
+    }
+
+    fn coded(code: &'static str, message: impl Display) -> Self {
+        Self {
+            code: Some(code),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "error[{code}]: {}", self.message),
+            None => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Runs a command, returning what to print.
+fn run(cli: Cli) -> Result<String, Failure> {
+    let limits = match cli.untrusted {
+        true => ParseOptions::untrusted(),
+        false => ParseOptions::default(),
+    };
+    let parse = |input: Input| {
+        let filter = read_filter(input)?;
+        parse_str_with(&filter, &limits).map_err(|error| Failure::coded(error.code(), error))
+    };
+
+    match cli.command {
+        Command::Format { input, output } => format(&parse(input)?, &output),
+
+        Command::Ast { input, compact } => {
+            let json = JsonFilter::new(&parse(input)?);
+            let json = match compact {
+                true => serde_json::to_string(&json),
+                false => serde_json::to_string_pretty(&json),
+            };
+
+            json.map_err(Failure::new)
+        }
+
+        Command::Validate {
+            input,
+            schema,
+            entity_set,
+        } => {
+            let expr = parse(input)?;
+            let (identifiers, functions) = schema::read(&schema, entity_set.as_deref())?;
+            let given = expr
+                .validate(&identifiers, &functions)
+                .map_err(|error| Failure::coded(error.code(), error))?;
+
+            if given != Type::Boolean {
+                let error = QueryError::FilterNotBoolean { given };
+                return Err(Failure::coded(error.code(), error));
+            }
+
+            Ok("The filter is valid.".to_owned())
+        }
+
+        Command::Translate {
+            input,
+            to,
+            max_clauses,
+            output,
+        } => {
+            let expr = parse(input)?;
+            let expr = match to {
+                Form::Canonical => expr.canonicalize(),
+                Form::Simplified => expr.simplify(),
+                Form::Cnf => expr.to_cnf(max_clauses).map_err(Failure::new)?,
+                Form::Dnf => expr.to_dnf(max_clauses).map_err(Failure::new)?,
+            };
+
+            format(&expr, &output)
+        }
+    }
+}
+
+fn read_filter(input: Input) -> Result<String, Failure> {
+    match input.filter {
+        Some(filter) if filter != "-" => Ok(filter),
+        _ => {
+            let mut filter = String::new();
+            std::io::stdin()
+                .read_to_string(&mut filter)
+                .map_err(|error| Failure::new(format!("Cannot read stdin: {error}")))?;
+
+            Ok(filter.trim_end_matches(['\r', '\n']).to_owned())
+        }
+    }
+}
+
+fn format(expr: &Expr, output: &Output) -> Result<String, Failure> {
+    let options = FormatOptions {
+        keyword_case: match output.upper {
+            true => KeywordCase::Upper,
+            false => KeywordCase::Lower,
+        },
+        quote_style: match output.backslash_quotes {
+            true => QuoteStyle::Backslash,
+            false => QuoteStyle::Doubled,
+        },
+        percent_encode: output.encode,
+        ..match output.pretty {
+            true => FormatOptions::pretty(),
+            false => FormatOptions::default(),
+        }
+    };
+
+    to_query_string_with(expr, &options).map_err(Failure::new)
+}
@@ -0,0 +1,130 @@
+//! Reading the identifiers and functions type maps of a schema file.
+
+use odata_params::csdl::Metadata;
+use odata_params::filters::{FunctionsTypeMap, IdentifiersTypeMap, Type};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::Failure;
+
+/// Reads a JSON type map, or a CSDL document in XML or JSON.
+///
+/// A JSON type map looks like:
+///
+/// ```json
+/// {
+///   "identifiers": { "age": "Number", "name": "String" },
+///   "functions": {
+///     "tolower": { "args": ["String"], "returns": "String" },
+///     "concat": { "args": ["String"], "variadic": "String", "returns": "String" }
+///   }
+/// }
+/// ```
+pub fn read(
+    path: &Path,
+    entity_set: Option<&str>,
+) -> Result<(IdentifiersTypeMap, FunctionsTypeMap), Failure> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|error| Failure::new(format!("Cannot read '{}': {error}", path.display())))?;
+
+    if path.extension().is_some_and(|extension| extension == "xml") {
+        let metadata = Metadata::from_xml(&content).map_err(Failure::new)?;
+        return from_metadata(&metadata, entity_set);
+    }
+
+    let json: Value = serde_json::from_str(&content).map_err(|error| {
+        Failure::new(format!("Malformed JSON in '{}': {error}", path.display()))
+    })?;
+
+    if json.get("$Version").is_some() {
+        let metadata = Metadata::from_json(&content).map_err(Failure::new)?;
+        return from_metadata(&metadata, entity_set);
+    }
+
+    from_type_map(&json)
+}
+
+fn from_metadata(
+    metadata: &Metadata,
+    entity_set: Option<&str>,
+) -> Result<(IdentifiersTypeMap, FunctionsTypeMap), Failure> {
+    let entity_set = entity_set
+        .ok_or_else(|| Failure::new("The --entity-set option is required with a CSDL schema."))?;
+    let identifiers = metadata
+        .identifiers_type_map(entity_set)
+        .map_err(Failure::new)?;
+
+    Ok((identifiers, metadata.functions_type_map()))
+}
+
+fn from_type_map(json: &Value) -> Result<(IdentifiersTypeMap, FunctionsTypeMap), Failure> {
+    let mut identifiers = HashMap::new();
+    let mut functions = HashMap::new();
+
+    for (name, t) in members(json, "identifiers")? {
+        identifiers.insert(name.clone(), read_type(t, &format!("identifiers/{name}"))?);
+    }
+
+    for (name, signature) in members(json, "functions")? {
+        let path = format!("functions/{name}");
+        let args = match signature.get("args") {
+            Some(Value::Array(args)) => args
+                .iter()
+                .enumerate()
+                .map(|(i, t)| read_type(t, &format!("{path}/args/{i}")))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+            Some(_) => {
+                return Err(Failure::new(format!(
+                    "Member '{path}/args' is expected to be an array."
+                )))
+            }
+        };
+        let variadic = match signature.get("variadic") {
+            Some(Value::Null) | None => None,
+            Some(t) => Some(read_type(t, &format!("{path}/variadic"))?),
+        };
+        let returns = match signature.get("returns") {
+            Some(t) => read_type(t, &format!("{path}/returns"))?,
+            None => return Err(Failure::new(format!("Member '{path}/returns' is missing."))),
+        };
+
+        functions.insert(name.clone(), (args, variadic, returns));
+    }
+
+    Ok((identifiers.into(), functions.into()))
+}
+
+fn members<'a>(
+    json: &'a Value,
+    key: &str,
+) -> Result<impl Iterator<Item = (&'a String, &'a Value)>, Failure> {
+    match json.get(key) {
+        Some(Value::Object(members)) => Ok(Some(members.iter()).into_iter().flatten()),
+        None => Ok(None.into_iter().flatten()),
+        Some(_) => Err(Failure::new(format!(
+            "Member '{key}' is expected to be an object."
+        ))),
+    }
+}
+
+fn read_type(t: &Value, path: &str) -> Result<Type, Failure> {
+    let t = match t.as_str().map(str::to_ascii_lowercase).as_deref() {
+        Some("null") => Type::Null,
+        Some("boolean") => Type::Boolean,
+        Some("number") => Type::Number,
+        Some("uuid") => Type::Uuid,
+        Some("datetime") => Type::DateTime,
+        Some("date") => Type::Date,
+        Some("time") => Type::Time,
+        Some("string") => Type::String,
+        _ => {
+            return Err(Failure::new(format!(
+                "Member '{path}' is expected to be a type such as \"Number\" or \"String\"."
+            )))
+        }
+    };
+
+    Ok(t)
+}
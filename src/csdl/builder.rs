@@ -0,0 +1,305 @@
+use super::{
+    ComplexType, EntityContainer, EntitySet, EntityType, EnumMember, EnumType, Function, Metadata,
+    NavigationProperty, Parameter, Property, ReturnType, Schema,
+};
+
+impl Metadata {
+    /// Creates an empty document for the given OData version.
+    pub fn new(version: impl Into<String>) -> Self {
+        Self {
+            version: version.into(),
+            schemas: Vec::new(),
+        }
+    }
+
+    /// Adds a schema to the document.
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schemas.push(schema);
+        self
+    }
+}
+
+impl Schema {
+    /// Creates an empty schema for the given namespace.
+    pub fn new(namespace: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the alias that can be used in place of the namespace.
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Adds an entity type to the schema.
+    pub fn with_entity_type(mut self, entity_type: EntityType) -> Self {
+        self.entity_types.push(entity_type);
+        self
+    }
+
+    /// Adds a complex type to the schema.
+    pub fn with_complex_type(mut self, complex_type: ComplexType) -> Self {
+        self.complex_types.push(complex_type);
+        self
+    }
+
+    /// Adds an enum type to the schema.
+    pub fn with_enum_type(mut self, enum_type: EnumType) -> Self {
+        self.enum_types.push(enum_type);
+        self
+    }
+
+    /// Adds a function to the schema.
+    pub fn with_function(mut self, function: Function) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    /// Sets the entity container of the schema.
+    pub fn with_entity_container(mut self, entity_container: EntityContainer) -> Self {
+        self.entity_container = Some(entity_container);
+        self
+    }
+
+    /// Adds an entity set to the entity container of the schema, creating a
+    /// container named `Default` if the schema does not have one yet.
+    pub fn with_entity_set(
+        mut self,
+        name: impl Into<String>,
+        entity_type: impl Into<String>,
+    ) -> Self {
+        self.entity_container
+            .get_or_insert_with(|| EntityContainer::new("Default"))
+            .entity_sets
+            .push(EntitySet {
+                name: name.into(),
+                entity_type: entity_type.into(),
+            });
+        self
+    }
+}
+
+impl EntityType {
+    /// Creates an entity type without properties.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the qualified name of the type this one derives from.
+    pub fn with_base_type(mut self, base_type: impl Into<String>) -> Self {
+        self.base_type = Some(base_type.into());
+        self
+    }
+
+    /// Sets the names of the properties making up the key.
+    pub fn with_key<I>(mut self, key: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.key = key.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a structural property to the type.
+    pub fn with_property(mut self, property: Property) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Adds a navigation property to the type.
+    pub fn with_navigation_property(mut self, property: NavigationProperty) -> Self {
+        self.navigation_properties.push(property);
+        self
+    }
+}
+
+impl ComplexType {
+    /// Creates a complex type without properties.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the qualified name of the type this one derives from.
+    pub fn with_base_type(mut self, base_type: impl Into<String>) -> Self {
+        self.base_type = Some(base_type.into());
+        self
+    }
+
+    /// Adds a structural property to the type.
+    pub fn with_property(mut self, property: Property) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Adds a navigation property to the type.
+    pub fn with_navigation_property(mut self, property: NavigationProperty) -> Self {
+        self.navigation_properties.push(property);
+        self
+    }
+}
+
+impl Property {
+    /// Creates a nullable property of the given type.
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_name: type_name.into(),
+            nullable: true,
+        }
+    }
+
+    /// Sets whether the property can be null.
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+}
+
+impl NavigationProperty {
+    /// Creates a nullable navigation property to the given type.
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_name: type_name.into(),
+            nullable: true,
+            partner: None,
+        }
+    }
+
+    /// Sets whether the property can be null.
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+
+    /// Sets the navigation property on the related type pointing back.
+    pub fn with_partner(mut self, partner: impl Into<String>) -> Self {
+        self.partner = Some(partner.into());
+        self
+    }
+}
+
+impl EnumType {
+    /// Creates an enum type without members.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the underlying integer type.
+    pub fn with_underlying_type(mut self, underlying_type: impl Into<String>) -> Self {
+        self.underlying_type = Some(underlying_type.into());
+        self
+    }
+
+    /// Sets whether members can be combined as bit flags.
+    pub fn with_flags(mut self, is_flags: bool) -> Self {
+        self.is_flags = is_flags;
+        self
+    }
+
+    /// Adds a member to the enumeration.
+    pub fn with_member(mut self, name: impl Into<String>, value: i64) -> Self {
+        self.members.push(EnumMember {
+            name: name.into(),
+            value,
+        });
+        self
+    }
+}
+
+impl Function {
+    /// Creates an unbound function without parameters.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets whether the first parameter is the binding parameter.
+    pub fn with_bound(mut self, is_bound: bool) -> Self {
+        self.is_bound = is_bound;
+        self
+    }
+
+    /// Adds a parameter to the function.
+    pub fn with_parameter(mut self, parameter: Parameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sets the type returned by the function.
+    pub fn with_return_type(mut self, return_type: ReturnType) -> Self {
+        self.return_type = Some(return_type);
+        self
+    }
+}
+
+impl Parameter {
+    /// Creates a nullable parameter of the given type.
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            type_name: type_name.into(),
+            nullable: true,
+        }
+    }
+
+    /// Sets whether the parameter can be null.
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+}
+
+impl ReturnType {
+    /// Creates a nullable return type.
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            nullable: true,
+        }
+    }
+
+    /// Sets whether the returned value can be null.
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+}
+
+impl EntityContainer {
+    /// Creates an entity container without entity sets.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entity_sets: Vec::new(),
+        }
+    }
+
+    /// Adds an entity set to the container.
+    pub fn with_entity_set(
+        mut self,
+        name: impl Into<String>,
+        entity_type: impl Into<String>,
+    ) -> Self {
+        self.entity_sets.push(EntitySet {
+            name: name.into(),
+            entity_type: entity_type.into(),
+        });
+        self
+    }
+}
@@ -0,0 +1,512 @@
+use super::{
+    collection_item, ComplexType, CsdlError, EntityContainer, EntitySet, EntityType, EnumMember,
+    EnumType, Function, Metadata, NavigationProperty, Parameter, Property, ReturnType, Schema,
+};
+use serde_json::{json, Map, Value};
+
+impl Metadata {
+    /// Parses a CSDL JSON document, as defined by OData 4.01.
+    ///
+    /// Annotations and model elements that are not part of `Metadata`, such
+    /// as actions, singletons and type definitions, are ignored.
+    ///
+    /// ```
+    /// use odata_params::csdl::Metadata;
+    ///
+    /// let metadata = Metadata::from_json(r#"{
+    ///     "$Version": "4.01",
+    ///     "$EntityContainer": "Shop.Default",
+    ///     "Shop": {
+    ///         "Product": {
+    ///             "$Kind": "EntityType",
+    ///             "$Key": ["id"],
+    ///             "id": { "$Type": "Edm.Int32" },
+    ///             "name": { "$Nullable": true }
+    ///         },
+    ///         "Default": {
+    ///             "$Kind": "EntityContainer",
+    ///             "Products": { "$Collection": true, "$Type": "Shop.Product" }
+    ///         }
+    ///     }
+    /// }"#).expect("valid CSDL");
+    ///
+    /// let product = metadata.entity_type("Shop.Product").expect("declared type");
+    ///
+    /// assert_eq!(product.properties[1].type_name, "Edm.String");
+    /// assert!(product.properties[1].nullable);
+    /// assert!(metadata.entity_set("Products").is_some());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, CsdlError> {
+        let document: Value = serde_json::from_str(json).map_err(|error| CsdlError::Json {
+            message: error.to_string(),
+        })?;
+
+        let root = object(&document, "$")?;
+
+        let schemas = members(root)
+            .map(|(namespace, schema)| parse_schema(namespace, schema))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            version: optional_str(root, "$", "$Version")?
+                .unwrap_or("4.01")
+                .to_owned(),
+            schemas,
+        })
+    }
+
+    /// Writes the document as CSDL JSON.
+    ///
+    /// Members are written in declaration order and attributes that have
+    /// their default value, such as `"$Nullable": false`, are left out.
+    pub fn to_json(&self) -> Value {
+        let mut root = Map::new();
+
+        root.insert("$Version".to_owned(), json!(self.version));
+
+        for schema in &self.schemas {
+            if let Some(container) = &schema.entity_container {
+                root.insert(
+                    "$EntityContainer".to_owned(),
+                    json!(format!("{}.{}", schema.namespace, container.name)),
+                );
+                break;
+            }
+        }
+
+        for schema in &self.schemas {
+            root.insert(schema.namespace.clone(), write_schema(schema));
+        }
+
+        Value::Object(root)
+    }
+}
+
+fn parse_schema(namespace: &str, value: &Value) -> Result<Schema, CsdlError> {
+    let path = namespace;
+    let members_map = object(value, path)?;
+
+    let mut schema = Schema {
+        namespace: namespace.to_owned(),
+        alias: optional_str(members_map, path, "$Alias")?.map(str::to_owned),
+        ..Schema::default()
+    };
+
+    for (name, member) in members(members_map) {
+        let path = format!("{path}.{name}");
+
+        // Functions and actions are arrays of overloads.
+        if let Value::Array(overloads) = member {
+            for overload in overloads {
+                let overload = object(overload, &path)?;
+
+                if optional_str(overload, &path, "$Kind")? == Some("Function") {
+                    schema
+                        .functions
+                        .push(parse_function(name, overload, &path)?);
+                }
+            }
+
+            continue;
+        }
+
+        let member = object(member, &path)?;
+
+        match optional_str(member, &path, "$Kind")? {
+            Some("EntityType") => {
+                let (properties, navigation_properties) = parse_properties(member, &path)?;
+
+                schema.entity_types.push(EntityType {
+                    name: name.to_owned(),
+                    base_type: optional_str(member, &path, "$BaseType")?.map(str::to_owned),
+                    key: parse_key(member, &path)?,
+                    properties,
+                    navigation_properties,
+                });
+            }
+
+            Some("ComplexType") => {
+                let (properties, navigation_properties) = parse_properties(member, &path)?;
+
+                schema.complex_types.push(ComplexType {
+                    name: name.to_owned(),
+                    base_type: optional_str(member, &path, "$BaseType")?.map(str::to_owned),
+                    properties,
+                    navigation_properties,
+                });
+            }
+
+            Some("EnumType") => schema.enum_types.push(EnumType {
+                name: name.to_owned(),
+                underlying_type: optional_str(member, &path, "$UnderlyingType")?.map(str::to_owned),
+                is_flags: optional_bool(member, &path, "$IsFlags")?.unwrap_or(false),
+                members: members(member)
+                    .map(|(name, value)| {
+                        Ok(EnumMember {
+                            name: name.to_owned(),
+                            value: value
+                                .as_i64()
+                                .ok_or_else(|| unexpected(&path, name, "an integer"))?,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?,
+            }),
+
+            Some("EntityContainer") => {
+                let mut container = EntityContainer::new(name);
+
+                for (name, entity_set) in members(member) {
+                    let entity_set = object(entity_set, &format!("{path}.{name}"))?;
+
+                    // Only entity sets are collections, singletons and
+                    // imports are left out.
+                    if optional_bool(entity_set, &path, "$Collection")? == Some(true) {
+                        container.entity_sets.push(EntitySet {
+                            name: name.to_owned(),
+                            entity_type: required_str(entity_set, &path, "$Type")?.to_owned(),
+                        });
+                    }
+                }
+
+                schema.entity_container = Some(container);
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(schema)
+}
+
+fn parse_key(member: &Map<String, Value>, path: &str) -> Result<Vec<String>, CsdlError> {
+    let Some(key) = member.get("$Key") else {
+        return Ok(Vec::new());
+    };
+
+    key.as_array()
+        .ok_or_else(|| unexpected(path, "$Key", "an array"))?
+        .iter()
+        .map(|property_ref| {
+            property_ref
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| unexpected(path, "$Key", "an array of property names"))
+        })
+        .collect()
+}
+
+fn parse_properties(
+    member: &Map<String, Value>,
+    path: &str,
+) -> Result<(Vec<Property>, Vec<NavigationProperty>), CsdlError> {
+    let mut properties = Vec::new();
+    let mut navigation_properties = Vec::new();
+
+    for (name, property) in members(member) {
+        let path = format!("{path}.{name}");
+        let property = object(property, &path)?;
+
+        let type_name = parse_type_name(property, &path)?;
+        let nullable = optional_bool(property, &path, "$Nullable")?.unwrap_or(false);
+
+        match optional_str(property, &path, "$Kind")? {
+            Some("NavigationProperty") => navigation_properties.push(NavigationProperty {
+                name: name.to_owned(),
+                type_name,
+                nullable,
+                partner: optional_str(property, &path, "$Partner")?.map(str::to_owned),
+            }),
+
+            _ => properties.push(Property {
+                name: name.to_owned(),
+                type_name,
+                nullable,
+            }),
+        }
+    }
+
+    Ok((properties, navigation_properties))
+}
+
+fn parse_function(
+    name: &str,
+    overload: &Map<String, Value>,
+    path: &str,
+) -> Result<Function, CsdlError> {
+    let parameters = match overload.get("$Parameter") {
+        None => Vec::new(),
+        Some(parameters) => parameters
+            .as_array()
+            .ok_or_else(|| unexpected(path, "$Parameter", "an array"))?
+            .iter()
+            .map(|parameter| {
+                let parameter = object(parameter, path)?;
+
+                Ok(Parameter {
+                    name: required_str(parameter, path, "$Name")?.to_owned(),
+                    type_name: parse_type_name(parameter, path)?,
+                    nullable: optional_bool(parameter, path, "$Nullable")?.unwrap_or(false),
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    let return_type = overload
+        .get("$ReturnType")
+        .map(|ret| {
+            let ret = object(ret, path)?;
+
+            Ok(ReturnType {
+                type_name: parse_type_name(ret, path)?,
+                nullable: optional_bool(ret, path, "$Nullable")?.unwrap_or(false),
+            })
+        })
+        .transpose()?;
+
+    Ok(Function {
+        name: name.to_owned(),
+        is_bound: optional_bool(overload, path, "$IsBound")?.unwrap_or(false),
+        parameters,
+        return_type,
+    })
+}
+
+/// Reads `$Type` and `$Collection` into a single type name such as
+/// `Collection(Edm.String)`. The type defaults to `Edm.String`.
+fn parse_type_name(member: &Map<String, Value>, path: &str) -> Result<String, CsdlError> {
+    let type_name = optional_str(member, path, "$Type")?.unwrap_or("Edm.String");
+
+    Ok(match optional_bool(member, path, "$Collection")? {
+        Some(true) => format!("Collection({type_name})"),
+        _ => type_name.to_owned(),
+    })
+}
+
+fn write_schema(schema: &Schema) -> Value {
+    let mut members = Map::new();
+
+    if let Some(alias) = &schema.alias {
+        members.insert("$Alias".to_owned(), json!(alias));
+    }
+
+    for enum_type in &schema.enum_types {
+        let mut member = Map::new();
+
+        member.insert("$Kind".to_owned(), json!("EnumType"));
+
+        if let Some(underlying_type) = &enum_type.underlying_type {
+            member.insert("$UnderlyingType".to_owned(), json!(underlying_type));
+        }
+
+        if enum_type.is_flags {
+            member.insert("$IsFlags".to_owned(), json!(true));
+        }
+
+        for enum_member in &enum_type.members {
+            member.insert(enum_member.name.clone(), json!(enum_member.value));
+        }
+
+        members.insert(enum_type.name.clone(), Value::Object(member));
+    }
+
+    for complex_type in &schema.complex_types {
+        let mut member = Map::new();
+
+        member.insert("$Kind".to_owned(), json!("ComplexType"));
+
+        if let Some(base_type) = &complex_type.base_type {
+            member.insert("$BaseType".to_owned(), json!(base_type));
+        }
+
+        write_properties(
+            &mut member,
+            &complex_type.properties,
+            &complex_type.navigation_properties,
+        );
+
+        members.insert(complex_type.name.clone(), Value::Object(member));
+    }
+
+    for entity_type in &schema.entity_types {
+        let mut member = Map::new();
+
+        member.insert("$Kind".to_owned(), json!("EntityType"));
+
+        if let Some(base_type) = &entity_type.base_type {
+            member.insert("$BaseType".to_owned(), json!(base_type));
+        }
+
+        if !entity_type.key.is_empty() {
+            member.insert("$Key".to_owned(), json!(entity_type.key));
+        }
+
+        write_properties(
+            &mut member,
+            &entity_type.properties,
+            &entity_type.navigation_properties,
+        );
+
+        members.insert(entity_type.name.clone(), Value::Object(member));
+    }
+
+    for function in &schema.functions {
+        let mut overload = Map::new();
+
+        overload.insert("$Kind".to_owned(), json!("Function"));
+
+        if function.is_bound {
+            overload.insert("$IsBound".to_owned(), json!(true));
+        }
+
+        if !function.parameters.is_empty() {
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|parameter| {
+                    let mut value = Map::new();
+                    value.insert("$Name".to_owned(), json!(parameter.name));
+                    write_type(&mut value, &parameter.type_name, parameter.nullable);
+                    Value::Object(value)
+                })
+                .collect();
+
+            overload.insert("$Parameter".to_owned(), Value::Array(parameters));
+        }
+
+        if let Some(ret) = &function.return_type {
+            let mut value = Map::new();
+            write_type(&mut value, &ret.type_name, ret.nullable);
+            overload.insert("$ReturnType".to_owned(), Value::Object(value));
+        }
+
+        // Overloads share the name of the function.
+        match members.get_mut(&function.name) {
+            Some(Value::Array(overloads)) => overloads.push(Value::Object(overload)),
+            _ => {
+                members.insert(
+                    function.name.clone(),
+                    Value::Array(vec![Value::Object(overload)]),
+                );
+            }
+        }
+    }
+
+    if let Some(container) = &schema.entity_container {
+        let mut member = Map::new();
+
+        member.insert("$Kind".to_owned(), json!("EntityContainer"));
+
+        for entity_set in &container.entity_sets {
+            member.insert(
+                entity_set.name.clone(),
+                json!({ "$Collection": true, "$Type": entity_set.entity_type }),
+            );
+        }
+
+        members.insert(container.name.clone(), Value::Object(member));
+    }
+
+    Value::Object(members)
+}
+
+fn write_properties(
+    member: &mut Map<String, Value>,
+    properties: &[Property],
+    navigation_properties: &[NavigationProperty],
+) {
+    for property in properties {
+        let mut value = Map::new();
+        write_type(&mut value, &property.type_name, property.nullable);
+        member.insert(property.name.clone(), Value::Object(value));
+    }
+
+    for property in navigation_properties {
+        let mut value = Map::new();
+        value.insert("$Kind".to_owned(), json!("NavigationProperty"));
+        write_type(&mut value, &property.type_name, property.nullable);
+
+        if let Some(partner) = &property.partner {
+            value.insert("$Partner".to_owned(), json!(partner));
+        }
+
+        member.insert(property.name.clone(), Value::Object(value));
+    }
+}
+
+/// Writes a type name as `$Type` and `$Collection`, along with `$Nullable`.
+fn write_type(value: &mut Map<String, Value>, type_name: &str, nullable: bool) {
+    match collection_item(type_name) {
+        Some(item) => {
+            value.insert("$Collection".to_owned(), json!(true));
+            value.insert("$Type".to_owned(), json!(item));
+        }
+        None => {
+            value.insert("$Type".to_owned(), json!(type_name));
+        }
+    }
+
+    if nullable {
+        value.insert("$Nullable".to_owned(), json!(true));
+    }
+}
+
+/// Iterates over the members of an object that are not `$` attributes or
+/// `@` annotations.
+fn members(object: &Map<String, Value>) -> impl Iterator<Item = (&str, &Value)> {
+    object
+        .iter()
+        .filter(|(name, _)| !name.starts_with('$') && !name.contains('@'))
+        .map(|(name, value)| (name.as_str(), value))
+}
+
+fn object<'a>(value: &'a Value, path: &str) -> Result<&'a Map<String, Value>, CsdlError> {
+    value.as_object().ok_or_else(|| CsdlError::UnexpectedJson {
+        path: path.to_owned(),
+        expected: "an object".to_owned(),
+    })
+}
+
+fn required_str<'a>(
+    object: &'a Map<String, Value>,
+    path: &str,
+    name: &str,
+) -> Result<&'a str, CsdlError> {
+    optional_str(object, path, name)?.ok_or_else(|| unexpected(path, name, "a string"))
+}
+
+fn optional_str<'a>(
+    object: &'a Map<String, Value>,
+    path: &str,
+    name: &str,
+) -> Result<Option<&'a str>, CsdlError> {
+    object
+        .get(name)
+        .map(|value| {
+            value
+                .as_str()
+                .ok_or_else(|| unexpected(path, name, "a string"))
+        })
+        .transpose()
+}
+
+fn optional_bool(
+    object: &Map<String, Value>,
+    path: &str,
+    name: &str,
+) -> Result<Option<bool>, CsdlError> {
+    object
+        .get(name)
+        .map(|value| {
+            value
+                .as_bool()
+                .ok_or_else(|| unexpected(path, name, "a boolean"))
+        })
+        .transpose()
+}
+
+fn unexpected(path: &str, name: &str, expected: &str) -> CsdlError {
+    CsdlError::UnexpectedJson {
+        path: format!("{path}.{name}"),
+        expected: expected.to_owned(),
+    }
+}
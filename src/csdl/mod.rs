@@ -0,0 +1,459 @@
+//! OData Common Schema Definition Language (CSDL) models.
+//!
+//! A `Metadata` document describes the entity types, complex types, enum
+//! types and functions a service advertises through `$metadata`. It can
+//! generate the `IdentifiersTypeMap` and `FunctionsTypeMap` needed to
+//! validate `$filter` expressions against exactly that model.
+//!
+//! Documents can be read from and written to both the XML and the JSON
+//! representations of CSDL, or built in Rust so that the same definition
+//! serves both validation and the `$metadata` endpoint.
+
+mod builder;
+mod json;
+mod xml;
+
+use crate::filters::{FunctionsTypeMap, IdentifiersTypeMap, Type};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Represents various errors that can occur while reading CSDL documents.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum CsdlError {
+    /// The document is not well-formed XML.
+    #[error("Malformed XML: {message}")]
+    Xml { message: String },
+
+    /// The document is not well-formed JSON.
+    #[error("Malformed JSON: {message}")]
+    Json { message: String },
+
+    /// A JSON member does not have the expected shape.
+    #[error("Member '{path}' is expected to be {expected}.")]
+    UnexpectedJson { path: String, expected: String },
+
+    /// The document does not have the expected root element.
+    #[error("Expected a '{expected}' root element but found '{found}'.")]
+    UnexpectedRoot { expected: String, found: String },
+
+    /// A required attribute is missing from an element.
+    #[error("Element '{element}' is missing the '{attribute}' attribute.")]
+    MissingAttribute { element: String, attribute: String },
+
+    /// An attribute has a value that could not be understood.
+    #[error("Element '{element}' has an invalid '{attribute}' value '{value}'.")]
+    InvalidAttribute {
+        element: String,
+        attribute: String,
+        value: String,
+    },
+
+    /// No entity set with the given name is declared.
+    #[error("Undefined entity set '{name}'.")]
+    UndefinedEntitySet { name: String },
+
+    /// A type is referenced but never declared.
+    #[error("Undefined type '{name}'.")]
+    UndefinedType { name: String },
+}
+
+/// A CSDL document made of one or more schemas.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// The OData version the document declares, such as `4.0`.
+    pub version: String,
+
+    /// The schemas declared by the document.
+    pub schemas: Vec<Schema>,
+}
+
+/// A namespace of type and function declarations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schema {
+    /// Namespace used to qualify the names declared in the schema.
+    pub namespace: String,
+
+    /// Optional alias that can be used in place of the namespace.
+    pub alias: Option<String>,
+
+    /// Entity types declared in the schema.
+    pub entity_types: Vec<EntityType>,
+
+    /// Complex types declared in the schema.
+    pub complex_types: Vec<ComplexType>,
+
+    /// Enumeration types declared in the schema.
+    pub enum_types: Vec<EnumType>,
+
+    /// Functions declared in the schema.
+    pub functions: Vec<Function>,
+
+    /// Entity container exposing the entity sets of the service.
+    pub entity_container: Option<EntityContainer>,
+}
+
+/// A structured type with a key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityType {
+    /// Name of the type within its schema.
+    pub name: String,
+
+    /// Qualified name of the type this one derives from.
+    pub base_type: Option<String>,
+
+    /// Names of the properties making up the key.
+    pub key: Vec<String>,
+
+    /// Structural properties of the type.
+    pub properties: Vec<Property>,
+
+    /// Navigation properties of the type.
+    pub navigation_properties: Vec<NavigationProperty>,
+}
+
+/// A structured type without a key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComplexType {
+    /// Name of the type within its schema.
+    pub name: String,
+
+    /// Qualified name of the type this one derives from.
+    pub base_type: Option<String>,
+
+    /// Structural properties of the type.
+    pub properties: Vec<Property>,
+
+    /// Navigation properties of the type.
+    pub navigation_properties: Vec<NavigationProperty>,
+}
+
+/// A structural property of an entity or complex type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Property {
+    /// Name of the property.
+    pub name: String,
+
+    /// Type of the property, such as `Edm.String` or `Collection(NS.Address)`.
+    pub type_name: String,
+
+    /// Whether the property can be null.
+    pub nullable: bool,
+}
+
+/// A property that navigates to related entities.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NavigationProperty {
+    /// Name of the property.
+    pub name: String,
+
+    /// Type of the related entities, such as `Collection(NS.Order)`.
+    pub type_name: String,
+
+    /// Whether the property can be null.
+    pub nullable: bool,
+
+    /// Name of the navigation property on the related type pointing back.
+    pub partner: Option<String>,
+}
+
+/// An enumeration type with named members.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EnumType {
+    /// Name of the type within its schema.
+    pub name: String,
+
+    /// Underlying integer type, `Edm.Int32` when not specified.
+    pub underlying_type: Option<String>,
+
+    /// Whether members can be combined as bit flags.
+    pub is_flags: bool,
+
+    /// Members of the enumeration.
+    pub members: Vec<EnumMember>,
+}
+
+/// A member of an enumeration type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumMember {
+    /// Name of the member.
+    pub name: String,
+
+    /// Value of the member.
+    ///
+    /// Members declared without a value are numbered from zero in the order
+    /// they are declared.
+    pub value: i64,
+}
+
+/// A side-effect free operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Function {
+    /// Name of the function within its schema.
+    pub name: String,
+
+    /// Whether the first parameter is the binding parameter.
+    pub is_bound: bool,
+
+    /// Parameters of the function.
+    pub parameters: Vec<Parameter>,
+
+    /// Type returned by the function.
+    pub return_type: Option<ReturnType>,
+}
+
+/// A parameter of a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parameter {
+    /// Name of the parameter.
+    pub name: String,
+
+    /// Type of the parameter.
+    pub type_name: String,
+
+    /// Whether the parameter can be null.
+    pub nullable: bool,
+}
+
+/// The type returned by a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReturnType {
+    /// Type of the returned value.
+    pub type_name: String,
+
+    /// Whether the returned value can be null.
+    pub nullable: bool,
+}
+
+/// The container of the entity sets a service exposes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityContainer {
+    /// Name of the container.
+    pub name: String,
+
+    /// Entity sets exposed by the container.
+    pub entity_sets: Vec<EntitySet>,
+}
+
+/// A named collection of entities of one entity type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntitySet {
+    /// Name of the entity set, as used in resource paths.
+    pub name: String,
+
+    /// Qualified name of the entity type of its entities.
+    pub entity_type: String,
+}
+
+impl Metadata {
+    /// Finds an entity set by name in any entity container.
+    pub fn entity_set(&self, name: &str) -> Option<&EntitySet> {
+        self.schemas
+            .iter()
+            .filter_map(|schema| schema.entity_container.as_ref())
+            .flat_map(|container| &container.entity_sets)
+            .find(|entity_set| entity_set.name == name)
+    }
+
+    /// Finds an entity type by its namespace or alias qualified name.
+    pub fn entity_type(&self, qualified_name: &str) -> Option<&EntityType> {
+        self.resolve(qualified_name, |schema| &schema.entity_types, |t| &t.name)
+    }
+
+    /// Finds a complex type by its namespace or alias qualified name.
+    pub fn complex_type(&self, qualified_name: &str) -> Option<&ComplexType> {
+        self.resolve(qualified_name, |schema| &schema.complex_types, |t| &t.name)
+    }
+
+    /// Finds an enum type by its namespace or alias qualified name.
+    pub fn enum_type(&self, qualified_name: &str) -> Option<&EnumType> {
+        self.resolve(qualified_name, |schema| &schema.enum_types, |t| &t.name)
+    }
+
+    /// Iterates over every enum type along with its namespace qualified name.
+    pub fn enum_types(&self) -> impl Iterator<Item = (String, &EnumType)> {
+        self.schemas.iter().flat_map(|schema| {
+            schema.enum_types.iter().map(|enum_type| {
+                (
+                    format!("{}.{}", schema.namespace, enum_type.name),
+                    enum_type,
+                )
+            })
+        })
+    }
+
+    /// Builds the identifiers type map for filtering the given entity set.
+    ///
+    /// Every structural property of the entity type, including inherited
+    /// ones, is mapped to the `Type` of its primitive type. Enum properties
+    /// are mapped to `Type::String` since their members are compared by name,
+    /// and collections are mapped to the type of their items so that they can
+    /// be used in lambda expressions. Collection-valued navigation properties
+    /// are mapped to `Type::Null` so they can be the target of a lambda.
+    /// Single-valued complex and navigation properties are left out, along
+    /// with the paths through them such as `address/city`, which can be added
+    /// to the map by hand.
+    ///
+    /// ```
+    /// use odata_params::csdl::Metadata;
+    /// use odata_params::filters::{parse_str, FunctionsTypeMap};
+    /// use std::collections::HashMap;
+    ///
+    /// let metadata = Metadata::from_xml(r#"
+    ///     <edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="4.0">
+    ///       <edmx:DataServices>
+    ///         <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="Shop">
+    ///           <EntityType Name="Product">
+    ///             <Key><PropertyRef Name="id"/></Key>
+    ///             <Property Name="id" Type="Edm.Int32" Nullable="false"/>
+    ///             <Property Name="name" Type="Edm.String"/>
+    ///           </EntityType>
+    ///           <EntityContainer Name="Default">
+    ///             <EntitySet Name="Products" EntityType="Shop.Product"/>
+    ///           </EntityContainer>
+    ///         </Schema>
+    ///       </edmx:DataServices>
+    ///     </edmx:Edmx>
+    /// "#).expect("valid CSDL");
+    ///
+    /// let identifiers = metadata.identifiers_type_map("Products").expect("known entity set");
+    /// let functions = FunctionsTypeMap::from(HashMap::new());
+    ///
+    /// let expr = parse_str("id gt 3 and name ne null").expect("valid filter tree");
+    /// assert_eq!(expr.are_types_valid(&identifiers, &functions), Ok(true));
+    /// ```
+    pub fn identifiers_type_map(&self, entity_set: &str) -> Result<IdentifiersTypeMap, CsdlError> {
+        let entity_set =
+            self.entity_set(entity_set)
+                .ok_or_else(|| CsdlError::UndefinedEntitySet {
+                    name: entity_set.to_owned(),
+                })?;
+
+        self.entity_type_identifiers(&entity_set.entity_type)
+    }
+
+    /// Builds the identifiers type map for filtering entities of the given
+    /// namespace or alias qualified entity type.
+    ///
+    /// See `identifiers_type_map` for how properties are mapped.
+    pub fn entity_type_identifiers(
+        &self,
+        qualified_name: &str,
+    ) -> Result<IdentifiersTypeMap, CsdlError> {
+        let mut map = HashMap::new();
+        let mut next = Some(qualified_name.to_owned());
+
+        // Walk up the inheritance chain, letting derived types win over
+        // their base types and guarding against cyclic declarations.
+        let mut visited = Vec::new();
+
+        while let Some(name) = next.take() {
+            if visited.contains(&name) {
+                break;
+            }
+
+            let entity_type = self
+                .entity_type(&name)
+                .ok_or_else(|| CsdlError::UndefinedType { name: name.clone() })?;
+
+            for property in &entity_type.properties {
+                if let Some(t) = self.filter_type(&property.type_name) {
+                    map.entry(property.name.clone()).or_insert(t);
+                }
+            }
+
+            for property in &entity_type.navigation_properties {
+                if collection_item(&property.type_name).is_some() {
+                    map.entry(property.name.clone()).or_insert(Type::Null);
+                }
+            }
+
+            next = entity_type.base_type.clone();
+            visited.push(name);
+        }
+
+        Ok(map.into())
+    }
+
+    /// Builds the functions type map from the unbound functions of every
+    /// schema whose parameters and return type are primitive or enum types.
+    ///
+    /// Functions are keyed by their unqualified name. When a function is
+    /// overloaded, the first declared overload is used.
+    pub fn functions_type_map(&self) -> FunctionsTypeMap {
+        let mut map = HashMap::new();
+
+        for function in self.schemas.iter().flat_map(|schema| &schema.functions) {
+            if function.is_bound || map.contains_key(&function.name) {
+                continue;
+            }
+
+            let Some(ret) = function
+                .return_type
+                .as_ref()
+                .and_then(|ret| self.filter_type(&ret.type_name))
+            else {
+                continue;
+            };
+
+            let arguments = function
+                .parameters
+                .iter()
+                .map(|parameter| self.filter_type(&parameter.type_name))
+                .collect::<Option<Vec<_>>>();
+
+            if let Some(arguments) = arguments {
+                map.insert(function.name.clone(), (arguments, None, ret));
+            }
+        }
+
+        map.into()
+    }
+
+    /// Maps a CSDL type name to the `Type` used when validating filters.
+    fn filter_type(&self, type_name: &str) -> Option<Type> {
+        let type_name = collection_item(type_name).unwrap_or(type_name);
+
+        primitive_type(type_name).or_else(|| self.enum_type(type_name).map(|_| Type::String))
+    }
+
+    /// Finds a declaration by qualified name across every schema.
+    fn resolve<'a, T>(
+        &'a self,
+        qualified_name: &str,
+        declarations: impl Fn(&'a Schema) -> &'a Vec<T>,
+        name: impl Fn(&T) -> &String,
+    ) -> Option<&'a T> {
+        self.schemas.iter().find_map(|schema| {
+            let local_name = qualified_name
+                .strip_prefix(&schema.namespace)
+                .or_else(|| qualified_name.strip_prefix(schema.alias.as_deref()?))?
+                .strip_prefix('.')?;
+
+            declarations(schema)
+                .iter()
+                .find(|declaration| name(declaration) == local_name)
+        })
+    }
+}
+
+/// Returns the item type of a `Collection(...)` type name.
+fn collection_item(type_name: &str) -> Option<&str> {
+    type_name
+        .strip_prefix("Collection(")
+        .and_then(|item| item.strip_suffix(')'))
+}
+
+/// Maps an `Edm` primitive type name to the `Type` used when validating filters.
+fn primitive_type(type_name: &str) -> Option<Type> {
+    Some(match type_name {
+        "Edm.Boolean" => Type::Boolean,
+        "Edm.Byte" | "Edm.SByte" | "Edm.Int16" | "Edm.Int32" | "Edm.Int64" | "Edm.Single"
+        | "Edm.Double" | "Edm.Decimal" => Type::Number,
+        "Edm.Guid" => Type::Uuid,
+        "Edm.DateTimeOffset" => Type::DateTime,
+        "Edm.Date" => Type::Date,
+        "Edm.TimeOfDay" => Type::Time,
+        "Edm.String" => Type::String,
+        _ => return None,
+    })
+}
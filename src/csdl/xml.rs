@@ -0,0 +1,440 @@
+use super::{
+    ComplexType, CsdlError, EntityContainer, EntitySet, EntityType, EnumMember, EnumType, Function,
+    Metadata, NavigationProperty, Parameter, Property, ReturnType, Schema,
+};
+use roxmltree::{Document, Node};
+use std::fmt::{self, Write};
+
+impl Metadata {
+    /// Parses a CSDL XML document, as served from a `$metadata` endpoint.
+    ///
+    /// Elements are matched by their local name so both the OData v4 and
+    /// the older EDM namespaces are accepted. Elements and attributes that
+    /// are not part of the model, such as annotations, are ignored.
+    pub fn from_xml(xml: &str) -> Result<Self, CsdlError> {
+        let document = Document::parse(xml).map_err(|error| CsdlError::Xml {
+            message: error.to_string(),
+        })?;
+
+        let root = document.root_element();
+
+        if root.tag_name().name() != "Edmx" {
+            return Err(CsdlError::UnexpectedRoot {
+                expected: "Edmx".to_owned(),
+                found: root.tag_name().name().to_owned(),
+            });
+        }
+
+        let schemas = children(root, "DataServices")
+            .flat_map(|data_services| children(data_services, "Schema"))
+            .map(parse_schema)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            version: root.attribute("Version").unwrap_or("4.0").to_owned(),
+            schemas,
+        })
+    }
+
+    /// Writes the document as CSDL XML, ready to be served from a
+    /// `$metadata` endpoint.
+    ///
+    /// ```
+    /// use odata_params::csdl::{EntityType, Metadata, Property, Schema};
+    ///
+    /// let metadata = Metadata::new("4.0").with_schema(
+    ///     Schema::new("Shop")
+    ///         .with_entity_type(
+    ///             EntityType::new("Product")
+    ///                 .with_key(["id"])
+    ///                 .with_property(Property::new("id", "Edm.Int32").with_nullable(false)),
+    ///         )
+    ///         .with_entity_set("Products", "Shop.Product"),
+    /// );
+    ///
+    /// let xml = metadata.to_xml();
+    ///
+    /// assert!(xml.contains(r#"<Property Name="id" Type="Edm.Int32" Nullable="false"/>"#));
+    /// assert_eq!(Metadata::from_xml(&xml), Ok(metadata));
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut output = String::new();
+
+        // Writing to a `String` cannot fail.
+        let _ = write_metadata(&mut output, self);
+
+        output
+    }
+}
+
+fn write_metadata(w: &mut String, metadata: &Metadata) -> fmt::Result {
+    writeln!(w, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<edmx:Edmx xmlns:edmx="http://docs.oasis-open.org/odata/ns/edmx" Version="{}">"#,
+        escape(&metadata.version)
+    )?;
+    writeln!(w, "  <edmx:DataServices>")?;
+
+    for schema in &metadata.schemas {
+        write_schema(w, schema)?;
+    }
+
+    writeln!(w, "  </edmx:DataServices>")?;
+    writeln!(w, "</edmx:Edmx>")
+}
+
+fn write_schema(w: &mut String, schema: &Schema) -> fmt::Result {
+    write!(
+        w,
+        r#"    <Schema xmlns="http://docs.oasis-open.org/odata/ns/edm" Namespace="{}""#,
+        escape(&schema.namespace)
+    )?;
+
+    if let Some(alias) = &schema.alias {
+        write!(w, r#" Alias="{}""#, escape(alias))?;
+    }
+
+    writeln!(w, ">")?;
+
+    for enum_type in &schema.enum_types {
+        write!(w, r#"      <EnumType Name="{}""#, escape(&enum_type.name))?;
+
+        if let Some(underlying_type) = &enum_type.underlying_type {
+            write!(w, r#" UnderlyingType="{}""#, escape(underlying_type))?;
+        }
+
+        if enum_type.is_flags {
+            write!(w, r#" IsFlags="true""#)?;
+        }
+
+        writeln!(w, ">")?;
+
+        for member in &enum_type.members {
+            writeln!(
+                w,
+                r#"        <Member Name="{}" Value="{}"/>"#,
+                escape(&member.name),
+                member.value
+            )?;
+        }
+
+        writeln!(w, "      </EnumType>")?;
+    }
+
+    for complex_type in &schema.complex_types {
+        write!(
+            w,
+            r#"      <ComplexType Name="{}""#,
+            escape(&complex_type.name)
+        )?;
+        write_base_type(w, &complex_type.base_type)?;
+        writeln!(w, ">")?;
+        write_properties(w, &complex_type.properties)?;
+        write_navigation_properties(w, &complex_type.navigation_properties)?;
+        writeln!(w, "      </ComplexType>")?;
+    }
+
+    for entity_type in &schema.entity_types {
+        write!(
+            w,
+            r#"      <EntityType Name="{}""#,
+            escape(&entity_type.name)
+        )?;
+        write_base_type(w, &entity_type.base_type)?;
+        writeln!(w, ">")?;
+
+        if !entity_type.key.is_empty() {
+            writeln!(w, "        <Key>")?;
+
+            for name in &entity_type.key {
+                writeln!(w, r#"          <PropertyRef Name="{}"/>"#, escape(name))?;
+            }
+
+            writeln!(w, "        </Key>")?;
+        }
+
+        write_properties(w, &entity_type.properties)?;
+        write_navigation_properties(w, &entity_type.navigation_properties)?;
+        writeln!(w, "      </EntityType>")?;
+    }
+
+    for function in &schema.functions {
+        write!(w, r#"      <Function Name="{}""#, escape(&function.name))?;
+
+        if function.is_bound {
+            write!(w, r#" IsBound="true""#)?;
+        }
+
+        writeln!(w, ">")?;
+
+        for parameter in &function.parameters {
+            write!(
+                w,
+                r#"        <Parameter Name="{}" Type="{}""#,
+                escape(&parameter.name),
+                escape(&parameter.type_name)
+            )?;
+            write_nullable(w, parameter.nullable)?;
+            writeln!(w, "/>")?;
+        }
+
+        if let Some(ret) = &function.return_type {
+            write!(
+                w,
+                r#"        <ReturnType Type="{}""#,
+                escape(&ret.type_name)
+            )?;
+            write_nullable(w, ret.nullable)?;
+            writeln!(w, "/>")?;
+        }
+
+        writeln!(w, "      </Function>")?;
+    }
+
+    if let Some(container) = &schema.entity_container {
+        writeln!(
+            w,
+            r#"      <EntityContainer Name="{}">"#,
+            escape(&container.name)
+        )?;
+
+        for entity_set in &container.entity_sets {
+            writeln!(
+                w,
+                r#"        <EntitySet Name="{}" EntityType="{}"/>"#,
+                escape(&entity_set.name),
+                escape(&entity_set.entity_type)
+            )?;
+        }
+
+        writeln!(w, "      </EntityContainer>")?;
+    }
+
+    writeln!(w, "    </Schema>")
+}
+
+fn write_base_type(w: &mut String, base_type: &Option<String>) -> fmt::Result {
+    match base_type {
+        Some(base_type) => write!(w, r#" BaseType="{}""#, escape(base_type)),
+        None => Ok(()),
+    }
+}
+
+fn write_properties(w: &mut String, properties: &[Property]) -> fmt::Result {
+    for property in properties {
+        write!(
+            w,
+            r#"        <Property Name="{}" Type="{}""#,
+            escape(&property.name),
+            escape(&property.type_name)
+        )?;
+        write_nullable(w, property.nullable)?;
+        writeln!(w, "/>")?;
+    }
+
+    Ok(())
+}
+
+fn write_navigation_properties(w: &mut String, properties: &[NavigationProperty]) -> fmt::Result {
+    for property in properties {
+        write!(
+            w,
+            r#"        <NavigationProperty Name="{}" Type="{}""#,
+            escape(&property.name),
+            escape(&property.type_name)
+        )?;
+        write_nullable(w, property.nullable)?;
+
+        if let Some(partner) = &property.partner {
+            write!(w, r#" Partner="{}""#, escape(partner))?;
+        }
+
+        writeln!(w, "/>")?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `Nullable` attribute when it differs from the XML default.
+fn write_nullable(w: &mut String, nullable: bool) -> fmt::Result {
+    if nullable {
+        Ok(())
+    } else {
+        write!(w, r#" Nullable="false""#)
+    }
+}
+
+/// Escapes a value for use within a double quoted attribute.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn parse_schema(node: Node) -> Result<Schema, CsdlError> {
+    Ok(Schema {
+        namespace: required(node, "Namespace")?,
+        alias: node.attribute("Alias").map(str::to_owned),
+        entity_types: children(node, "EntityType")
+            .map(parse_entity_type)
+            .collect::<Result<_, _>>()?,
+        complex_types: children(node, "ComplexType")
+            .map(parse_complex_type)
+            .collect::<Result<_, _>>()?,
+        enum_types: children(node, "EnumType")
+            .map(parse_enum_type)
+            .collect::<Result<_, _>>()?,
+        functions: children(node, "Function")
+            .map(parse_function)
+            .collect::<Result<_, _>>()?,
+        entity_container: children(node, "EntityContainer")
+            .next()
+            .map(parse_entity_container)
+            .transpose()?,
+    })
+}
+
+fn parse_entity_type(node: Node) -> Result<EntityType, CsdlError> {
+    Ok(EntityType {
+        name: required(node, "Name")?,
+        base_type: node.attribute("BaseType").map(str::to_owned),
+        key: children(node, "Key")
+            .flat_map(|key| children(key, "PropertyRef"))
+            .map(|property_ref| required(property_ref, "Name"))
+            .collect::<Result<_, _>>()?,
+        properties: children(node, "Property")
+            .map(parse_property)
+            .collect::<Result<_, _>>()?,
+        navigation_properties: children(node, "NavigationProperty")
+            .map(parse_navigation_property)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn parse_complex_type(node: Node) -> Result<ComplexType, CsdlError> {
+    Ok(ComplexType {
+        name: required(node, "Name")?,
+        base_type: node.attribute("BaseType").map(str::to_owned),
+        properties: children(node, "Property")
+            .map(parse_property)
+            .collect::<Result<_, _>>()?,
+        navigation_properties: children(node, "NavigationProperty")
+            .map(parse_navigation_property)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn parse_property(node: Node) -> Result<Property, CsdlError> {
+    Ok(Property {
+        name: required(node, "Name")?,
+        type_name: required(node, "Type")?,
+        nullable: boolean(node, "Nullable", true)?,
+    })
+}
+
+fn parse_navigation_property(node: Node) -> Result<NavigationProperty, CsdlError> {
+    Ok(NavigationProperty {
+        name: required(node, "Name")?,
+        type_name: required(node, "Type")?,
+        nullable: boolean(node, "Nullable", true)?,
+        partner: node.attribute("Partner").map(str::to_owned),
+    })
+}
+
+fn parse_enum_type(node: Node) -> Result<EnumType, CsdlError> {
+    Ok(EnumType {
+        name: required(node, "Name")?,
+        underlying_type: node.attribute("UnderlyingType").map(str::to_owned),
+        is_flags: boolean(node, "IsFlags", false)?,
+        members: children(node, "Member")
+            .zip(0..)
+            .map(|(member, position)| {
+                Ok(EnumMember {
+                    name: required(member, "Name")?,
+                    value: member
+                        .attribute("Value")
+                        .map(|value| value.parse().map_err(|_| invalid(member, "Value", value)))
+                        .transpose()?
+                        .unwrap_or(position),
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn parse_function(node: Node) -> Result<Function, CsdlError> {
+    Ok(Function {
+        name: required(node, "Name")?,
+        is_bound: boolean(node, "IsBound", false)?,
+        parameters: children(node, "Parameter")
+            .map(|parameter| {
+                Ok(Parameter {
+                    name: required(parameter, "Name")?,
+                    type_name: required(parameter, "Type")?,
+                    nullable: boolean(parameter, "Nullable", true)?,
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        return_type: children(node, "ReturnType")
+            .next()
+            .map(|ret| {
+                Ok(ReturnType {
+                    type_name: required(ret, "Type")?,
+                    nullable: boolean(ret, "Nullable", true)?,
+                })
+            })
+            .transpose()?,
+    })
+}
+
+fn parse_entity_container(node: Node) -> Result<EntityContainer, CsdlError> {
+    Ok(EntityContainer {
+        name: required(node, "Name")?,
+        entity_sets: children(node, "EntitySet")
+            .map(|entity_set| {
+                Ok(EntitySet {
+                    name: required(entity_set, "Name")?,
+                    entity_type: required(entity_set, "EntityType")?,
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Iterates over the child elements with the given local name.
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn required(node: Node, attribute: &str) -> Result<String, CsdlError> {
+    node.attribute(attribute)
+        .map(str::to_owned)
+        .ok_or_else(|| CsdlError::MissingAttribute {
+            element: node.tag_name().name().to_owned(),
+            attribute: attribute.to_owned(),
+        })
+}
+
+fn boolean(node: Node, attribute: &str, default: bool) -> Result<bool, CsdlError> {
+    match node.attribute(attribute) {
+        None => Ok(default),
+        Some("true") => Ok(true),
+        Some("false") => Ok(false),
+        Some(value) => Err(invalid(node, attribute, value)),
+    }
+}
+
+fn invalid(node: Node, attribute: &str, value: &str) -> CsdlError {
+    CsdlError::InvalidAttribute {
+        element: node.tag_name().name().to_owned(),
+        attribute: attribute.to_owned(),
+        value: value.to_owned(),
+    }
+}
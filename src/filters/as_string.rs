@@ -0,0 +1,117 @@
+//! Serde helpers storing an `Expr` as its OData `$filter` string, such as
+//! `"age gt 30 and startswith(name, 'J')"`, instead of as a tree.
+//!
+//! Use it with `#[serde(with = "odata_params::filters::as_string")]` on an
+//! `Expr` field, or with `as_string::option` on an `Option<Expr>` field.
+//! The `FilterString` newtype does the same without an attribute.
+//!
+//! Expressions are written with the formatter, escaping quotes with a
+//! backslash and keeping the full precision of date and times so that the
+//! string reads back as the same tree. Strings are read with `parse_str`,
+//! without limits, so they should come from trusted storage.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use odata_params::filters::{as_string, parse_str, Expr};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct SavedSearch {
+//!     name: String,
+//!     #[serde(with = "as_string")]
+//!     filter: Expr,
+//!     #[serde(with = "as_string::option", default)]
+//!     extra: Option<Expr>,
+//! }
+//!
+//! let search = SavedSearch {
+//!     name: "Adults".to_string(),
+//!     filter: parse_str("age ge 18 and name ne 'O\\'Brien'").expect("valid filter tree"),
+//!     extra: None,
+//! };
+//!
+//! let json = serde_json::to_string(&search).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"name":"Adults","filter":"age ge 18 and name ne 'O\\'Brien'","extra":null}"#,
+//! );
+//! assert_eq!(serde_json::from_str::<SavedSearch>(&json).unwrap(), search);
+//! ```
+
+use super::{parse_str, to_query_string_with, Expr, FilterString, FormatOptions, QuoteStyle};
+use chrono::SecondsFormat;
+use serde::de::{self, Deserializer};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Serializes an expression as its `$filter` string.
+pub fn serialize<S: Serializer>(expr: &Expr, serializer: S) -> Result<S::Ok, S::Error> {
+    let options = FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    };
+
+    to_query_string_with(expr, &options)
+        .map_err(ser::Error::custom)?
+        .serialize(serializer)
+}
+
+/// Deserializes an expression from its `$filter` string.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Expr, D::Error> {
+    let filter = String::deserialize(deserializer)?;
+    parse_str(&filter).map_err(de::Error::custom)
+}
+
+/// Serde helpers storing an `Option<Expr>` as its `$filter` string or null.
+pub mod option {
+    use super::{Expr, FilterString};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes an optional expression as its `$filter` string or null.
+    pub fn serialize<S: Serializer>(expr: &Option<Expr>, serializer: S) -> Result<S::Ok, S::Error> {
+        match expr {
+            Some(expr) => serializer.serialize_some(&Wrapper(expr)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an optional expression from its `$filter` string or null.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Expr>, D::Error> {
+        let filter = Option::<FilterString>::deserialize(deserializer)?;
+        Ok(filter.map(|filter| filter.0))
+    }
+
+    struct Wrapper<'a>(&'a Expr);
+
+    impl Serialize for Wrapper<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+}
+
+impl Serialize for FilterString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FilterString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(FilterString)
+    }
+}
+
+impl From<Expr> for FilterString {
+    fn from(expr: Expr) -> Self {
+        FilterString(expr)
+    }
+}
+
+impl From<FilterString> for Expr {
+    fn from(filter: FilterString) -> Self {
+        filter.0
+    }
+}
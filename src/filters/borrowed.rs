@@ -0,0 +1,184 @@
+//! Filters parsed without copying strings out of the `$filter` string.
+//!
+//! Identifiers, function names, lambda variables, aliases and string
+//! literals borrow from the parsed string, so parsing only allocates for the
+//! tree itself, numbers, and string literals holding escape sequences.
+//!
+//! ```
+//! use std::borrow::Cow;
+//! use odata_params::filters::borrowed::{parse_str, Expr, Value};
+//! use odata_params::filters::{to_query_string, CompareOperator};
+//!
+//! let filter = String::from("name eq 'Bob'");
+//! let expr = parse_str(&filter).expect("valid filter tree");
+//!
+//! let Expr::Compare(lhs, CompareOperator::Equal, rhs) = &expr else { unreachable!() };
+//! assert!(matches!(**lhs, Expr::Identifier(Cow::Borrowed("name"))));
+//! assert!(matches!(**rhs, Expr::Value(Value::String(Cow::Borrowed("Bob")))));
+//!
+//! let owned = expr.into_owned();
+//! drop(filter);
+//! assert_eq!(to_query_string(&owned).unwrap(), "name eq 'Bob'");
+//! ```
+
+use super::parse::{parse_filter_with, Build, Parsed};
+use super::{CompareOperator, LambdaOperator, ParseError, ParseOptions, Span};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::borrow::Cow;
+use uuid::Uuid;
+
+/// Represents the different types of expressions in the AST, borrowing
+/// strings from the `$filter` string they were parsed from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expr<'a> {
+    /// Logical OR between two expressions.
+    Or(Box<Expr<'a>>, Box<Expr<'a>>),
+
+    /// Logical AND between two expressions.
+    And(Box<Expr<'a>>, Box<Expr<'a>>),
+
+    /// Logical NOT to invert an expression.
+    Not(Box<Expr<'a>>),
+
+    /// Comparison between two expressions.
+    Compare(Box<Expr<'a>>, CompareOperator, Box<Expr<'a>>),
+
+    /// In operator to check if a value is within a list of values.
+    In(Box<Expr<'a>>, Vec<Expr<'a>>),
+
+    /// Function call with a name and a list of arguments.
+    Function(Cow<'a, str>, Vec<Expr<'a>>),
+
+    /// Lambda expression (any/all)
+    /// Structure: (Collection Identifier, Operator, Lambda Variable, Filter Expression)
+    Lambda(Box<Expr<'a>>, LambdaOperator, Cow<'a, str>, Box<Expr<'a>>),
+
+    /// An identifier.
+    Identifier(Cow<'a, str>),
+
+    /// A parameter alias (e.g., @p1)
+    Alias(Cow<'a, str>),
+
+    /// A constant value.
+    Value(Value<'a>),
+}
+
+/// Represents the various value types, borrowing string literals from the
+/// `$filter` string when they hold no escape sequences.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Value<'a> {
+    /// Null value.
+    Null,
+
+    /// Boolean value.
+    Bool(bool),
+
+    /// Numeric value.
+    Number(BigDecimal),
+
+    /// Uuid value.
+    Uuid(Uuid),
+
+    /// DateTime value.
+    DateTime(DateTime<Utc>),
+
+    /// Date value.
+    Date(NaiveDate),
+
+    /// Time value.
+    Time(NaiveTime),
+
+    /// String value.
+    String(Cow<'a, str>),
+}
+
+/// Parses an OData v4 `$filter` expression string into a borrowed `Expr`
+/// AST.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use odata_params::filters::borrowed::{parse_str, Expr, Value};
+///
+/// let Expr::Compare(_, _, rhs) = parse_str(r"name eq 'O\'Brien'").unwrap() else { unreachable!() };
+///
+/// // Escape sequences are decoded into an owned string.
+/// assert_eq!(*rhs, Expr::Value(Value::String(Cow::Owned("O'Brien".to_string()))));
+/// ```
+pub fn parse_str(query: &str) -> Result<Expr<'_>, ParseError> {
+    parse_str_with(query, &ParseOptions::default())
+}
+
+/// Parses an OData v4 `$filter` expression string into a borrowed `Expr`
+/// AST while enforcing the limits set in `options`.
+pub fn parse_str_with<'a>(query: &'a str, options: &ParseOptions) -> Result<Expr<'a>, ParseError> {
+    parse_filter_with(query, options)
+}
+
+impl Expr<'_> {
+    /// Converts the expression into a plain `Expr`, copying any borrowed
+    /// strings.
+    pub fn into_owned(self) -> super::Expr {
+        let boxed = |expr: Box<Expr>| Box::new(expr.into_owned());
+        let list = |exprs: Vec<Expr>| exprs.into_iter().map(Expr::into_owned).collect();
+
+        match self {
+            Expr::Or(lhs, rhs) => super::Expr::Or(boxed(lhs), boxed(rhs)),
+            Expr::And(lhs, rhs) => super::Expr::And(boxed(lhs), boxed(rhs)),
+            Expr::Not(expr) => super::Expr::Not(boxed(expr)),
+            Expr::Compare(lhs, op, rhs) => super::Expr::Compare(boxed(lhs), op, boxed(rhs)),
+            Expr::In(lhs, values) => super::Expr::In(boxed(lhs), list(values)),
+            Expr::Function(name, args) => super::Expr::Function(name.into_owned(), list(args)),
+            Expr::Lambda(collection, op, variable, body) => {
+                super::Expr::Lambda(boxed(collection), op, variable.into_owned(), boxed(body))
+            }
+            Expr::Identifier(name) => super::Expr::Identifier(name.into_owned()),
+            Expr::Alias(name) => super::Expr::Alias(name.into_owned()),
+            Expr::Value(value) => super::Expr::Value(value.into_owned()),
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Converts the value into a plain `Value`, copying a borrowed string.
+    pub fn into_owned(self) -> super::Value {
+        match self {
+            Value::Null => super::Value::Null,
+            Value::Bool(b) => super::Value::Bool(b),
+            Value::Number(n) => super::Value::Number(n),
+            Value::Uuid(id) => super::Value::Uuid(id),
+            Value::DateTime(dt) => super::Value::DateTime(dt),
+            Value::Date(d) => super::Value::Date(d),
+            Value::Time(t) => super::Value::Time(t),
+            Value::String(s) => super::Value::String(s.into_owned()),
+        }
+    }
+}
+
+impl From<Expr<'_>> for super::Expr {
+    fn from(expr: Expr<'_>) -> Self {
+        expr.into_owned()
+    }
+}
+
+impl<'a> Build<'a> for Expr<'a> {
+    fn build(_span: Span, parsed: Parsed<'a, Self>) -> Self {
+        match parsed {
+            Parsed::Or(lhs, rhs) => Expr::Or(Box::new(lhs), Box::new(rhs)),
+            Parsed::And(lhs, rhs) => Expr::And(Box::new(lhs), Box::new(rhs)),
+            Parsed::Not(expr) => Expr::Not(Box::new(expr)),
+            Parsed::Compare(lhs, op, rhs) => Expr::Compare(Box::new(lhs), op, Box::new(rhs)),
+            Parsed::In(lhs, values) => Expr::In(Box::new(lhs), values),
+            Parsed::Function(name, args) => Expr::Function(Cow::Borrowed(name), args),
+            Parsed::Lambda(collection, op, variable, body) => Expr::Lambda(
+                Box::new(collection),
+                op,
+                Cow::Borrowed(variable),
+                Box::new(body),
+            ),
+            Parsed::Identifier(name) => Expr::Identifier(Cow::Borrowed(name)),
+            Parsed::Alias(name) => Expr::Alias(Cow::Borrowed(name)),
+            Parsed::Value(value) => Expr::Value(value),
+        }
+    }
+}
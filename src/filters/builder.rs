@@ -0,0 +1,377 @@
+use super::{CompareOperator, Condition, Expr, LambdaOperator, Operand, Value};
+use bigdecimal::{BigDecimal, ParseBigDecimalError};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Starts building an expression from an identifier or a property path such
+/// as `owner/name`.
+///
+/// ```
+/// use odata_params::filters::{field, parse_str, Expr};
+///
+/// let built: Expr = field("owner/name").eq("Bob").into();
+///
+/// assert_eq!(built, parse_str("owner/name eq 'Bob'").expect("valid filter tree"));
+/// ```
+pub fn field(name: impl Into<String>) -> Operand {
+    Operand(Expr::Identifier(name.into()))
+}
+
+/// Starts building an expression from a constant.
+///
+/// ```
+/// use odata_params::filters::{field, parse_str, value, Expr};
+///
+/// let built: Expr = value(18).le(field("age")).into();
+///
+/// assert_eq!(built, parse_str("18 le age").expect("valid filter tree"));
+/// ```
+pub fn value(value: impl Into<Value>) -> Operand {
+    Operand(Expr::Value(value.into()))
+}
+
+/// Starts building an expression from a parameter alias such as `@p1`.
+///
+/// ```
+/// use odata_params::filters::{alias, field, parse_str, Expr};
+///
+/// let built: Expr = field("age").gt(alias("@minAge")).into();
+///
+/// assert_eq!(built, parse_str("age gt @minAge").expect("valid filter tree"));
+/// ```
+pub fn alias(name: impl Into<String>) -> Operand {
+    Operand(Expr::Alias(name.into()))
+}
+
+/// Starts building an expression from a function call.
+///
+/// ```
+/// use odata_params::filters::{field, function, parse_str, Expr};
+///
+/// let built: Expr = function("concat", [field("first"), field("last")])
+///     .eq("JaneDoe")
+///     .into();
+///
+/// assert_eq!(
+///     built,
+///     parse_str("concat(first, last) eq 'JaneDoe'").expect("valid filter tree"),
+/// );
+/// ```
+pub fn function(name: impl Into<String>, args: impl IntoIterator<Item = Operand>) -> Operand {
+    Operand(Expr::Function(
+        name.into(),
+        args.into_iter().map(|arg| arg.0).collect(),
+    ))
+}
+
+impl Operand {
+    fn compare(self, op: CompareOperator, other: impl Into<Operand>) -> Condition {
+        Condition(Expr::Compare(
+            Box::new(self.0),
+            op,
+            Box::new(other.into().0),
+        ))
+    }
+
+    fn call(self, name: &str, other: impl Into<Operand>) -> Condition {
+        Condition(Expr::Function(
+            name.to_owned(),
+            vec![self.0, other.into().0],
+        ))
+    }
+
+    fn lambda(
+        self,
+        op: LambdaOperator,
+        variable: impl Into<String>,
+        body: impl FnOnce(Operand) -> Condition,
+    ) -> Condition {
+        let variable = variable.into();
+        let body = body(field(variable.clone()));
+
+        Condition(Expr::Lambda(
+            Box::new(self.0),
+            op,
+            variable,
+            Box::new(body.0),
+        ))
+    }
+
+    /// Compares with `eq`.
+    pub fn eq(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::Equal, other)
+    }
+
+    /// Compares with `ne`.
+    pub fn ne(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::NotEqual, other)
+    }
+
+    /// Compares with `gt`.
+    pub fn gt(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::GreaterThan, other)
+    }
+
+    /// Compares with `ge`.
+    pub fn ge(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::GreaterOrEqual, other)
+    }
+
+    /// Compares with `lt`.
+    pub fn lt(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::LessThan, other)
+    }
+
+    /// Compares with `le`.
+    pub fn le(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::LessOrEqual, other)
+    }
+
+    /// Compares with `has`.
+    pub fn has(self, other: impl Into<Operand>) -> Condition {
+        self.compare(CompareOperator::Has, other)
+    }
+
+    /// Checks membership with `in`.
+    ///
+    /// ```
+    /// use odata_params::filters::{field, parse_str, Expr};
+    ///
+    /// let built: Expr = field("id").is_in([1, 2, 3]).into();
+    ///
+    /// assert_eq!(built, parse_str("id in (1, 2, 3)").expect("valid filter tree"));
+    /// ```
+    pub fn is_in<T: Into<Operand>>(self, values: impl IntoIterator<Item = T>) -> Condition {
+        Condition(Expr::In(
+            Box::new(self.0),
+            values.into_iter().map(|value| value.into().0).collect(),
+        ))
+    }
+
+    /// Calls `startswith` with this operand and a prefix.
+    pub fn starts_with(self, prefix: impl Into<Operand>) -> Condition {
+        self.call("startswith", prefix)
+    }
+
+    /// Calls `endswith` with this operand and a suffix.
+    pub fn ends_with(self, suffix: impl Into<Operand>) -> Condition {
+        self.call("endswith", suffix)
+    }
+
+    /// Calls `contains` with this operand and a substring.
+    pub fn contains(self, substring: impl Into<Operand>) -> Condition {
+        self.call("contains", substring)
+    }
+
+    /// Calls `tolower` on this operand.
+    pub fn to_lower(self) -> Operand {
+        function("tolower", [self])
+    }
+
+    /// Calls `toupper` on this operand.
+    pub fn to_upper(self) -> Operand {
+        function("toupper", [self])
+    }
+
+    /// Checks whether any item of this collection matches the condition
+    /// built from the lambda variable.
+    ///
+    /// ```
+    /// use odata_params::filters::{field, parse_str, Expr};
+    ///
+    /// let built: Expr = field("tags").any("tag", |tag| tag.eq("vip")).into();
+    ///
+    /// assert_eq!(
+    ///     built,
+    ///     parse_str("tags/any(tag: tag eq 'vip')").expect("valid filter tree"),
+    /// );
+    /// ```
+    pub fn any(
+        self,
+        variable: impl Into<String>,
+        body: impl FnOnce(Operand) -> Condition,
+    ) -> Condition {
+        self.lambda(LambdaOperator::Any, variable, body)
+    }
+
+    /// Checks whether all items of this collection match the condition built
+    /// from the lambda variable.
+    pub fn all(
+        self,
+        variable: impl Into<String>,
+        body: impl FnOnce(Operand) -> Condition,
+    ) -> Condition {
+        self.lambda(LambdaOperator::All, variable, body)
+    }
+}
+
+impl Condition {
+    /// Joins this condition and `other` with `and`.
+    pub fn and(self, other: impl Into<Condition>) -> Condition {
+        Condition(self.0.and_with(other.into().0))
+    }
+
+    /// Joins this condition and `other` with `or`.
+    ///
+    /// ```
+    /// use odata_params::filters::{field, to_query_string};
+    ///
+    /// let built = field("age").lt(18).or(field("age").gt(65)).and(field("vip").eq(true));
+    ///
+    /// assert_eq!(
+    ///     to_query_string(&built.into()).expect("valid filter"),
+    ///     "(age lt 18 or age gt 65) and vip eq true",
+    /// );
+    /// ```
+    pub fn or(self, other: impl Into<Condition>) -> Condition {
+        Condition(self.0.or_with(other.into().0))
+    }
+}
+
+/// Negates the condition with `not`.
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Condition {
+        Condition(self.0.negate())
+    }
+}
+
+impl<T: Into<Value>> From<T> for Operand {
+    fn from(value: T) -> Self {
+        Operand(Expr::Value(value.into()))
+    }
+}
+
+impl From<Expr> for Operand {
+    fn from(expr: Expr) -> Self {
+        Operand(expr)
+    }
+}
+
+impl From<Operand> for Expr {
+    fn from(operand: Operand) -> Self {
+        operand.0
+    }
+}
+
+impl From<Expr> for Condition {
+    fn from(expr: Expr) -> Self {
+        Condition(expr)
+    }
+}
+
+impl From<Condition> for Expr {
+    fn from(condition: Condition) -> Self {
+        condition.0
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Value {
+                fn from(value: $integer) -> Self {
+                    Value::Number(BigDecimal::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+impl From<isize> for Value {
+    fn from(value: isize) -> Self {
+        Value::Number(BigDecimal::from(value as i64))
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Number(BigDecimal::from(value as u64))
+    }
+}
+
+/// Converts through the shortest decimal representation of the float, so
+/// that `0.1` becomes `0.1`. Fails on infinite and NaN values.
+impl TryFrom<f32> for Value {
+    type Error = ParseBigDecimalError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        BigDecimal::from_str(&value.to_string()).map(Value::Number)
+    }
+}
+
+/// Converts through the shortest decimal representation of the float, so
+/// that `0.1` becomes `0.1`. Fails on infinite and NaN values.
+impl TryFrom<f64> for Value {
+    type Error = ParseBigDecimalError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        BigDecimal::from_str(&value.to_string()).map(Value::Number)
+    }
+}
+
+impl From<BigDecimal> for Value {
+    fn from(value: BigDecimal) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(value: Uuid) -> Self {
+        Value::Uuid(value)
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for Value {
+    fn from(value: DateTime<Tz>) -> Self {
+        Value::DateTime(value.with_timezone(&Utc))
+    }
+}
+
+/// Takes the date and time as UTC.
+impl From<NaiveDateTime> for Value {
+    fn from(value: NaiveDateTime) -> Self {
+        Value::DateTime(value.and_utc())
+    }
+}
+
+impl From<NaiveDate> for Value {
+    fn from(value: NaiveDate) -> Self {
+        Value::Date(value)
+    }
+}
+
+impl From<NaiveTime> for Value {
+    fn from(value: NaiveTime) -> Self {
+        Value::Time(value)
+    }
+}
+
+/// Converts `None` to `null`.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
@@ -0,0 +1,207 @@
+use super::simplify::{flatten, mirror, Junction};
+use super::{
+    to_query_string_with, CompareOperator, Expr, Fold, FormatOptions, LambdaOperator, Value,
+};
+use chrono::SecondsFormat;
+use std::fmt;
+
+impl Expr {
+    /// Rewrites the expression into a canonical form, so that expressions
+    /// differing only in how they are written compare, hash and print the same.
+    ///
+    /// - Operands of `and` and `or` are flattened, sorted and deduplicated,
+    ///   and so are the values of `in` lists.
+    /// - Comparisons keep constants on the right, as in `a gt 5` rather than
+    ///   `5 lt a`. Other operands are sorted, swapping `gt` with `lt` and
+    ///   `ge` with `le` where needed.
+    /// - Numbers lose their trailing zeros. Datetimes keep their full
+    ///   precision and are already held in UTC, so offsets they were written
+    ///   with do not matter.
+    /// - Lambda variables are renamed after their nesting depth.
+    ///
+    /// The result has the same meaning but is not simplified; call
+    /// `Expr::simplify` first to also merge equivalent terms. Within a
+    /// process the canonical form can key a `HashMap` as it is; use
+    /// `Expr::canonical_key` for a key that is stable across processes.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, to_query_string};
+    ///
+    /// let lhs = parse_str("name eq 'a' and 3 lt age").expect("valid filter tree");
+    /// let rhs = parse_str("age gt 3.00 and name eq 'a'").expect("valid filter tree");
+    ///
+    /// assert_eq!(lhs.clone().canonicalize(), rhs.canonicalize());
+    /// assert_eq!(
+    ///     to_query_string(&lhs.canonicalize()).expect("valid filter"),
+    ///     "age gt 3 and name eq 'a'",
+    /// );
+    /// ```
+    pub fn canonicalize(self) -> Expr {
+        // Lambda variables are renamed to underscores followed by their depth,
+        // with as many underscores as needed to not clash with an identifier.
+        let mut prefix = "_".to_owned();
+
+        while self
+            .identifiers()
+            .any(|identifier| identifier.starts_with(&prefix))
+        {
+            prefix.push('_');
+        }
+
+        Canonicalizer {
+            prefix,
+            scope: Vec::new(),
+        }
+        .fold_expr(self)
+    }
+
+    /// Writes the canonical form of the expression as a string, for use as
+    /// a cache key shared across processes.
+    ///
+    /// Unlike `to_query_string`, which writes datetimes to the millisecond,
+    /// the key keeps their full precision, so that filters differing by less
+    /// than a millisecond do not share a key.
+    ///
+    /// ```
+    /// use odata_params::filters::parse_str;
+    ///
+    /// let lhs = parse_str("created lt 2024-03-01T10:00:00.000001Z").expect("valid filter tree");
+    /// let rhs = parse_str("created lt 2024-03-01T10:00:00.000002Z").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     lhs.clone().canonical_key().expect("valid filter"),
+    ///     "created lt 2024-03-01T10:00:00.000001Z",
+    /// );
+    /// assert_ne!(lhs.canonical_key(), rhs.canonical_key());
+    /// ```
+    pub fn canonical_key(self) -> Result<String, fmt::Error> {
+        let options = FormatOptions {
+            datetime_precision: SecondsFormat::AutoSi,
+            ..FormatOptions::default()
+        };
+
+        to_query_string_with(&self.canonicalize(), &options)
+    }
+}
+
+struct Canonicalizer {
+    prefix: String,
+
+    /// Lambda variables in scope along with their new names.
+    scope: Vec<(String, String)>,
+}
+
+impl Canonicalizer {
+    /// Folds, sorts and deduplicates the terms of a junction.
+    fn fold_junction(&mut self, kind: Junction, lhs: Expr, rhs: Expr) -> Expr {
+        let mut terms = Vec::new();
+
+        // Folding may turn a term into a junction of the same kind, so the
+        // terms are flattened again afterwards.
+        for side in [lhs, rhs] {
+            let mut side_terms = Vec::new();
+            flatten(kind, side, &mut side_terms);
+
+            for term in side_terms {
+                flatten(kind, self.fold_expr(term), &mut terms);
+            }
+        }
+
+        terms.sort();
+        terms.dedup();
+
+        kind.join_all(terms)
+    }
+}
+
+impl Fold for Canonicalizer {
+    fn fold_or(&mut self, lhs: Expr, rhs: Expr) -> Expr {
+        self.fold_junction(Junction::Or, lhs, rhs)
+    }
+
+    fn fold_and(&mut self, lhs: Expr, rhs: Expr) -> Expr {
+        self.fold_junction(Junction::And, lhs, rhs)
+    }
+
+    fn fold_compare(&mut self, lhs: Expr, op: CompareOperator, rhs: Expr) -> Expr {
+        let lhs = self.fold_expr(lhs);
+        let rhs = self.fold_expr(rhs);
+
+        let swap = match (&lhs, &rhs) {
+            (Expr::Value(_), Expr::Value(_)) => lhs > rhs,
+            (Expr::Value(_), _) => true,
+            (_, Expr::Value(_)) => false,
+            (lhs, rhs) => lhs > rhs,
+        };
+
+        match mirror(&op) {
+            Some(mirrored) if swap => Expr::Compare(Box::new(rhs), mirrored, Box::new(lhs)),
+            _ => Expr::Compare(Box::new(lhs), op, Box::new(rhs)),
+        }
+    }
+
+    fn fold_in(&mut self, lhs: Expr, values: Vec<Expr>) -> Expr {
+        let mut values: Vec<Expr> = values
+            .into_iter()
+            .map(|value| self.fold_expr(value))
+            .collect();
+
+        values.sort();
+        values.dedup();
+
+        Expr::In(Box::new(self.fold_expr(lhs)), values)
+    }
+
+    fn fold_lambda(
+        &mut self,
+        collection: Expr,
+        op: LambdaOperator,
+        variable: String,
+        body: Expr,
+    ) -> Expr {
+        let collection = self.fold_expr(collection);
+
+        let renamed = format!("{}{}", self.prefix, self.scope.len());
+        self.scope.push((variable, renamed.clone()));
+        let body = self.fold_expr(body);
+        self.scope.pop();
+
+        Expr::Lambda(Box::new(collection), op, renamed, Box::new(body))
+    }
+
+    fn fold_identifier(&mut self, name: String) -> Expr {
+        let (root, rest) = match name.split_once('/') {
+            Some((root, rest)) => (root, Some(rest)),
+            None => (name.as_str(), None),
+        };
+
+        let renamed = self
+            .scope
+            .iter()
+            .rev()
+            .find(|(variable, _)| variable == root)
+            .map(|(_, renamed)| match rest {
+                Some(rest) => format!("{renamed}/{rest}"),
+                None => renamed.clone(),
+            });
+
+        Expr::Identifier(renamed.unwrap_or(name))
+    }
+
+    fn fold_value(&mut self, value: Value) -> Expr {
+        Expr::Value(match value {
+            Value::Number(number) => {
+                let number = number.normalized();
+
+                // Keep whole numbers such as `100` out of exponent notation.
+                if number.as_bigint_and_exponent().1 < 0 {
+                    Value::Number(number.with_scale(0))
+                } else {
+                    Value::Number(number)
+                }
+            }
+
+            value => value,
+        })
+    }
+}
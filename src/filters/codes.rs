@@ -0,0 +1,132 @@
+use super::{ParseError, PolicyViolation, SpannedValidationError, ValidationError};
+
+impl ParseError {
+    /// A stable, machine-readable code for the error.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, ParseError};
+    ///
+    /// assert_eq!(parse_str("name eq").unwrap_err().code(), "SyntaxError");
+    /// assert_eq!(ParseError::TooManyNodes { max: 64 }.code(), "TooManyNodes");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::Parsing { .. } => "SyntaxError",
+            ParseError::ParsingUuid => "InvalidUuid",
+            ParseError::ParsingNumber => "InvalidNumber",
+            ParseError::ParsingDate => "InvalidDate",
+            ParseError::ParsingTime => "InvalidTime",
+            ParseError::ParsingDateTime => "InvalidDateTime",
+            ParseError::ParsingTimeZone => "InvalidTimeZoneOffset",
+            ParseError::ParsingTimeZoneNamed => "InvalidTimeZoneName",
+            ParseError::ParsingUnicodeCodePoint => "InvalidUnicodeEscape",
+            ParseError::InputTooLong { .. } => "InputTooLong",
+            ParseError::NestingTooDeep { .. } => "NestingTooDeep",
+            ParseError::TooManyNodes { .. } => "TooManyNodes",
+            ParseError::InListTooLarge { .. } => "InListTooLarge",
+            ParseError::LambdaNestingTooDeep { .. } => "LambdaNestingTooDeep",
+            ParseError::StringLiteralTooLong { .. } => "StringLiteralTooLong",
+        }
+    }
+
+    /// The identifier or function the error is about, which parse errors
+    /// never have. Syntax errors are located by `ParseError::position`.
+    pub fn target(&self) -> Option<&str> {
+        None
+    }
+
+    /// The byte offset in the query a syntax error was found at.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, ParseError};
+    ///
+    /// assert_eq!(parse_str("name eq 'a' and").unwrap_err().position(), Some(15));
+    /// assert_eq!(ParseError::TooManyNodes { max: 64 }.position(), None);
+    /// ```
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            ParseError::Parsing { position } => Some(*position),
+            _ => None,
+        }
+    }
+}
+
+impl ValidationError {
+    /// A stable, machine-readable code for the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::LogicalJoinRequiresBooleans { .. } => "LogicalJoinRequiresBooleans",
+            ValidationError::LogicalNotRequiresBoolean { .. } => "LogicalNotRequiresBoolean",
+            ValidationError::ComparingIncompatibleTypes { .. } => "IncompatibleTypes",
+            ValidationError::UndefinedIdentifier { .. } => "UndefinedIdentifier",
+            ValidationError::UndefinedFunction { .. } => "UndefinedFunction",
+            ValidationError::IncorrectFunctionArgumentsCount { .. } => "IncorrectArgumentCount",
+            ValidationError::IncorrectFunctionArgumentType { .. } => "IncorrectArgumentType",
+        }
+    }
+
+    /// The identifier or function the error is about, if any.
+    ///
+    /// ```
+    /// use odata_params::filters::ValidationError;
+    ///
+    /// let error = ValidationError::UndefinedFunction {
+    ///     name: "soundex".to_string(),
+    /// };
+    ///
+    /// assert_eq!(error.code(), "UndefinedFunction");
+    /// assert_eq!(error.target(), Some("soundex"));
+    /// ```
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            ValidationError::UndefinedIdentifier { name }
+            | ValidationError::UndefinedFunction { name }
+            | ValidationError::IncorrectFunctionArgumentsCount { name, .. }
+            | ValidationError::IncorrectFunctionArgumentType { name, .. } => Some(name),
+            ValidationError::LogicalJoinRequiresBooleans { .. }
+            | ValidationError::LogicalNotRequiresBoolean { .. }
+            | ValidationError::ComparingIncompatibleTypes { .. } => None,
+        }
+    }
+}
+
+impl SpannedValidationError {
+    /// The code of the underlying `ValidationError`.
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
+    /// The target of the underlying `ValidationError`.
+    pub fn target(&self) -> Option<&str> {
+        self.error.target()
+    }
+}
+
+impl PolicyViolation {
+    /// A stable, machine-readable code for the violation.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PolicyViolation::OperatorNotAllowed { .. }
+            | PolicyViolation::OperatorNotAllowedOn { .. } => "OperatorNotAllowed",
+            PolicyViolation::InNotAllowed | PolicyViolation::InNotAllowedOn { .. } => {
+                "InNotAllowed"
+            }
+            PolicyViolation::FunctionNotAllowed { .. } => "FunctionNotAllowed",
+            PolicyViolation::LambdaNotAllowed { .. } => "LambdaNotAllowed",
+            PolicyViolation::IdentifierNotFilterable { .. } => "IdentifierNotFilterable",
+        }
+    }
+
+    /// The identifier or function the violation is about, if any.
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            PolicyViolation::OperatorNotAllowedOn { identifier, .. }
+            | PolicyViolation::InNotAllowedOn { identifier }
+            | PolicyViolation::IdentifierNotFilterable { identifier } => Some(identifier),
+            PolicyViolation::FunctionNotAllowed { name } => Some(name),
+            PolicyViolation::OperatorNotAllowed { .. }
+            | PolicyViolation::InNotAllowed
+            | PolicyViolation::LambdaNotAllowed { .. } => None,
+        }
+    }
+}
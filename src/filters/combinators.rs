@@ -0,0 +1,53 @@
+use super::Expr;
+
+impl Expr {
+    /// Joins this expression and `other` with `and`.
+    ///
+    /// The tree keeps each side grouped as a whole, so an `or` within either
+    /// side cannot bind across the `and`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, to_query_string};
+    ///
+    /// let lhs = parse_str("name eq 'Bob' or name eq 'Alice'").expect("valid filter tree");
+    /// let rhs = parse_str("age gt 3").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     to_query_string(&lhs.and_with(rhs)).expect("valid filter"),
+    ///     "(name eq 'Bob' or name eq 'Alice') and age gt 3",
+    /// );
+    /// ```
+    pub fn and_with(self, other: Expr) -> Expr {
+        Expr::And(Box::new(self), Box::new(other))
+    }
+
+    /// Joins this expression and `other` with `or`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, to_query_string};
+    ///
+    /// let lhs = parse_str("age gt 3 and age lt 9").expect("valid filter tree");
+    /// let rhs = parse_str("vip eq true").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     to_query_string(&lhs.or_with(rhs)).expect("valid filter"),
+    ///     "(age gt 3 and age lt 9) or vip eq true",
+    /// );
+    /// ```
+    pub fn or_with(self, other: Expr) -> Expr {
+        Expr::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Negates the whole expression with `not`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, Expr};
+    ///
+    /// let expr = parse_str("age gt 3").expect("valid filter tree");
+    ///
+    /// assert_eq!(expr.clone().negate(), Expr::Not(Box::new(expr)));
+    /// ```
+    pub fn negate(self) -> Expr {
+        Expr::Not(Box::new(self))
+    }
+}
@@ -0,0 +1,446 @@
+use super::simplify::order;
+use super::{Bound, CompareOperator, Constraint, Expr, Value, ValueSet};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+impl Expr {
+    /// Extracts the values each identifier may take whenever the expression
+    /// holds, after simplifying it.
+    ///
+    /// Comparisons and `in` lists between an identifier and constants give
+    /// equality sets, ranges and `null` checks, and `startswith` gives a
+    /// prefix. Constraints are intersected through `and`, and unioned through
+    /// `or`, where only identifiers constrained on both sides are kept. Other
+    /// terms, such as lambdas, constrain nothing, and neither does an
+    /// expression simplifying to `false`.
+    ///
+    /// This can be used to prune partitions, pick shards or check whether a
+    /// filter can use an index before running it.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, Bound, Constraint, Value, ValueSet};
+    ///
+    /// let expr = parse_str("(tenant eq 7 or tenant eq 9) and price ge 10 and startswith(sku, 'AB')")
+    ///     .expect("valid filter tree");
+    ///
+    /// let constraints = expr.constraints();
+    ///
+    /// assert!(constraints["tenant"].contains(&Value::Number(9.into())));
+    /// assert!(!constraints["tenant"].contains(&Value::Number(8.into())));
+    /// assert!(!constraints["tenant"].contains(&Value::Null));
+    ///
+    /// assert_eq!(
+    ///     constraints["price"],
+    ///     Constraint {
+    ///         nullable: false,
+    ///         values: Some(vec![ValueSet::Range {
+    ///             lower: Some(Bound {
+    ///                 value: Value::Number(10.into()),
+    ///                 inclusive: true,
+    ///             }),
+    ///             upper: None,
+    ///         }]),
+    ///     },
+    /// );
+    ///
+    /// assert!(constraints["sku"].contains(&Value::String("ABC".to_string())));
+    /// ```
+    pub fn constraints(&self) -> BTreeMap<String, Constraint> {
+        constraints(&self.clone().simplify())
+    }
+}
+
+impl Constraint {
+    /// Whether the identifier can take no value at all, in which case the
+    /// expression never holds.
+    pub fn is_empty(&self) -> bool {
+        !self.nullable && self.values.as_ref().is_some_and(Vec::is_empty)
+    }
+
+    /// Whether the identifier may take the given value.
+    pub fn contains(&self, value: &Value) -> bool {
+        match (value, &self.values) {
+            (Value::Null, _) => self.nullable,
+            (_, None) => true,
+            (value, Some(sets)) => sets.iter().any(|set| set.contains(value)),
+        }
+    }
+
+    fn intersect(self, other: Self) -> Self {
+        let values = match (self.values, other.values) {
+            (None, values) | (values, None) => values,
+            (Some(lhs), Some(rhs)) => Some(normalize(
+                lhs.iter()
+                    .flat_map(|lhs| {
+                        rhs.iter()
+                            .filter_map(|rhs| intersect(lhs.clone(), rhs.clone()))
+                    })
+                    .collect(),
+            )),
+        };
+
+        Constraint {
+            nullable: self.nullable && other.nullable,
+            values,
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        let values = match (self.values, other.values) {
+            (Some(mut lhs), Some(rhs)) => {
+                lhs.extend(rhs);
+                Some(normalize(lhs))
+            }
+            _ => None,
+        };
+
+        Constraint {
+            nullable: self.nullable || other.nullable,
+            values,
+        }
+    }
+}
+
+impl ValueSet {
+    /// Whether the set may contain the given value. Values that cannot be
+    /// compared with the set, such as a number against a prefix, may be.
+    pub fn contains(&self, value: &Value) -> bool {
+        self.includes(value).unwrap_or(true)
+    }
+
+    /// Whether the set contains the value, or `None` if they cannot be
+    /// compared.
+    fn includes(&self, value: &Value) -> Option<bool> {
+        match self {
+            ValueSet::In(values) => Some(values.contains(value)),
+            ValueSet::Range { lower, upper } => Some(above(value, lower)? && below(value, upper)?),
+            ValueSet::Prefix(prefix) => match value {
+                Value::String(string) => Some(string.starts_with(prefix.as_str())),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn constraints(expr: &Expr) -> BTreeMap<String, Constraint> {
+    match expr {
+        Expr::And(lhs, rhs) => {
+            let mut constraints = constraints(lhs);
+
+            for (name, constraint) in self::constraints(rhs) {
+                let constraint = match constraints.remove(&name) {
+                    Some(existing) => existing.intersect(constraint),
+                    None => constraint,
+                };
+
+                constraints.insert(name, constraint);
+            }
+
+            constraints
+        }
+
+        Expr::Or(lhs, rhs) => {
+            let mut rhs = constraints(rhs);
+
+            constraints(lhs)
+                .into_iter()
+                .filter_map(|(name, constraint)| {
+                    let other = rhs.remove(&name)?;
+                    Some((name, constraint.union(other)))
+                })
+                .collect()
+        }
+
+        expr => term(expr).into_iter().collect(),
+    }
+}
+
+/// The constraint a single term puts on an identifier.
+fn term(expr: &Expr) -> Option<(String, Constraint)> {
+    match expr {
+        Expr::Compare(lhs, op, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+            (Expr::Identifier(name), Expr::Value(value)) => {
+                Some((name.clone(), compare(op.clone(), value)?))
+            }
+            _ => None,
+        },
+
+        // A negated ordering also holds for `null`.
+        Expr::Not(expr) => match expr.as_ref() {
+            Expr::Compare(lhs, op, rhs) => match (lhs.as_ref(), negate(op)?, rhs.as_ref()) {
+                (Expr::Identifier(name), op, Expr::Value(value)) if *value != Value::Null => {
+                    let constraint = Constraint {
+                        nullable: true,
+                        ..compare(op, value)?
+                    };
+
+                    Some((name.clone(), constraint))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+
+        Expr::In(lhs, values) => {
+            let Expr::Identifier(name) = lhs.as_ref() else {
+                return None;
+            };
+
+            let mut values = values
+                .iter()
+                .map(|value| match value {
+                    Expr::Value(value) => Some(value.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let nullable = values.contains(&Value::Null);
+            values.retain(|value| *value != Value::Null);
+
+            let constraint = Constraint {
+                nullable,
+                values: Some(normalize(vec![ValueSet::In(values)])),
+            };
+
+            Some((name.clone(), constraint))
+        }
+
+        Expr::Function(function, args) if function == "startswith" => match args.as_slice() {
+            [Expr::Identifier(name), Expr::Value(Value::String(prefix))] => {
+                let constraint = Constraint {
+                    nullable: false,
+                    values: Some(vec![ValueSet::Prefix(prefix.clone())]),
+                };
+
+                Some((name.clone(), constraint))
+            }
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+/// The constraint of comparing an identifier with a constant.
+fn compare(op: CompareOperator, value: &Value) -> Option<Constraint> {
+    let bound = |inclusive| {
+        Some(Bound {
+            value: value.clone(),
+            inclusive,
+        })
+    };
+
+    let (nullable, values) = match (op.clone(), value) {
+        (CompareOperator::Equal, Value::Null) => (true, Some(vec![])),
+        (CompareOperator::NotEqual, Value::Null) => (false, None),
+
+        // Ordering against `null` is left to the data source.
+        (_, Value::Null) | (CompareOperator::Has, _) => return None,
+
+        (CompareOperator::Equal, value) => (false, Some(vec![ValueSet::In(vec![value.clone()])])),
+        (CompareOperator::NotEqual, _) => (
+            true,
+            Some(vec![
+                ValueSet::Range {
+                    lower: None,
+                    upper: bound(false),
+                },
+                ValueSet::Range {
+                    lower: bound(false),
+                    upper: None,
+                },
+            ]),
+        ),
+
+        (CompareOperator::GreaterThan | CompareOperator::GreaterOrEqual, _) => (
+            false,
+            Some(vec![ValueSet::Range {
+                lower: bound(op == CompareOperator::GreaterOrEqual),
+                upper: None,
+            }]),
+        ),
+        (CompareOperator::LessThan | CompareOperator::LessOrEqual, _) => (
+            false,
+            Some(vec![ValueSet::Range {
+                lower: None,
+                upper: bound(op == CompareOperator::LessOrEqual),
+            }]),
+        ),
+    };
+
+    Some(Constraint { nullable, values })
+}
+
+/// The ordering operator holding exactly when the given one does not, for
+/// non-null operands.
+fn negate(op: &CompareOperator) -> Option<CompareOperator> {
+    match op {
+        CompareOperator::GreaterThan => Some(CompareOperator::LessOrEqual),
+        CompareOperator::GreaterOrEqual => Some(CompareOperator::LessThan),
+        CompareOperator::LessThan => Some(CompareOperator::GreaterOrEqual),
+        CompareOperator::LessOrEqual => Some(CompareOperator::GreaterThan),
+        _ => None,
+    }
+}
+
+/// Whether a value is within a lower bound, or `None` if they cannot be
+/// compared.
+fn above(value: &Value, bound: &Option<Bound>) -> Option<bool> {
+    let Some(bound) = bound else {
+        return Some(true);
+    };
+
+    Some(match order(value, &bound.value)? {
+        Ordering::Equal => bound.inclusive,
+        ordering => ordering.is_gt(),
+    })
+}
+
+/// Whether a value is within an upper bound, or `None` if they cannot be
+/// compared.
+fn below(value: &Value, bound: &Option<Bound>) -> Option<bool> {
+    let Some(bound) = bound else {
+        return Some(true);
+    };
+
+    Some(match order(value, &bound.value)? {
+        Ordering::Equal => bound.inclusive,
+        ordering => ordering.is_lt(),
+    })
+}
+
+/// Picks the bound preferring the one ordering as `prefer` against the other,
+/// or the first one if they cannot be compared.
+fn tighter(lhs: Option<Bound>, rhs: Option<Bound>, prefer: Ordering) -> Option<Bound> {
+    let (lhs, rhs) = match (lhs, rhs) {
+        (None, bound) | (bound, None) => return bound,
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+    };
+
+    Some(match order(&rhs.value, &lhs.value) {
+        Some(Ordering::Equal) => Bound {
+            inclusive: lhs.inclusive && rhs.inclusive,
+            ..lhs
+        },
+        Some(ordering) if ordering == prefer => rhs,
+        _ => lhs,
+    })
+}
+
+/// A range between two bounds, or `None` if it is empty.
+fn range(lower: Option<Bound>, upper: Option<Bound>) -> Option<ValueSet> {
+    if let (Some(low), Some(high)) = (&lower, &upper) {
+        match order(&low.value, &high.value) {
+            Some(Ordering::Greater) => return None,
+            Some(Ordering::Equal) if low.inclusive && high.inclusive => {
+                return Some(ValueSet::In(vec![low.value.clone()]));
+            }
+            Some(Ordering::Equal) => return None,
+            _ => {}
+        }
+    }
+
+    Some(ValueSet::Range { lower, upper })
+}
+
+/// The range of strings starting with a prefix.
+fn prefix_range(prefix: &str) -> (Option<Bound>, Option<Bound>) {
+    let lower = Bound {
+        value: Value::String(prefix.to_owned()),
+        inclusive: true,
+    };
+
+    // The first string after all those starting with the prefix increments
+    // its last character that can be.
+    let mut chars: Vec<char> = prefix.chars().collect();
+
+    while let Some(last) = chars.pop() {
+        if let Some(next) = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32) {
+            chars.push(next);
+
+            let upper = Bound {
+                value: Value::String(chars.into_iter().collect()),
+                inclusive: false,
+            };
+
+            return (Some(lower), Some(upper));
+        }
+    }
+
+    (Some(lower), None)
+}
+
+/// The values within both sets, or `None` if there are none. Sets that
+/// cannot be represented exactly are kept larger than needed.
+fn intersect(lhs: ValueSet, rhs: ValueSet) -> Option<ValueSet> {
+    match (lhs, rhs) {
+        (ValueSet::In(values), other) | (other, ValueSet::In(values)) => {
+            let values: Vec<Value> = values
+                .into_iter()
+                .filter(|value| other.contains(value))
+                .collect();
+
+            (!values.is_empty()).then_some(ValueSet::In(values))
+        }
+
+        (
+            ValueSet::Range { lower, upper },
+            ValueSet::Range {
+                lower: other_lower,
+                upper: other_upper,
+            },
+        ) => range(
+            tighter(lower, other_lower, Ordering::Greater),
+            tighter(upper, other_upper, Ordering::Less),
+        ),
+
+        (ValueSet::Prefix(lhs), ValueSet::Prefix(rhs)) => {
+            if lhs.starts_with(rhs.as_str()) {
+                Some(ValueSet::Prefix(lhs))
+            } else if rhs.starts_with(lhs.as_str()) {
+                Some(ValueSet::Prefix(rhs))
+            } else {
+                None
+            }
+        }
+
+        (ValueSet::Prefix(prefix), ValueSet::Range { lower, upper })
+        | (ValueSet::Range { lower, upper }, ValueSet::Prefix(prefix)) => {
+            let (prefix_lower, prefix_upper) = prefix_range(&prefix);
+
+            range(
+                tighter(prefix_lower, lower, Ordering::Greater),
+                tighter(prefix_upper, upper, Ordering::Less),
+            )
+            .map(|_| ValueSet::Prefix(prefix))
+        }
+    }
+}
+
+/// Merges the `in` lists of a union into a single sorted one placed first,
+/// and removes repeated sets and values already within another set.
+fn normalize(sets: Vec<ValueSet>) -> Vec<ValueSet> {
+    let mut values = Vec::new();
+    let mut others: Vec<ValueSet> = Vec::new();
+
+    for set in sets {
+        match set {
+            ValueSet::In(list) => values.extend(list),
+            set if !others.contains(&set) => others.push(set),
+            _ => {}
+        }
+    }
+
+    values.sort();
+    values.dedup();
+    values.retain(|value| !others.iter().any(|set| set.includes(value) == Some(true)));
+
+    let mut sets = Vec::with_capacity(others.len() + 1);
+
+    if !values.is_empty() {
+        sets.push(ValueSet::In(values));
+    }
+
+    sets.extend(others);
+    sets
+}
@@ -0,0 +1,260 @@
+use super::{Expr, FormatOptions, KeywordCase, QuoteStyle, Value};
+use std::fmt::{self, Write};
+
+/// Converts an `Expr` AST to its OData `$filter` string representation,
+/// written as set in `options` with as few parentheses as possible.
+///
+/// ```
+/// use odata_params::filters::{parse_str, to_query_string, to_query_string_with, FormatOptions};
+///
+/// let expr = parse_str("aa eq 1 or (bb eq 2 and not (cc eq 3))").expect("valid filter tree");
+///
+/// assert_eq!(
+///     to_query_string(&expr).expect("valid filter"),
+///     "aa eq 1 or (bb eq 2 and not cc eq 3)",
+/// );
+/// assert_eq!(
+///     to_query_string_with(&expr, &FormatOptions::default()).expect("valid filter"),
+///     "aa eq 1 or bb eq 2 and not (cc eq 3)",
+/// );
+///
+/// let expr = parse_str("aa eq 1 and (bb eq 2 or cc eq 3)").expect("valid filter tree");
+///
+/// assert_eq!(
+///     to_query_string_with(&expr, &FormatOptions::pretty()).expect("valid filter"),
+///     "aa eq 1\nand (\n  bb eq 2\n  or cc eq 3\n)",
+/// );
+/// ```
+pub fn to_query_string_with(expr: &Expr, options: &FormatOptions) -> Result<String, fmt::Error> {
+    let mut output = String::new();
+    write_query_string_with(&mut output, expr, options)?;
+    Ok(output)
+}
+
+/// Writes an `Expr` AST to a writer as its OData `$filter` string
+/// representation, written as set in `options`.
+pub fn write_query_string_with<W: Write>(
+    writer: &mut W,
+    expr: &Expr,
+    options: &FormatOptions,
+) -> fmt::Result {
+    match options.percent_encode {
+        true => Printer::new(&mut PercentEncoder(writer), options).expr(expr),
+        false => Printer::new(writer, options).expr(expr),
+    }
+}
+
+impl KeywordCase {
+    /// Writes a lowercase keyword in this case.
+    pub(crate) fn write<W: Write + ?Sized>(self, writer: &mut W, keyword: &str) -> fmt::Result {
+        match self {
+            KeywordCase::Lower => writer.write_str(keyword),
+            KeywordCase::Upper => writer.write_str(&keyword.to_ascii_uppercase()),
+        }
+    }
+}
+
+/// Percent-encodes everything written through it but the unreserved
+/// characters of RFC 3986.
+pub(crate) struct PercentEncoder<'a, W: Write>(pub(crate) &'a mut W);
+
+impl<W: Write> Write for PercentEncoder<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    self.0.write_char(byte as char)?
+                }
+                _ => write!(self.0, "%{byte:02X}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Printer<'a> {
+    writer: &'a mut dyn Write,
+    options: &'a FormatOptions,
+
+    /// How many groups the expression being written is nested in.
+    level: usize,
+
+    /// Whether the expression being written must stay on one line.
+    inline: bool,
+}
+
+impl<'a> Printer<'a> {
+    fn new(writer: &'a mut dyn Write, options: &'a FormatOptions) -> Self {
+        Self {
+            writer,
+            options,
+            level: 0,
+            inline: options.indent.is_none(),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> fmt::Result {
+        self.options.keyword_case.write(self.writer, keyword)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> fmt::Result {
+        match expr {
+            Expr::Or(lhs, rhs) | Expr::And(lhs, rhs) => {
+                let keyword = match expr {
+                    Expr::Or(..) => "or",
+                    _ => "and",
+                };
+
+                // The parser reads `and` and `or` as right-associative with
+                // the same precedence and lets `not` take the rest of the
+                // filter, so only an `or` on the right of an `and` needs
+                // grouping to also follow the precedence of the specification.
+                self.operand(
+                    lhs,
+                    matches!(**lhs, Expr::Or(..) | Expr::And(..) | Expr::Not(..)),
+                )?;
+                self.separator(keyword)?;
+                self.operand(rhs, keyword == "and" && matches!(**rhs, Expr::Or(..)))
+            }
+
+            Expr::Not(expr) => {
+                // `not` binds tighter than comparisons in the specification.
+                self.keyword("not")?;
+                self.writer.write_char(' ')?;
+                self.operand(expr, !is_primary(expr))
+            }
+
+            Expr::Compare(lhs, op, rhs) => {
+                self.operand(lhs, !is_primary(lhs))?;
+                self.writer.write_char(' ')?;
+                self.keyword(&op.to_string())?;
+                self.writer.write_char(' ')?;
+                self.operand(rhs, !is_primary(rhs))
+            }
+
+            Expr::In(lhs, values) => {
+                self.operand(lhs, !is_primary(lhs))?;
+                self.writer.write_char(' ')?;
+                self.keyword("in")?;
+                self.writer.write_str(" (")?;
+                self.list(values)?;
+                self.writer.write_char(')')
+            }
+
+            Expr::Function(name, args) => {
+                write!(self.writer, "{name}(")?;
+                self.list(args)?;
+                self.writer.write_char(')')
+            }
+
+            Expr::Lambda(collection, op, variable, body) => {
+                self.expr(collection)?;
+                self.writer.write_char('/')?;
+                self.keyword(&op.to_string())?;
+                write!(self.writer, "({variable}: ")?;
+                self.inline(body)?;
+                self.writer.write_char(')')
+            }
+
+            Expr::Identifier(name) | Expr::Alias(name) => self.writer.write_str(name),
+
+            Expr::Value(value) => self.value(value),
+        }
+    }
+
+    /// Writes an operand, grouped in parentheses if needed.
+    fn operand(&mut self, expr: &Expr, grouped: bool) -> fmt::Result {
+        if !grouped {
+            return self.expr(expr);
+        }
+
+        // Only groups of `and` and `or` operands are split across lines.
+        if self.inline || !matches!(expr, Expr::Or(..) | Expr::And(..)) {
+            self.writer.write_char('(')?;
+            self.expr(expr)?;
+            return self.writer.write_char(')');
+        }
+
+        self.writer.write_char('(')?;
+        self.level += 1;
+        self.newline()?;
+        self.expr(expr)?;
+        self.level -= 1;
+        self.newline()?;
+        self.writer.write_char(')')
+    }
+
+    fn separator(&mut self, keyword: &str) -> fmt::Result {
+        match self.inline {
+            true => self.writer.write_char(' ')?,
+            false => self.newline()?,
+        }
+
+        self.keyword(keyword)?;
+        self.writer.write_char(' ')
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        let indent = self.options.indent.unwrap_or(0) * self.level;
+        write!(self.writer, "\n{:indent$}", "")
+    }
+
+    fn inline(&mut self, expr: &Expr) -> fmt::Result {
+        let inline = std::mem::replace(&mut self.inline, true);
+        let result = self.expr(expr);
+        self.inline = inline;
+        result
+    }
+
+    fn list(&mut self, exprs: &[Expr]) -> fmt::Result {
+        for (i, expr) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.writer.write_str(", ")?;
+            }
+
+            self.inline(expr)?;
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self, value: &Value) -> fmt::Result {
+        let precision = self.options.datetime_precision;
+
+        match value {
+            Value::Null => self.writer.write_str("null"),
+            Value::Bool(b) => write!(self.writer, "{b}"),
+            Value::Number(n) => write!(self.writer, "{n}"),
+            Value::Uuid(id) => write!(self.writer, "{id}"),
+            Value::DateTime(dt) => match self.options.datetime_offset {
+                Some(offset) => self
+                    .writer
+                    .write_str(&dt.with_timezone(&offset).to_rfc3339_opts(precision, true)),
+                None => self.writer.write_str(&dt.to_rfc3339_opts(precision, true)),
+            },
+            Value::Date(d) => write!(self.writer, "{d}"),
+            Value::Time(t) => write!(self.writer, "{t}"),
+            Value::String(s) => match self.options.quote_style {
+                QuoteStyle::Doubled => write!(self.writer, "'{}'", s.replace('\'', "''")),
+                QuoteStyle::Backslash => write!(
+                    self.writer,
+                    "'{}'",
+                    s.replace('\\', r"\\").replace('\'', r"\'")
+                ),
+            },
+        }
+    }
+}
+
+/// Whether an expression can be an operand of any operator without grouping.
+fn is_primary(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Function(..)
+            | Expr::Lambda(..)
+            | Expr::Identifier(..)
+            | Expr::Alias(..)
+            | Expr::Value(..)
+    )
+}
@@ -0,0 +1,278 @@
+//! Generators of random expressions for property testing and fuzzing.
+//!
+//! Generated expressions are shaped like parsed filters, so `parse_str`
+//! reads them back from `to_query_string_with` when datetimes are written
+//! with `SecondsFormat::AutoSi` and strings with `QuoteStyle::Backslash`.
+//! Comparison and `in` operands are values, names, function calls and
+//! lambdas, and names never start like a keyword or a literal.
+
+use super::{CompareOperator, Expr, LambdaOperator, Value};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use uuid::Uuid;
+
+/// How deeply generated expressions are nested at most.
+const MAX_DEPTH: u32 = 6;
+
+/// The last second of 2099, bounding generated datetimes.
+const MAX_TIMESTAMP: i64 = 4_102_444_799;
+
+/// Whether the parser reads a name as something else, such as `notes` as
+/// `not es` or `nullable` as the `null` literal followed by `able`.
+fn is_reserved(name: &str) -> bool {
+    name.starts_with("not")
+        || ["true", "false", "null"].iter().any(|keyword| {
+            name.get(..keyword.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(keyword))
+        })
+}
+
+fn number(mantissa: u64, scale: i64) -> Value {
+    Value::Number(BigDecimal::new(mantissa.into(), scale))
+}
+
+fn datetime(seconds: i64, nanoseconds: u32) -> Value {
+    Value::DateTime(DateTime::<Utc>::from_timestamp(seconds, nanoseconds).expect("valid datetime"))
+}
+
+fn date(year: i32, ordinal: u32) -> Value {
+    Value::Date(NaiveDate::from_yo_opt(year, ordinal).expect("valid date"))
+}
+
+fn time(seconds: u32, nanoseconds: u32) -> Value {
+    Value::Time(
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanoseconds).expect("valid time"),
+    )
+}
+
+const COMPARE_OPERATORS: [CompareOperator; 7] = [
+    CompareOperator::Equal,
+    CompareOperator::NotEqual,
+    CompareOperator::GreaterThan,
+    CompareOperator::GreaterOrEqual,
+    CompareOperator::LessThan,
+    CompareOperator::LessOrEqual,
+    CompareOperator::Has,
+];
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn name() -> impl Strategy<Value = String> {
+        "[a-zA-Z_][a-zA-Z0-9_]{1,7}"
+            .prop_filter("names must not read as keywords", |name| !is_reserved(name))
+    }
+
+    fn path() -> impl Strategy<Value = String> {
+        prop::collection::vec(name(), 1..=3).prop_map(|segments| segments.join("/"))
+    }
+
+    /// Operands of comparisons, which are either leaves or function calls and
+    /// lambdas holding filters from `filter`.
+    fn operand(filter: BoxedStrategy<Expr>) -> BoxedStrategy<Expr> {
+        prop_oneof![
+            4 => path().prop_map(Expr::Identifier),
+            1 => name().prop_map(|name| Expr::Alias(format!("@{name}"))),
+            4 => any::<Value>().prop_map(Expr::Value),
+            2 => (name(), prop::collection::vec(filter.clone(), 0..=3))
+                .prop_map(|(name, args)| Expr::Function(name, args)),
+            1 => (path(), any::<bool>(), name(), filter).prop_map(|(path, any, variable, body)| {
+                let op = if any { LambdaOperator::Any } else { LambdaOperator::All };
+                Expr::Lambda(Box::new(Expr::Identifier(path)), op, variable, Box::new(body))
+            }),
+        ]
+        .boxed()
+    }
+
+    impl Arbitrary for CompareOperator {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            prop::sample::select(COMPARE_OPERATORS.to_vec()).boxed()
+        }
+    }
+
+    impl Arbitrary for Value {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            prop_oneof![
+                Just(Value::Null),
+                any::<bool>().prop_map(Value::Bool),
+                (any::<u64>(), 0..=6i64).prop_map(|(mantissa, scale)| number(mantissa, scale)),
+                any::<u128>().prop_map(|id| Value::Uuid(Uuid::from_u128(id))),
+                (0..=MAX_TIMESTAMP, 0..1_000_000_000u32).prop_map(|(s, ns)| datetime(s, ns)),
+                (1000..=9999i32, 1..=365u32).prop_map(|(year, ordinal)| date(year, ordinal)),
+                (0..86_400u32, 0..1_000_000_000u32).prop_map(|(s, ns)| time(s, ns)),
+                any::<String>().prop_map(Value::String),
+            ]
+            .boxed()
+        }
+    }
+
+    impl Arbitrary for Expr {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            let leaf = prop_oneof![
+                path().prop_map(Expr::Identifier),
+                name().prop_map(|name| Expr::Alias(format!("@{name}"))),
+                any::<Value>().prop_map(Expr::Value),
+            ];
+
+            leaf.prop_recursive(MAX_DEPTH, 64, 4, |filter| {
+                let operand = operand(filter.clone());
+
+                prop_oneof![
+                    2 => operand.clone(),
+                    1 => (filter.clone(), filter.clone())
+                        .prop_map(|(lhs, rhs)| Expr::Or(Box::new(lhs), Box::new(rhs))),
+                    1 => (filter.clone(), filter.clone())
+                        .prop_map(|(lhs, rhs)| Expr::And(Box::new(lhs), Box::new(rhs))),
+                    1 => filter.prop_map(|expr| Expr::Not(Box::new(expr))),
+                    3 => (operand.clone(), any::<CompareOperator>(), operand.clone())
+                        .prop_map(|(lhs, op, rhs)| Expr::Compare(Box::new(lhs), op, Box::new(rhs))),
+                    1 => (operand.clone(), prop::collection::vec(operand, 0..=4))
+                        .prop_map(|(lhs, values)| Expr::In(Box::new(lhs), values)),
+                ]
+            })
+            .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod unstructured {
+    use super::*;
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+    const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_0123456789";
+
+    fn name(u: &mut Unstructured) -> Result<String> {
+        let length = u.int_in_range(2..=8)?;
+        let mut name = String::with_capacity(length + 1);
+
+        name.push(*u.choose(FIRST)? as char);
+        for _ in 1..length {
+            name.push(*u.choose(REST)? as char);
+        }
+
+        if is_reserved(&name) {
+            name.insert(0, '_');
+        }
+
+        Ok(name)
+    }
+
+    fn path(u: &mut Unstructured) -> Result<String> {
+        let segments = u.int_in_range(1..=3)?;
+        let names = (0..segments).map(|_| name(u)).collect::<Result<Vec<_>>>()?;
+
+        Ok(names.join("/"))
+    }
+
+    fn list(
+        u: &mut Unstructured,
+        max: usize,
+        item: fn(&mut Unstructured, u32) -> Result<Expr>,
+        depth: u32,
+    ) -> Result<Vec<Expr>> {
+        let length = u.int_in_range(0..=max)?;
+        (0..length).map(|_| item(u, depth)).collect()
+    }
+
+    fn leaf(u: &mut Unstructured) -> Result<Expr> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Expr::Identifier(path(u)?),
+            1 => Expr::Alias(format!("@{}", name(u)?)),
+            _ => Expr::Value(Value::arbitrary(u)?),
+        })
+    }
+
+    fn operand(u: &mut Unstructured, depth: u32) -> Result<Expr> {
+        if depth >= MAX_DEPTH {
+            return leaf(u);
+        }
+
+        Ok(match u.int_in_range(0..=5)? {
+            0 => Expr::Function(name(u)?, list(u, 3, filter, depth + 1)?),
+            1 => {
+                let op = match bool::arbitrary(u)? {
+                    true => LambdaOperator::Any,
+                    false => LambdaOperator::All,
+                };
+
+                Expr::Lambda(
+                    Box::new(Expr::Identifier(path(u)?)),
+                    op,
+                    name(u)?,
+                    Box::new(filter(u, depth + 1)?),
+                )
+            }
+            _ => leaf(u)?,
+        })
+    }
+
+    fn filter(u: &mut Unstructured, depth: u32) -> Result<Expr> {
+        if depth >= MAX_DEPTH {
+            return leaf(u);
+        }
+
+        let boxed = |u: &mut Unstructured| filter(u, depth + 1).map(Box::new);
+
+        Ok(match u.int_in_range(0..=7)? {
+            0 => Expr::Or(boxed(u)?, boxed(u)?),
+            1 => Expr::And(boxed(u)?, boxed(u)?),
+            2 => Expr::Not(boxed(u)?),
+            3 | 4 => Expr::Compare(
+                Box::new(operand(u, depth + 1)?),
+                CompareOperator::arbitrary(u)?,
+                Box::new(operand(u, depth + 1)?),
+            ),
+            5 => Expr::In(
+                Box::new(operand(u, depth + 1)?),
+                list(u, 4, operand, depth + 1)?,
+            ),
+            _ => operand(u, depth)?,
+        })
+    }
+
+    impl<'a> Arbitrary<'a> for CompareOperator {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            u.choose(&COMPARE_OPERATORS).cloned()
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Value {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(match u.int_in_range(0..=7)? {
+                0 => Value::Null,
+                1 => Value::Bool(bool::arbitrary(u)?),
+                2 => number(u64::arbitrary(u)?, u.int_in_range(0..=6)?),
+                3 => Value::Uuid(Uuid::from_u128(u128::arbitrary(u)?)),
+                4 => datetime(
+                    u.int_in_range(0..=MAX_TIMESTAMP)?,
+                    u.int_in_range(0..=999_999_999)?,
+                ),
+                5 => date(u.int_in_range(1000..=9999)?, u.int_in_range(1..=365)?),
+                6 => time(
+                    u.int_in_range(0..=86_399)?,
+                    u.int_in_range(0..=999_999_999)?,
+                ),
+                _ => Value::String(String::arbitrary(u)?),
+            })
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for Expr {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            filter(u, 0)
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use super::simplify::{flatten, order, satisfies, Junction};
+use super::{CompareOperator, Expr, Implication, Value};
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::TimeDelta;
+use std::mem::discriminant;
+use uuid::Uuid;
+
+/// Most clauses the normal forms of either expression may have before the
+/// analysis gives up with `Implication::Unknown`.
+const MAX_CLAUSES: usize = 64;
+
+const TRUE: Expr = Expr::Value(Value::Bool(true));
+const FALSE: Expr = Expr::Value(Value::Bool(false));
+
+impl Expr {
+    /// Checks whether every row matching this expression also matches `other`.
+    ///
+    /// The check is conservative: `Implication::Yes` and `Implication::No`
+    /// are only returned when proven, and `Implication::Unknown` otherwise.
+    /// It understands `and`, `or` and `not` over comparisons and `in` lists
+    /// between an identifier and constants, covering ranges of numbers, dates,
+    /// times, strings and other ordered values. Any other term, such as a
+    /// function call or a lambda, only matches an equivalent term once
+    /// canonicalized. Comparing
+    /// with `null` follows the OData semantics, where `a lt 5` does not hold
+    /// when `a` is `null`. As the type of identifiers is not known, numbers
+    /// may be integers or decimals, so ranges only differing by fractional
+    /// values, such as `n gt 1 and n lt 2` against `n eq 5`, are `Unknown`.
+    ///
+    /// This can prove that a requested filter stays within the filter a user
+    /// is allowed to see.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, Implication};
+    ///
+    /// let allowed = parse_str("region in ('EU', 'US') and year ge 2020").expect("valid filter tree");
+    ///
+    /// let requested = parse_str("region eq 'EU' and year gt 2022").expect("valid filter tree");
+    /// assert_eq!(requested.implies(&allowed), Implication::Yes);
+    ///
+    /// let requested = parse_str("region eq 'EU'").expect("valid filter tree");
+    /// assert_eq!(requested.implies(&allowed), Implication::No);
+    ///
+    /// let requested = parse_str("contains(name, 'a')").expect("valid filter tree");
+    /// assert_eq!(requested.implies(&allowed), Implication::Unknown);
+    /// ```
+    pub fn implies(&self, other: &Expr) -> Implication {
+        // The premise holds when any of its `and` clauses does, and the
+        // conclusion when all of its `or` clauses do. Both are canonicalized
+        // first so that other terms match regardless of how they are written.
+        let (Ok(premise), Ok(conclusion)) = (
+            self.clone().canonicalize().to_dnf(MAX_CLAUSES),
+            other.clone().canonicalize().to_cnf(MAX_CLAUSES),
+        ) else {
+            return Implication::Unknown;
+        };
+
+        let mut disjuncts = Vec::new();
+        flatten(Junction::Or, premise, &mut disjuncts);
+
+        let mut clauses = Vec::new();
+        flatten(Junction::And, conclusion, &mut clauses);
+
+        let mut implication = Implication::Yes;
+
+        for disjunct in disjuncts {
+            let mut premise = Vec::new();
+            flatten(Junction::And, disjunct, &mut premise);
+
+            for clause in &clauses {
+                let mut conclusion = Vec::new();
+                flatten(Junction::Or, clause.clone(), &mut conclusion);
+
+                match entails(&premise, &conclusion) {
+                    Implication::Yes => {}
+                    Implication::No => return Implication::No,
+                    Implication::Unknown => implication = Implication::Unknown,
+                }
+            }
+        }
+
+        implication
+    }
+
+    /// Checks whether every row matching `other` also matches this
+    /// expression, so that results cached for this expression can be
+    /// filtered down to answer `other`.
+    ///
+    /// This is `other.implies(self)`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, Implication};
+    ///
+    /// let cached = parse_str("price lt 100").expect("valid filter tree");
+    /// let requested = parse_str("price lt 50 and stock gt 0").expect("valid filter tree");
+    ///
+    /// assert_eq!(cached.subsumes(&requested), Implication::Yes);
+    /// assert_eq!(requested.subsumes(&cached), Implication::No);
+    /// ```
+    pub fn subsumes(&self, other: &Expr) -> Implication {
+        other.implies(self)
+    }
+}
+
+/// Checks whether terms joined by `and` imply terms joined by `or`.
+///
+/// The understood terms constrain each identifier independently, so the
+/// implication fails exactly when every identifier can take a value making
+/// the premise hold and the conclusion fail. Such values are searched among
+/// the constants, `null`, and a value within each gap between constants.
+/// Numbers are searched as integers where possible, and the implication is
+/// left undecided when only a fractional number disproves it.
+fn entails(premise: &[Expr], conclusion: &[Expr]) -> Implication {
+    if premise.contains(&FALSE)
+        || conclusion.contains(&TRUE)
+        || premise.iter().any(|term| conclusion.contains(term))
+    {
+        return Implication::Yes;
+    }
+
+    let premise: Vec<Option<Atom>> = premise
+        .iter()
+        .filter(|term| **term != TRUE)
+        .map(Atom::parse)
+        .collect();
+    let conclusion: Vec<Option<Atom>> = conclusion
+        .iter()
+        .filter(|term| **term != FALSE)
+        .map(Atom::parse)
+        .collect();
+
+    let mut undecided = premise.iter().chain(&conclusion).any(Option::is_none);
+
+    let premise: Vec<Atom> = premise.into_iter().flatten().collect();
+    let conclusion: Vec<Atom> = conclusion.into_iter().flatten().collect();
+
+    let mut names: Vec<&str> = premise
+        .iter()
+        .chain(&conclusion)
+        .map(|atom| atom.name)
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    for name in names {
+        let premise: Vec<&Atom> = premise.iter().filter(|atom| atom.name == name).collect();
+        let conclusion: Vec<&Atom> = conclusion.iter().filter(|atom| atom.name == name).collect();
+
+        let Some(candidates) = candidates(premise.iter().chain(&conclusion)) else {
+            undecided = true;
+            continue;
+        };
+
+        let counterexamples: Vec<&Value> = candidates
+            .iter()
+            .filter(|value| {
+                premise.iter().all(|atom| atom.holds(value))
+                    && !conclusion.iter().any(|atom| atom.holds(value))
+            })
+            .collect();
+
+        if counterexamples.is_empty() {
+            return Implication::Yes;
+        }
+
+        // The type of the identifier is not known, and fractional numbers
+        // are only searched in gaps without integers, such as between `1`
+        // and `2`, so they only disprove the implication for decimal fields.
+        if counterexamples.iter().all(|value| is_fractional(value)) {
+            undecided = true;
+        }
+    }
+
+    match undecided {
+        true => Implication::Unknown,
+        false => Implication::No,
+    }
+}
+
+/// A term understood by the analysis, comparing an identifier with constants.
+struct Atom<'a> {
+    name: &'a str,
+    test: Test<'a>,
+    negated: bool,
+}
+
+enum Test<'a> {
+    Compare(&'a CompareOperator, &'a Value),
+    In(Vec<&'a Value>),
+}
+
+impl<'a> Atom<'a> {
+    fn parse(expr: &'a Expr) -> Option<Self> {
+        match expr {
+            Expr::Not(expr) => Self::parse(expr).map(|atom| Atom {
+                negated: !atom.negated,
+                ..atom
+            }),
+
+            Expr::Compare(lhs, op, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+                // Ordering against `null` is left to the data source.
+                (Expr::Identifier(name), Expr::Value(value))
+                    if *op != CompareOperator::Has
+                        && (*value != Value::Null
+                            || matches!(
+                                op,
+                                CompareOperator::Equal | CompareOperator::NotEqual
+                            )) =>
+                {
+                    Some(Atom {
+                        name,
+                        test: Test::Compare(op, value),
+                        negated: false,
+                    })
+                }
+                _ => None,
+            },
+
+            Expr::In(lhs, values) => {
+                let Expr::Identifier(name) = lhs.as_ref() else {
+                    return None;
+                };
+
+                let values = values
+                    .iter()
+                    .map(|value| match value {
+                        Expr::Value(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()?;
+
+                Some(Atom {
+                    name,
+                    test: Test::In(values),
+                    negated: false,
+                })
+            }
+
+            _ => None,
+        }
+    }
+
+    fn constants(&self) -> Vec<&'a Value> {
+        match &self.test {
+            Test::Compare(_, value) => vec![value],
+            Test::In(values) => values.clone(),
+        }
+    }
+
+    /// Whether the term holds when the identifier has the given value.
+    fn holds(&self, value: &Value) -> bool {
+        let holds = match &self.test {
+            Test::Compare(CompareOperator::Equal, constant) => value == *constant,
+            Test::Compare(CompareOperator::NotEqual, constant) => value != *constant,
+            Test::Compare(op, constant) => {
+                order(value, constant).is_some_and(|ordering| satisfies(ordering, op))
+            }
+            Test::In(values) => values.contains(&value),
+        };
+
+        holds != self.negated
+    }
+}
+
+/// The values telling apart all outcomes of the terms on one identifier, or
+/// `None` if they compare with constants of different types.
+fn candidates<'a>(atoms: impl Iterator<Item = &'a &'a Atom<'a>>) -> Option<Vec<Value>> {
+    let mut constants: Vec<Value> = atoms
+        .flat_map(|atom| atom.constants())
+        .filter(|value| **value != Value::Null)
+        .cloned()
+        .collect();
+
+    if constants
+        .windows(2)
+        .any(|pair| discriminant(&pair[0]) != discriminant(&pair[1]))
+    {
+        return None;
+    }
+
+    constants.sort();
+    constants.dedup();
+
+    let mut candidates = vec![Value::Null];
+
+    let (Some(first), Some(last)) = (constants.first(), constants.last()) else {
+        // Only `null` is compared with, so any other value stands for the rest.
+        candidates.push(Value::Bool(true));
+        return Some(candidates);
+    };
+
+    candidates.extend(before(first));
+    candidates.extend(after(last));
+
+    for pair in constants.windows(2) {
+        candidates.extend(between(&pair[0], &pair[1]));
+    }
+
+    candidates.extend(constants);
+
+    Some(candidates)
+}
+
+/// Whether the value is a number with a fractional part, which an integer
+/// field cannot take.
+fn is_fractional(value: &Value) -> bool {
+    matches!(value, Value::Number(number) if !number.is_integer())
+}
+
+/// A value ordering before the given one, if any.
+fn before(value: &Value) -> Option<Value> {
+    Some(match value {
+        Value::Bool(true) => Value::Bool(false),
+        Value::Number(number) => {
+            Value::Number(number.with_scale_round(0, RoundingMode::Ceiling) - 1)
+        }
+        Value::Uuid(uuid) => Value::Uuid(Uuid::from_u128(uuid.as_u128().checked_sub(1)?)),
+        Value::DateTime(datetime) => {
+            Value::DateTime(datetime.checked_sub_signed(TimeDelta::nanoseconds(1))?)
+        }
+        Value::Date(date) => Value::Date(date.pred_opt()?),
+        Value::Time(time) => match time.overflowing_sub_signed(TimeDelta::nanoseconds(1)) {
+            (time, 0) => Value::Time(time),
+            _ => return None,
+        },
+        Value::String(string) if !string.is_empty() => Value::String(String::new()),
+        _ => return None,
+    })
+}
+
+/// A value ordering after the given one, if any.
+fn after(value: &Value) -> Option<Value> {
+    Some(match value {
+        Value::Bool(false) => Value::Bool(true),
+        Value::Number(number) => Value::Number(number.with_scale_round(0, RoundingMode::Floor) + 1),
+        Value::Uuid(uuid) => Value::Uuid(Uuid::from_u128(uuid.as_u128().checked_add(1)?)),
+        Value::DateTime(datetime) => {
+            Value::DateTime(datetime.checked_add_signed(TimeDelta::nanoseconds(1))?)
+        }
+        Value::Date(date) => Value::Date(date.succ_opt()?),
+        Value::Time(time) => match time.overflowing_add_signed(TimeDelta::nanoseconds(1)) {
+            (time, 0) => Value::Time(time),
+            _ => return None,
+        },
+        // Appending the smallest character gives the next string.
+        Value::String(string) => Value::String(format!("{string}\0")),
+        _ => return None,
+    })
+}
+
+/// A value ordering strictly between two constants of the same type, if any.
+fn between(low: &Value, high: &Value) -> Option<Value> {
+    let value = match (low, high) {
+        // An integer fits fields of any numeric type, so the midpoint is
+        // only used when the gap has none.
+        (Value::Number(low), Value::Number(high)) => {
+            match low.with_scale_round(0, RoundingMode::Floor) + 1 {
+                integer if &integer < high => Value::Number(integer),
+                _ => Value::Number((low + high) / BigDecimal::from(2)),
+            }
+        }
+        (Value::Uuid(low), Value::Uuid(high)) => Value::Uuid(Uuid::from_u128(
+            low.as_u128() + (high.as_u128() - low.as_u128()) / 2,
+        )),
+        (Value::DateTime(low), Value::DateTime(high)) => Value::DateTime(*low + (*high - *low) / 2),
+        (Value::Time(low), Value::Time(high)) => Value::Time(*low + (*high - *low) / 2),
+        (Value::Date(..), _) | (Value::String(..), _) => after(low)?,
+        _ => return None,
+    };
+
+    (low < &value && &value < high).then_some(value)
+}
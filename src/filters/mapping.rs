@@ -0,0 +1,370 @@
+use super::visit::root;
+use super::{Expr, Field, FieldMap, Fold, LambdaOperator, MappingError, Visitor};
+use crate::orderby::OrderBy;
+use std::collections::HashSet;
+
+impl FieldMap {
+    /// Creates an empty map, rejecting every identifier.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps a public field to an internal identifier or path.
+    pub fn with_renamed(mut self, public: impl Into<String>, internal: impl Into<String>) -> Self {
+        self.fields
+            .insert(public.into(), Field::Renamed(internal.into()));
+        self
+    }
+
+    /// Maps a public field to an expression computing it.
+    pub fn with_computed(mut self, public: impl Into<String>, expr: Expr) -> Self {
+        self.fields.insert(public.into(), Field::Computed(expr));
+        self
+    }
+
+    /// Marks a public field as known but not usable.
+    pub fn with_hidden(mut self, public: impl Into<String>) -> Self {
+        self.fields.insert(public.into(), Field::Hidden);
+        self
+    }
+
+    /// Maps the identifiers of a `$filter` expression.
+    ///
+    /// A `Result` which is the mapped expression, or an `Err` with every
+    /// distinct `MappingError` found, in the order they appear.
+    pub fn map_filter(&self, expr: Expr) -> Result<Expr, Vec<MappingError>> {
+        let mut mapper = Mapper {
+            fields: self,
+            scope: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let expr = mapper.fold_expr(expr);
+
+        if mapper.errors.is_empty() {
+            Ok(expr)
+        } else {
+            Err(mapper.errors)
+        }
+    }
+
+    /// Maps the identifiers of `$orderby` items, the same way as
+    /// `FieldMap::map_filter`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, FieldMap};
+    /// use odata_params::orderby;
+    ///
+    /// let fields = FieldMap::new()
+    ///     .with_renamed("createdAt", "created_at")
+    ///     .with_computed("fullName", parse_str("concat(first, last)").expect("valid filter tree"));
+    ///
+    /// let items = orderby::parse_str("createdAt desc, fullName").expect("valid order by");
+    /// let items = fields.map_order_by(items).expect("mapped order by");
+    ///
+    /// assert_eq!(
+    ///     orderby::to_query_string(&items).expect("valid order by"),
+    ///     "created_at desc, concat(first, last)",
+    /// );
+    /// ```
+    pub fn map_order_by(&self, items: Vec<OrderBy>) -> Result<Vec<OrderBy>, Vec<MappingError>> {
+        let mut mapper = Mapper {
+            fields: self,
+            scope: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let items = items
+            .into_iter()
+            .map(|item| OrderBy {
+                expr: mapper.fold_expr(item.expr),
+                ..item
+            })
+            .collect();
+
+        if mapper.errors.is_empty() {
+            Ok(items)
+        } else {
+            Err(mapper.errors)
+        }
+    }
+
+    /// Looks a path up as a whole, then through its longest listed prefix.
+    fn resolve(&self, path: &str) -> Result<Expr, MappingError> {
+        let mut prefix = path;
+
+        loop {
+            let field = self.fields.get(prefix);
+            let rest = &path[prefix.len()..];
+
+            match field {
+                Some(Field::Renamed(name)) => return Ok(Expr::Identifier(format!("{name}{rest}"))),
+                Some(Field::Computed(expr)) if rest.is_empty() => return Ok(expr.clone()),
+                Some(Field::Hidden) => {
+                    return Err(MappingError::HiddenField {
+                        name: prefix.to_owned(),
+                    })
+                }
+                _ => {}
+            }
+
+            match prefix.rsplit_once('/') {
+                Some((shorter, _)) => prefix = shorter,
+                None => {
+                    return Err(MappingError::UnknownField {
+                        name: path.to_owned(),
+                    })
+                }
+            }
+        }
+    }
+}
+
+struct Mapper<'a> {
+    fields: &'a FieldMap,
+
+    /// Lambda variables in scope.
+    scope: Vec<Variable>,
+
+    errors: Vec<MappingError>,
+}
+
+/// A lambda variable in scope.
+struct Variable {
+    name: String,
+
+    /// The public path of the collection the variable ranges over, along
+    /// with the internal path it maps to, if known.
+    collection: Option<(String, Option<String>)>,
+
+    /// Whether an identifier a field maps to is named like the variable, and
+    /// would be captured.
+    captured: bool,
+}
+
+impl Mapper<'_> {
+    /// Maps the body of a lambda, or returns it in an `Err` when a field maps
+    /// to an identifier the variable would capture.
+    fn fold_body(
+        &mut self,
+        variable: &str,
+        collection: Option<(String, Option<String>)>,
+        body: Expr,
+    ) -> Result<Expr, Expr> {
+        self.scope.push(Variable {
+            name: variable.to_owned(),
+            collection,
+            captured: false,
+        });
+        let body = self.fold_expr(body);
+        let variable = self.scope.pop().expect("lambda variable in scope");
+
+        match variable.captured {
+            true => Err(body),
+            false => Ok(body),
+        }
+    }
+
+    /// The public path an identifier stands for, looking through lambda
+    /// variables to the collection they range over.
+    fn public_path(&self, name: &str) -> Option<String> {
+        match self.variable(name) {
+            Some(variable) => variable
+                .collection
+                .as_ref()
+                .map(|(public, _)| format!("{public}{}", &name[variable.name.len()..])),
+            None => Some(name.to_owned()),
+        }
+    }
+
+    /// The innermost lambda variable a path starts from.
+    fn variable(&self, name: &str) -> Option<&Variable> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|variable| variable.name == root(name))
+    }
+
+    fn report(&mut self, error: MappingError) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+}
+
+impl Fold for Mapper<'_> {
+    fn fold_lambda(
+        &mut self,
+        collection: Expr,
+        op: LambdaOperator,
+        variable: String,
+        body: Expr,
+    ) -> Expr {
+        // Paths from the variable are looked up under the public path of the
+        // collection, and must map under the identifier it maps to.
+        let public = match &collection {
+            Expr::Identifier(name) => self.public_path(name),
+            _ => None,
+        };
+        let collection = self.fold_expr(collection);
+
+        let collection_paths = public.map(|public| {
+            let internal = match self.fields.resolve(&public) {
+                Ok(Expr::Identifier(internal)) => Some(internal),
+                Ok(_) => {
+                    self.report(MappingError::NotACollection {
+                        name: public.clone(),
+                    });
+                    None
+                }
+                Err(_) => None,
+            };
+
+            (public, internal)
+        });
+
+        let (variable, body) =
+            match self.fold_body(&variable, collection_paths.clone(), body.clone()) {
+                Ok(mapped) => (variable, mapped),
+
+                // The variable is renamed to a name neither the body nor what it
+                // maps to uses, then the body is mapped again.
+                Err(mapped) => {
+                    let mut names = Names::default();
+                    names.visit_expr(&body);
+                    names.visit_expr(&mapped);
+
+                    let renamed = (1..)
+                        .map(|n| format!("{variable}{n}"))
+                        .find(|name| !names.0.contains(name.as_str()))
+                        .expect("unused variable name");
+                    let body = RenameVariable {
+                        from: &variable,
+                        to: &renamed,
+                    }
+                    .fold_expr(body);
+                    let mapped = self
+                        .fold_body(&renamed, collection_paths, body)
+                        .expect("renamed variable is not captured");
+
+                    (renamed, mapped)
+                }
+            };
+
+        Expr::Lambda(Box::new(collection), op, variable, Box::new(body))
+    }
+
+    fn fold_identifier(&mut self, name: String) -> Expr {
+        if let Some(variable) = self.variable(&name) {
+            // The variable itself stands for an item of the collection, and
+            // paths from it are mapped relative to it.
+            let Some((public, internal)) = variable
+                .collection
+                .as_ref()
+                .filter(|_| name.len() > variable.name.len())
+            else {
+                return Expr::Identifier(name);
+            };
+
+            let public = format!("{public}{}", &name[variable.name.len()..]);
+            let relative = internal
+                .as_ref()
+                .map(|internal| (variable.name.clone(), internal.clone()));
+
+            match (self.fields.resolve(&public), relative) {
+                (Ok(Expr::Identifier(path)), Some((variable, internal))) => {
+                    match path
+                        .strip_prefix(internal.as_str())
+                        .filter(|rest| rest.starts_with('/'))
+                    {
+                        Some(rest) => return Expr::Identifier(format!("{variable}{rest}")),
+                        None => self.report(MappingError::OutsideCollection { name: public }),
+                    }
+                }
+                (Ok(_), Some(_)) => self.report(MappingError::OutsideCollection { name: public }),
+                (Ok(_), None) => {}
+                (Err(error), _) => self.report(error),
+            }
+
+            return Expr::Identifier(name);
+        }
+
+        match self.fields.resolve(&name) {
+            Ok(expr) => {
+                for identifier in expr.identifiers() {
+                    let capturing = self
+                        .scope
+                        .iter_mut()
+                        .rev()
+                        .find(|variable| variable.name == root(identifier));
+
+                    if let Some(variable) = capturing {
+                        variable.captured = true;
+                    }
+                }
+
+                expr
+            }
+            Err(error) => {
+                self.report(error);
+                Expr::Identifier(name)
+            }
+        }
+    }
+}
+
+/// Collects the roots of identifiers and the lambda variables of an
+/// expression.
+#[derive(Default)]
+struct Names<'a>(HashSet<&'a str>);
+
+impl<'a> Visitor<'a> for Names<'a> {
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        _op: &'a LambdaOperator,
+        variable: &'a str,
+        body: &'a Expr,
+    ) {
+        self.0.insert(variable);
+        self.visit_expr(collection);
+        self.visit_expr(body);
+    }
+
+    fn visit_identifier(&mut self, name: &'a str) {
+        self.0.insert(root(name));
+    }
+}
+
+/// Renames a lambda variable in the body of its lambda.
+struct RenameVariable<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl Fold for RenameVariable<'_> {
+    fn fold_lambda(
+        &mut self,
+        collection: Expr,
+        op: LambdaOperator,
+        variable: String,
+        body: Expr,
+    ) -> Expr {
+        let collection = self.fold_expr(collection);
+
+        // A lambda with a variable of the same name shadows it.
+        let body = match variable == self.from {
+            true => body,
+            false => self.fold_expr(body),
+        };
+
+        Expr::Lambda(Box::new(collection), op, variable, Box::new(body))
+    }
+
+    fn fold_identifier(&mut self, name: String) -> Expr {
+        match root(&name) == self.from {
+            true => Expr::Identifier(format!("{}{}", self.to, &name[self.from.len()..])),
+            false => Expr::Identifier(name),
+        }
+    }
+}
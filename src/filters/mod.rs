@@ -1,17 +1,40 @@
+#[cfg(feature = "serde")]
+pub mod as_string;
+pub mod borrowed;
+mod builder;
+mod canonical;
+mod codes;
+mod combinators;
+mod constraints;
+mod format;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod generate;
+mod implication;
+mod mapping;
 mod parse;
+mod policy;
+mod security;
+mod simplify;
+mod spanned;
 mod to_query_string;
 mod validate;
+mod visit;
 
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, SecondsFormat, Utc};
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
-pub use parse::parse_str;
+pub(crate) use format::PercentEncoder;
+pub(crate) use parse::{parse_order_by_with, parse_search_with};
+pub use builder::{alias, field, function, value};
+pub use format::{to_query_string_with, write_query_string_with};
+pub use parse::{parse_str, parse_str_spanned, parse_str_spanned_with, parse_str_with};
 pub use to_query_string::{to_query_string, write_query_string};
+pub use visit::{walk_expr, walk_expr_mut, walk_fold_expr, Fold, Visitor, VisitorMut};
 
 /// This alias is to make the rename to ParseError a non-breaking change.
 /// You should prefer using ParseError.
@@ -21,9 +44,10 @@ pub use ParseError as Error;
 /// Represents various errors that can occur during parsing.
 #[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum ParseError {
-    /// Error during general parsing.
-    #[error("Error during general parsing.")]
-    Parsing,
+    /// Error during general parsing, with the byte offset in the query
+    /// where the input stopped matching the grammar.
+    #[error("Error during general parsing at position {position}.")]
+    Parsing { position: usize },
 
     /// Error parsing a UUID.
     #[error("Error parsing a UUID.")]
@@ -56,6 +80,195 @@ pub enum ParseError {
     /// Error parsing a Unicode code point escape sequence.
     #[error("Error parsing a Unicode code point escape sequence.")]
     ParsingUnicodeCodePoint,
+
+    /// The input is longer than allowed by `ParseOptions::max_length`.
+    #[error("Filter is {length} bytes long which exceeds the maximum of {max}.")]
+    InputTooLong { length: usize, max: usize },
+
+    /// The input nests deeper than allowed by `ParseOptions::max_depth`.
+    #[error("Filter exceeds the maximum nesting depth of {max}.")]
+    NestingTooDeep { max: usize },
+
+    /// The input has more nodes than allowed by `ParseOptions::max_nodes`.
+    #[error("Filter exceeds the maximum of {max} expression nodes.")]
+    TooManyNodes { max: usize },
+
+    /// An `in` list is longer than allowed by `ParseOptions::max_in_list`.
+    #[error("An 'in' list exceeds the maximum of {max} items.")]
+    InListTooLarge { max: usize },
+
+    /// Lambdas nest deeper than allowed by `ParseOptions::max_lambda_depth`.
+    #[error("Filter exceeds the maximum lambda nesting depth of {max}.")]
+    LambdaNestingTooDeep { max: usize },
+
+    /// A string literal is longer than allowed by `ParseOptions::max_string_length`.
+    #[error("A string literal exceeds the maximum of {max} characters.")]
+    StringLiteralTooLong { max: usize },
+}
+
+/// Limits applied while parsing a `$filter` expression.
+///
+/// Every limit is optional and unset by default, which is what `parse_str`
+/// uses. Filters from untrusted clients should be parsed with limits set,
+/// for example from `ParseOptions::untrusted`, so that hostile input is
+/// rejected with a typed `ParseError` before it can exhaust the stack or memory.
+///
+/// ```
+/// use odata_params::filters::{parse_str_with, ParseError, ParseOptions};
+///
+/// let options = ParseOptions {
+///     max_in_list: Some(2),
+///     ..ParseOptions::default()
+/// };
+///
+/// assert!(parse_str_with("id in (1, 2)", &options).is_ok());
+/// assert_eq!(
+///     parse_str_with("id in (1, 2, 3)", &options),
+///     Err(ParseError::InListTooLarge { max: 2 }),
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Maximum length of the input in bytes.
+    pub max_length: Option<usize>,
+
+    /// Maximum nesting depth of the input.
+    ///
+    /// Every level of grouping parentheses, `not`, the right hand side of
+    /// `and`/`or`, function arguments, `in` list items and lambda bodies
+    /// counts as one level of nesting. In `$search` expressions, grouping
+    /// parentheses and `NOT` do.
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of nodes in the resulting expression tree.
+    pub max_nodes: Option<usize>,
+
+    /// Maximum number of items in a single `in` list.
+    pub max_in_list: Option<usize>,
+
+    /// Maximum number of lambdas (any/all) nested within each other.
+    pub max_lambda_depth: Option<usize>,
+
+    /// Maximum number of characters in a single string literal.
+    pub max_string_length: Option<usize>,
+}
+
+impl ParseOptions {
+    /// Conservative limits suitable for filters from anonymous clients.
+    pub fn untrusted() -> Self {
+        Self {
+            max_length: Some(4096),
+            max_depth: Some(32),
+            max_nodes: Some(512),
+            max_in_list: Some(100),
+            max_lambda_depth: Some(2),
+            max_string_length: Some(1024),
+        }
+    }
+}
+
+/// Options for writing a `$filter` expression with `to_query_string_with`.
+///
+/// Unlike `to_query_string`, which groups every nested `and` and `or`, the
+/// output only has the parentheses needed to read the same both with the
+/// precedence of the OData specification, where `not` binds tighter than
+/// `and` and `and` tighter than `or`, and with `parse_str`.
+///
+/// ```
+/// use odata_params::chrono::SecondsFormat;
+/// use odata_params::filters::{parse_str, to_query_string_with, FormatOptions, KeywordCase};
+///
+/// let expr = parse_str("(name eq 'Bob' and age gt 3) or created lt 2024-03-01T10:00:00Z")
+///     .expect("valid filter tree");
+///
+/// let options = FormatOptions {
+///     keyword_case: KeywordCase::Upper,
+///     datetime_precision: SecondsFormat::Secs,
+///     ..FormatOptions::default()
+/// };
+///
+/// assert_eq!(
+///     to_query_string_with(&expr, &options).expect("valid filter"),
+///     "(name EQ 'Bob' AND age GT 3) OR created LT 2024-03-01T10:00:00Z",
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Spaces to indent by when writing `and` and `or` operands on separate
+    /// lines, or `None` to write everything on a single line.
+    ///
+    /// Function arguments, `in` lists and lambda bodies stay on one line.
+    pub indent: Option<usize>,
+
+    /// Case of operators and other keywords.
+    pub keyword_case: KeywordCase,
+
+    /// Precision of the fractional seconds of datetimes.
+    pub datetime_precision: SecondsFormat,
+
+    /// Offset datetimes are written in, or `None` for UTC written as `Z`.
+    ///
+    /// Datetimes are held in UTC, so the offset a parsed datetime was
+    /// written with is not kept.
+    pub datetime_offset: Option<FixedOffset>,
+
+    /// How single quotes within strings are escaped.
+    pub quote_style: QuoteStyle,
+
+    /// Whether to percent-encode the output for use in a URL query string.
+    pub percent_encode: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: None,
+            keyword_case: KeywordCase::Lower,
+            datetime_precision: SecondsFormat::Millis,
+            datetime_offset: None,
+            quote_style: QuoteStyle::Doubled,
+            percent_encode: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Multi-line output indented by two spaces, suitable for logs and
+    /// debugging.
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some(2),
+            ..Self::default()
+        }
+    }
+}
+
+/// Case of the keywords written by `to_query_string_with`.
+///
+/// `parse_str` only reads lowercase keywords.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeywordCase {
+    /// Lowercase keywords such as `and` and `eq`.
+    #[default]
+    Lower,
+
+    /// Uppercase keywords such as `AND` and `EQ`.
+    Upper,
+}
+
+/// How `to_query_string_with` escapes single quotes within strings.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Doubles quotes as in `'O''Brien'`, following the OData specification.
+    ///
+    /// Backslashes are written as is, so `parse_str` reads strings holding
+    /// them back differently.
+    #[default]
+    Doubled,
+
+    /// Escapes quotes and backslashes with a backslash as in `'O\'Brien'`,
+    /// which `parse_str` reads back to the same string.
+    Backslash,
 }
 
 #[derive(Clone, Debug, Error, PartialEq, Eq)]
@@ -102,8 +315,354 @@ pub enum ValidationError {
     },
 }
 
-/// Represents the different types of expressions in the AST.
+/// A `ValidationError` found in a [`SpannedExpr`], along with the span of
+/// the node it was found at.
+///
+/// Type mismatches point at the operator or call that failed, undefined
+/// names at the identifier or call, and wrong argument types at the argument.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("{error} (at {span})")]
+pub struct SpannedValidationError {
+    /// The error itself.
+    pub error: ValidationError,
+
+    /// Where in the `$filter` string the error was found.
+    pub span: Span,
+}
+
+/// Represents errors that can occur while converting an expression to a
+/// normal form.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum NormalFormError {
+    /// The normal form would have more clauses than allowed.
+    #[error("The normal form has more than {max} clauses.")]
+    TooManyClauses { max: usize },
+}
+
+/// The outcome of checking whether an expression implies another, as
+/// returned by `Expr::implies`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Implication {
+    /// Every row matching the first expression also matches the second.
+    Yes,
+
+    /// Some row matches the first expression but not the second.
+    No,
+
+    /// The analysis cannot tell, for example because the expressions use
+    /// functions, lambdas or comparisons between identifiers.
+    Unknown,
+}
+
+/// The values an identifier may take whenever an expression holds, as
+/// returned by `Expr::constraints`.
+///
+/// Constraints are conservative: the identifier is guaranteed to take one of
+/// the described values, but not every described value necessarily matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    /// Whether the identifier may be `null`.
+    pub nullable: bool,
+
+    /// The non-null values the identifier may take as a union of sets, or
+    /// `None` if it may take any of them. An empty list allows none.
+    pub values: Option<Vec<ValueSet>>,
+}
+
+/// A set of non-null values within a `Constraint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueSet {
+    /// Any of the listed values.
+    In(Vec<Value>),
+
+    /// Values within a range, which is open on a side without a bound.
+    Range {
+        lower: Option<Bound>,
+        upper: Option<Bound>,
+    },
+
+    /// Strings starting with a prefix.
+    Prefix(String),
+}
+
+/// A lower or upper bound of a `ValueSet::Range`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bound {
+    pub value: Value,
+    pub inclusive: bool,
+}
+
+/// Represents a reason an expression is rejected by a `FilterPolicy`.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// Comparison operator that is not allowed anywhere.
+    #[error("The '{operator}' operator is not allowed.")]
+    OperatorNotAllowed { operator: CompareOperator },
+
+    /// Comparison operator that is not allowed on a specific identifier.
+    #[error("The '{operator}' operator is not allowed on '{identifier}'.")]
+    OperatorNotAllowedOn {
+        identifier: String,
+        operator: CompareOperator,
+    },
+
+    /// The `in` operator is not allowed anywhere.
+    #[error("The 'in' operator is not allowed.")]
+    InNotAllowed,
+
+    /// The `in` operator is not allowed on a specific identifier.
+    #[error("The 'in' operator is not allowed on '{identifier}'.")]
+    InNotAllowedOn { identifier: String },
+
+    /// Function that is not allowed.
+    #[error("The function '{name}' is not allowed.")]
+    FunctionNotAllowed { name: String },
+
+    /// Lambda operator that is not allowed.
+    #[error("The '{operator}' lambda operator is not allowed.")]
+    LambdaNotAllowed { operator: LambdaOperator },
+
+    /// Identifier that may not be filtered on at all.
+    #[error("Filtering on '{identifier}' is not allowed.")]
+    IdentifierNotFilterable { identifier: String },
+}
+
+/// A declarative allow-list of what a `$filter` expression may use, similar
+/// to the `FilterRestrictions` term of the OData Capabilities vocabulary.
+///
+/// Every restriction is optional and unset by default, in which case
+/// everything is allowed. The `in` operator is treated as a list of `eq`
+/// comparisons, so it is allowed wherever `eq` is.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use odata_params::filters::{parse_str, CompareOperator, FilterPolicy, PolicyViolation};
+///
+/// let policy = FilterPolicy {
+///     functions: Some(["startswith".to_string()].into()),
+///     identifier_operators: HashMap::from([(
+///         "id".to_string(),
+///         [CompareOperator::Equal].into(),
+///     )]),
+///     ..FilterPolicy::default()
+/// };
+///
+/// let expr = parse_str("id in (1, 2) and startswith(name, 'J')").expect("valid filter tree");
+/// assert_eq!(expr.check_policy(&policy), Ok(()));
+///
+/// let expr = parse_str("id gt 1 and contains(name, 'J')").expect("valid filter tree");
+/// assert_eq!(
+///     expr.check_policy(&policy),
+///     Err(vec![
+///         PolicyViolation::OperatorNotAllowedOn {
+///             identifier: "id".to_string(),
+///             operator: CompareOperator::GreaterThan,
+///         },
+///         PolicyViolation::FunctionNotAllowed {
+///             name: "contains".to_string(),
+///         },
+///     ]),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterPolicy {
+    /// Comparison operators allowed anywhere in the expression.
+    pub operators: Option<HashSet<CompareOperator>>,
+
+    /// Names of the functions that may be called.
+    pub functions: Option<HashSet<String>>,
+
+    /// Lambda operators that may be used.
+    pub lambda_operators: Option<HashSet<LambdaOperator>>,
+
+    /// Comparison operators allowed when an identifier is directly compared.
+    ///
+    /// This narrows `operators` for the listed identifiers. Lambda variables
+    /// are checked against the entry of the collection they range over, and
+    /// paths from them such as `it/total` against the entry of the same path
+    /// under the collection, such as `orders/total`.
+    pub identifier_operators: HashMap<String, HashSet<CompareOperator>>,
+
+    /// Identifiers that may not appear in the expression at all, along with
+    /// every path under them, including through lambda variables.
+    pub non_filterable: HashSet<String>,
+}
+
+/// Represents a reason a user filter is rejected by `RowFilter::merge`.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SecurityViolation {
+    /// Identifier reserved for the mandatory predicate.
+    #[error("Filtering on '{identifier}' is reserved.")]
+    ProtectedIdentifier { identifier: String },
+
+    /// Parameter alias, whose value could be substituted after merging.
+    #[error("The parameter alias '{alias}' is not allowed.")]
+    AliasNotAllowed { alias: String },
+}
+
+/// A mandatory predicate, such as a tenant or ownership check, that every
+/// user filter is restricted to for row-level security.
+///
+/// ```
+/// use odata_params::filters::{parse_str, to_query_string, RowFilter, SecurityViolation};
+///
+/// let row_filter = RowFilter::new(parse_str("tenant eq 7").expect("valid filter tree"));
+///
+/// let user = parse_str("name eq 'Bob' or age gt 3").expect("valid filter tree");
+/// let merged = row_filter.merge(Some(user)).expect("allowed filter");
+///
+/// assert_eq!(
+///     to_query_string(&merged).expect("valid filter"),
+///     "tenant eq 7 and (name eq 'Bob' or age gt 3)",
+/// );
+///
+/// let user = parse_str("tenant eq 8 or true").expect("valid filter tree");
+///
+/// assert_eq!(
+///     row_filter.merge(Some(user)),
+///     Err(vec![SecurityViolation::ProtectedIdentifier {
+///         identifier: "tenant".to_string(),
+///     }]),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowFilter {
+    /// Predicate every row must match, whatever the user filter.
+    pub predicate: Expr,
+
+    /// Identifiers user filters may not mention.
+    pub protected: HashSet<String>,
+}
+
+/// Represents a reason an expression cannot be mapped by a `FieldMap`.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MappingError {
+    /// Identifier that is not a public field.
+    #[error("Unknown field '{name}'.")]
+    UnknownField { name: String },
+
+    /// Identifier of a field that is known but not exposed.
+    #[error("The field '{name}' is not available.")]
+    HiddenField { name: String },
+
+    /// Lambda collection whose field does not map to an identifier.
+    #[error("The field '{name}' cannot be used as a collection.")]
+    NotACollection { name: String },
+
+    /// Path from a lambda variable whose field does not map to a path under
+    /// the collection the variable ranges over.
+    #[error("The field '{name}' cannot be used through a lambda variable.")]
+    OutsideCollection { name: String },
+}
+
+/// What a public field maps to in a `FieldMap`.
 #[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// An internal identifier or path, such as a column name.
+    Renamed(String),
+
+    /// An expression computing the field from internal identifiers.
+    Computed(Expr),
+
+    /// A field that is known but may not be used.
+    Hidden,
+}
+
+/// Maps the public fields of `$filter` and `$orderby` expressions to their
+/// internal names, so that storage details stay out of public names.
+///
+/// Identifiers that are not listed are rejected. A path such as `owner/name`
+/// is looked up as a whole first, then through its longest listed prefix, so
+/// renaming `owner` to `users` maps `owner/name` to `users/name`. Paths from
+/// a lambda variable are looked up under the collection it ranges over, so
+/// `tag/name` in `tags/any(tag: tag/name eq 'x')` is looked up as `tags/name`,
+/// and must map to a path under what `tags` maps to. A lambda variable named
+/// like an identifier a field maps to is renamed so that it does not capture
+/// it.
+///
+/// ```
+/// use odata_params::filters::{parse_str, to_query_string, FieldMap, MappingError};
+///
+/// let fields = FieldMap::new()
+///     .with_renamed("createdAt", "created_at")
+///     .with_renamed("owner/name", "users.display_name")
+///     .with_computed(
+///         "fullName",
+///         parse_str("concat(first, concat(' ', last))").expect("valid filter tree"),
+///     )
+///     .with_hidden("passwordHash");
+///
+/// let expr = parse_str("owner/name eq 'Bob' and fullName ne '' and createdAt gt 2024-01-01")
+///     .expect("valid filter tree");
+///
+/// assert_eq!(
+///     to_query_string(&fields.map_filter(expr).expect("mapped filter")).expect("valid filter"),
+///     "users.display_name eq 'Bob' and (concat(first, concat(' ', last)) ne '' and created_at gt 2024-01-01)",
+/// );
+///
+/// let expr = parse_str("passwordHash eq 'x' or age gt 3").expect("valid filter tree");
+///
+/// assert_eq!(
+///     fields.map_filter(expr),
+///     Err(vec![
+///         MappingError::HiddenField {
+///             name: "passwordHash".to_string(),
+///         },
+///         MappingError::UnknownField {
+///             name: "age".to_string(),
+///         },
+///     ]),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldMap {
+    /// Public fields along with what they map to.
+    pub fields: HashMap<String, Field>,
+}
+
+/// A non-boolean operand of an expression being built, such as an
+/// identifier, a constant or a function call, as started by `field` or
+/// `value`.
+///
+/// Comparing operands gives a `Condition`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operand(Expr);
+
+/// A boolean expression being built, which can be joined with `and`, `or`
+/// and negated with `!` before being turned into an `Expr`.
+///
+/// ```
+/// use odata_params::filters::{field, parse_str, Expr};
+///
+/// let built: Expr = field("age").gt(30).and(field("name").starts_with("J")).into();
+///
+/// assert_eq!(
+///     built,
+///     parse_str("age gt 30 and startswith(name, 'J')").expect("valid filter tree"),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition(Expr);
+
+/// An expression that serializes as its OData `$filter` string, and
+/// deserializes from one, rather than as a tree.
+///
+/// ```
+/// use odata_params::filters::{parse_str, FilterString};
+///
+/// let filter = FilterString(parse_str("tags/any(tag: tag eq 'vip')").expect("valid filter tree"));
+///
+/// let json = serde_json::to_string(&filter).unwrap();
+/// assert_eq!(json, r#""tags/any(tag: tag eq 'vip')""#);
+/// assert_eq!(serde_json::from_str::<FilterString>(&json).unwrap(), filter);
+/// assert!(serde_json::from_str::<FilterString>(r#""tags eq""#).is_err());
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FilterString(pub Expr);
+
+/// Represents the different types of expressions in the AST.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Expr {
     /// Logical OR between two expressions.
@@ -139,7 +698,7 @@ pub enum Expr {
 }
 
 /// Represents the lambda operators 'any' and 'all'.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LambdaOperator {
     Any,
@@ -156,7 +715,7 @@ impl std::fmt::Display for LambdaOperator {
 }
 
 /// Represents the various comparison operators.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CompareOperator {
     /// Equal to.
@@ -197,7 +756,7 @@ impl std::fmt::Display for CompareOperator {
 }
 
 /// Represents the various value types.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Value {
     /// Null value.
@@ -247,6 +806,138 @@ impl PartialEq for Type {
     }
 }
 
+/// An expression annotated with the type resolved for it during validation.
+///
+/// Produced by [`Expr::validate_typed`] so that evaluators and query
+/// generators can rely on the inferred types without checking them again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedExpr {
+    /// The resolved type of this node.
+    pub ty: Type,
+
+    /// The node itself, holding its typed children.
+    pub kind: TypedExprKind,
+}
+
+/// Represents the different kinds of nodes in a [`TypedExpr`] tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypedExprKind {
+    /// Logical OR between two expressions.
+    Or(Box<TypedExpr>, Box<TypedExpr>),
+
+    /// Logical AND between two expressions.
+    And(Box<TypedExpr>, Box<TypedExpr>),
+
+    /// Logical NOT to invert an expression.
+    Not(Box<TypedExpr>),
+
+    /// Comparison between two expressions.
+    Compare(Box<TypedExpr>, CompareOperator, Box<TypedExpr>),
+
+    /// In operator to check if a value is within a list of values.
+    In(Box<TypedExpr>, Vec<TypedExpr>),
+
+    /// Function call with a name, the signature it was resolved against and
+    /// a list of arguments.
+    Function(String, FunctionSignature, Vec<TypedExpr>),
+
+    /// Lambda expression (any/all)
+    /// Structure: (Collection, Operator, Lambda Variable, Filter Expression)
+    Lambda(Box<TypedExpr>, LambdaOperator, String, Box<TypedExpr>),
+
+    /// An identifier resolved from the `IdentifiersTypeMap`.
+    Identifier(String),
+
+    /// A variable bound by an enclosing lambda expression.
+    LambdaVariable(String),
+
+    /// A parameter alias (e.g., @p1) resolved from the `IdentifiersTypeMap`.
+    Alias(String),
+
+    /// A constant value.
+    Value(Value),
+
+    /// An implicit conversion of the inner expression to the type of this node.
+    ///
+    /// Only operands without a concrete type (`null` literals and lambda
+    /// variables) are converted, and only when they meet an operand or
+    /// function argument of a concrete type.
+    Convert(Box<TypedExpr>),
+}
+
+/// The signature a function call was resolved against.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionSignature {
+    /// Types of the required arguments.
+    pub arguments: Vec<Type>,
+
+    /// Type of any extra arguments if the function is variadic.
+    pub variadic: Option<Type>,
+
+    /// Type returned by the function.
+    pub returns: Type,
+}
+
+/// A byte range of the `$filter` string an expression was parsed from.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first character.
+    pub start: usize,
+
+    /// Byte offset just past the last character.
+    pub end: usize,
+}
+
+/// An expression annotated with where it was found in the `$filter` string.
+///
+/// Produced by [`parse_str_spanned`] for tooling that needs to point back at
+/// the source, such as highlighting the part of a filter that failed
+/// validation. Spans are byte ranges of the string given to the parser,
+/// leading whitespace included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpannedExpr {
+    /// Where this node was found.
+    pub span: Span,
+
+    /// The node itself, holding its spanned children.
+    pub kind: SpannedExprKind,
+}
+
+/// Represents the different kinds of nodes in a [`SpannedExpr`] tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpannedExprKind {
+    /// Logical OR between two expressions.
+    Or(Box<SpannedExpr>, Box<SpannedExpr>),
+
+    /// Logical AND between two expressions.
+    And(Box<SpannedExpr>, Box<SpannedExpr>),
+
+    /// Logical NOT to invert an expression.
+    Not(Box<SpannedExpr>),
+
+    /// Comparison between two expressions.
+    Compare(Box<SpannedExpr>, CompareOperator, Box<SpannedExpr>),
+
+    /// In operator to check if a value is within a list of values.
+    In(Box<SpannedExpr>, Vec<SpannedExpr>),
+
+    /// Function call with a name and a list of arguments.
+    Function(String, Vec<SpannedExpr>),
+
+    /// Lambda expression (any/all)
+    /// Structure: (Collection Identifier, Operator, Lambda Variable, Filter Expression)
+    Lambda(Box<SpannedExpr>, LambdaOperator, String, Box<SpannedExpr>),
+
+    /// An identifier.
+    Identifier(String),
+
+    /// A parameter alias (e.g., @p1)
+    Alias(String),
+
+    /// A constant value.
+    Value(Value),
+}
+
 /// Represents a map of identifiers to their corresponding types.
 ///
 /// ```
@@ -288,6 +979,7 @@ pub struct IdentifiersTypeMap(HashMap<String, Type>);
 ///
 /// let functions_map: FunctionsTypeMap = map.into();
 /// ```
+#[derive(Clone)]
 pub struct FunctionsTypeMap(HashMap<String, (Vec<Type>, Option<Type>, Type)>);
 
 impl From<HashMap<String, Type>> for IdentifiersTypeMap {
@@ -1,6 +1,15 @@
-use super::{CompareOperator, Expr, LambdaOperator, ParseError, Value};
+use super::borrowed::Value;
+use super::{
+    CompareOperator, Expr, LambdaOperator, ParseError, ParseOptions, Span, SpannedExpr,
+    SpannedExprKind,
+};
+use crate::orderby::{Direction, OrderBy};
+use crate::search::SearchExpr;
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+use peg::str::LineCol;
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -15,56 +24,520 @@ use uuid::Uuid;
 /// let result = parse_str(filter).expect("valid filter tree");
 /// ```
 pub fn parse_str(query: impl AsRef<str>) -> Result<Expr, ParseError> {
-    match odata_filter::parse_str(query.as_ref().trim()) {
-        Ok(expr) => expr,
-        Err(_error) => Err(ParseError::Parsing),
+    parse_str_with(query, &ParseOptions::default())
+}
+
+/// Parses an OData v4 `$filter` expression string into an `Expr` AST while
+/// enforcing the limits set in `options`.
+///
+/// Limits are checked as the input is consumed, so a violation stops parsing
+/// before the offending part of the input is allocated.
+///
+/// ```
+/// use odata_params::filters::{parse_str_with, ParseError, ParseOptions};
+///
+/// let options = ParseOptions::untrusted();
+///
+/// assert!(parse_str_with("name eq 'John'", &options).is_ok());
+///
+/// let hostile = format!("{}age gt 30{}", "(".repeat(1000), ")".repeat(1000));
+/// assert_eq!(
+///     parse_str_with(hostile, &options),
+///     Err(ParseError::NestingTooDeep { max: 32 }),
+/// );
+/// ```
+pub fn parse_str_with(query: impl AsRef<str>, options: &ParseOptions) -> Result<Expr, ParseError> {
+    parse_filter_with(query.as_ref(), options)
+}
+
+/// Parses an OData v4 `$filter` expression string into a `SpannedExpr` AST,
+/// recording the byte range of the string every node was parsed from.
+///
+/// ```
+/// use odata_params::filters::{parse_str_spanned, SpannedExprKind};
+///
+/// let filter = "name eq 'John' and isActive eq true";
+/// let expr = parse_str_spanned(filter).expect("valid filter tree");
+///
+/// let SpannedExprKind::And(lhs, rhs) = expr.kind else { unreachable!() };
+/// assert_eq!(&filter[lhs.span.range()], "name eq 'John'");
+/// assert_eq!(&filter[rhs.span.range()], "isActive eq true");
+/// ```
+pub fn parse_str_spanned(query: impl AsRef<str>) -> Result<SpannedExpr, ParseError> {
+    parse_str_spanned_with(query, &ParseOptions::default())
+}
+
+/// Parses an OData v4 `$filter` expression string into a `SpannedExpr` AST
+/// while enforcing the limits set in `options`.
+pub fn parse_str_spanned_with(
+    query: impl AsRef<str>,
+    options: &ParseOptions,
+) -> Result<SpannedExpr, ParseError> {
+    parse_filter_with(query.as_ref(), options)
+}
+
+/// Parses a `$filter` expression string into any tree that can be built
+/// from the grammar.
+pub(super) fn parse_filter_with<'a, N: Build<'a>>(
+    query: &'a str,
+    options: &ParseOptions,
+) -> Result<N, ParseError> {
+    parse_with(query, options, odata_filter::parse_str::<N>)
+}
+
+/// Parses an OData v4 `$orderby` expression string while enforcing the
+/// limits set in `options` across all of its items.
+pub(crate) fn parse_order_by_with(
+    query: &str,
+    options: &ParseOptions,
+) -> Result<Vec<OrderBy>, ParseError> {
+    parse_with(query, options, odata_filter::order_by)
+}
+
+/// Parses an OData v4 `$search` expression string while enforcing the
+/// limits set in `options`.
+pub(crate) fn parse_search_with(
+    query: &str,
+    options: &ParseOptions,
+) -> Result<SearchExpr, ParseError> {
+    parse_with(query, options, odata_filter::search)
+}
+
+type RuleResult<T> = Result<Result<T, ParseError>, peg::error::ParseError<LineCol>>;
+
+fn parse_with<'a, T>(
+    query: &'a str,
+    options: &ParseOptions,
+    rule: impl FnOnce(&'a str, &Limits) -> RuleResult<T>,
+) -> Result<T, ParseError> {
+    if let Some(max) = options.max_length {
+        if query.len() > max {
+            return Err(ParseError::InputTooLong {
+                length: query.len(),
+                max,
+            });
+        }
+    }
+
+    // Spans are given relative to the untrimmed query.
+    let offset = query.len() - query.trim_start().len();
+    let limits = Limits::new(options, offset);
+    let result = rule(query.trim(), &limits);
+
+    // A violated limit makes the grammar fail at that point so the recorded
+    // violation takes precedence over the generic parsing error.
+    if let Some(violation) = limits.violation.take() {
+        return Err(violation);
+    }
+
+    match result {
+        Ok(parsed) => parsed,
+        Err(error) => Err(ParseError::Parsing {
+            position: error.location.offset + offset,
+        }),
+    }
+}
+
+/// Tracks the limits of `ParseOptions` while the grammar runs.
+struct Limits {
+    options: ParseOptions,
+    nodes: Cell<usize>,
+    violation: Cell<Option<ParseError>>,
+
+    /// Where the parsed input starts in the query, added to every position.
+    offset: usize,
+}
+
+impl Limits {
+    fn new(options: &ParseOptions, offset: usize) -> Self {
+        Self {
+            options: *options,
+            nodes: Cell::new(0),
+            violation: Cell::new(None),
+            offset,
+        }
+    }
+
+    /// Clears what was counted, as peg runs the grammar a second time to
+    /// collect the details of a parsing error.
+    fn reset(&self) -> Result<(), &'static str> {
+        self.nodes.set(0);
+        self.violation.set(None);
+        Ok(())
+    }
+
+    /// Records the first violated limit and fails the current rule.
+    fn violate(&self, error: ParseError) -> Result<(), &'static str> {
+        let first = self.violation.take().unwrap_or(error);
+        self.violation.set(Some(first));
+        Err("within parse limits")
+    }
+
+    fn check_nesting(&self, depth: Depth) -> Result<(), &'static str> {
+        match self.options.max_depth {
+            Some(max) if depth.nesting > max => self.violate(ParseError::NestingTooDeep { max }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_lambda_nesting(&self, depth: Depth) -> Result<(), &'static str> {
+        match self.options.max_lambda_depth {
+            Some(max) if depth.lambdas >= max => {
+                self.violate(ParseError::LambdaNestingTooDeep { max })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn count_node(&self) -> Result<(), &'static str> {
+        let nodes = self.nodes.get() + 1;
+        self.nodes.set(nodes);
+
+        match self.options.max_nodes {
+            Some(max) if nodes > max => self.violate(ParseError::TooManyNodes { max }),
+            _ => Ok(()),
+        }
+    }
+
+    fn max_in_list(&self) -> usize {
+        self.options.max_in_list.unwrap_or(usize::MAX)
+    }
+
+    fn in_list_too_large(&self) -> Result<(), &'static str> {
+        self.violate(ParseError::InListTooLarge {
+            max: self.max_in_list(),
+        })
+    }
+
+    fn max_string_length(&self) -> usize {
+        self.options.max_string_length.unwrap_or(usize::MAX)
+    }
+
+    fn string_too_long(&self) -> Result<(), &'static str> {
+        self.violate(ParseError::StringLiteralTooLong {
+            max: self.max_string_length(),
+        })
+    }
+}
+
+/// How deeply nested the expression currently being parsed is.
+#[derive(Copy, Clone)]
+struct Depth {
+    nesting: usize,
+    lambdas: usize,
+}
+
+impl Depth {
+    const ROOT: Depth = Depth {
+        nesting: 1,
+        lambdas: 0,
+    };
+
+    fn deeper(self) -> Self {
+        Self {
+            nesting: self.nesting + 1,
+            ..self
+        }
+    }
+
+    fn lambda(self) -> Self {
+        Self {
+            nesting: self.nesting + 1,
+            lambdas: self.lambdas + 1,
+        }
     }
 }
 
-enum AfterValueExpr {
-    Compare(CompareOperator, Box<Expr>),
-    In(Vec<Expr>),
+/// A node of the tree being parsed, with its children already built.
+pub(super) enum Parsed<'a, N> {
+    Or(N, N),
+    And(N, N),
+    Not(N),
+    Compare(N, CompareOperator, N),
+    In(N, Vec<N>),
+    Function(&'a str, Vec<N>),
+    Lambda(N, LambdaOperator, &'a str, N),
+    Identifier(&'a str),
+    Alias(&'a str),
+    Value(Value<'a>),
+}
+
+/// A tree the grammar can parse a `$filter` string into.
+pub(super) trait Build<'a>: Sized {
+    /// Builds a node found at `span` of the query.
+    fn build(span: Span, parsed: Parsed<'a, Self>) -> Self;
+
+    /// Gives a node the span of the parentheses grouping it.
+    fn group(self, _span: Span) -> Self {
+        self
+    }
+}
+
+impl<'a> Build<'a> for Expr {
+    fn build(_span: Span, parsed: Parsed<'a, Self>) -> Self {
+        match parsed {
+            Parsed::Or(lhs, rhs) => Expr::Or(Box::new(lhs), Box::new(rhs)),
+            Parsed::And(lhs, rhs) => Expr::And(Box::new(lhs), Box::new(rhs)),
+            Parsed::Not(expr) => Expr::Not(Box::new(expr)),
+            Parsed::Compare(lhs, op, rhs) => Expr::Compare(Box::new(lhs), op, Box::new(rhs)),
+            Parsed::In(lhs, values) => Expr::In(Box::new(lhs), values),
+            Parsed::Function(name, args) => Expr::Function(name.to_owned(), args),
+            Parsed::Lambda(collection, op, variable, body) => Expr::Lambda(
+                Box::new(collection),
+                op,
+                variable.to_owned(),
+                Box::new(body),
+            ),
+            Parsed::Identifier(name) => Expr::Identifier(name.to_owned()),
+            Parsed::Alias(name) => Expr::Alias(name.to_owned()),
+            Parsed::Value(value) => Expr::Value(value.into_owned()),
+        }
+    }
+}
+
+impl<'a> Build<'a> for SpannedExpr {
+    fn build(span: Span, parsed: Parsed<'a, Self>) -> Self {
+        let kind = match parsed {
+            Parsed::Or(lhs, rhs) => SpannedExprKind::Or(Box::new(lhs), Box::new(rhs)),
+            Parsed::And(lhs, rhs) => SpannedExprKind::And(Box::new(lhs), Box::new(rhs)),
+            Parsed::Not(expr) => SpannedExprKind::Not(Box::new(expr)),
+            Parsed::Compare(lhs, op, rhs) => {
+                SpannedExprKind::Compare(Box::new(lhs), op, Box::new(rhs))
+            }
+            Parsed::In(lhs, values) => SpannedExprKind::In(Box::new(lhs), values),
+            Parsed::Function(name, args) => SpannedExprKind::Function(name.to_owned(), args),
+            Parsed::Lambda(collection, op, variable, body) => SpannedExprKind::Lambda(
+                Box::new(collection),
+                op,
+                variable.to_owned(),
+                Box::new(body),
+            ),
+            Parsed::Identifier(name) => SpannedExprKind::Identifier(name.to_owned()),
+            Parsed::Alias(name) => SpannedExprKind::Alias(name.to_owned()),
+            Parsed::Value(value) => SpannedExprKind::Value(value.into_owned()),
+        };
+
+        SpannedExpr { span, kind }
+    }
+
+    fn group(self, span: Span) -> Self {
+        SpannedExpr { span, ..self }
+    }
+}
+
+enum LogicalTail<N> {
+    Or(N),
+    And(N),
+    End,
+}
+
+enum AfterValueExpr<N> {
+    Compare(CompareOperator, N),
+    In(Vec<N>),
     End,
 }
 
+/// Decodes the escape sequences of a string literal, borrowing it when it
+/// has none.
+///
+/// Quotes within the literal can only be doubled quotes, and a backslash
+/// not starting a valid escape sequence is kept as is, as the grammar reads
+/// it as a plain character.
+fn unescape(content: &str) -> Result<Cow<'_, str>, ParseError> {
+    if !content.contains(['\\', '\'']) {
+        return Ok(Cow::Borrowed(content));
+    }
+
+    let mut unescaped = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(index) = rest.find(['\\', '\'']) {
+        let quote = rest.as_bytes()[index] == b'\'';
+        unescaped.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let (c, length) = match rest.as_bytes().first() {
+            _ if quote => ('\'', 1),
+            Some(b'\'') => ('\'', 1),
+            Some(b'n') => ('\n', 1),
+            Some(b'r') => ('\r', 1),
+            Some(b't') => ('\t', 1),
+            Some(b'\\') => ('\\', 1),
+            Some(b'u') => {
+                let digits = rest[1..]
+                    .bytes()
+                    .take(8)
+                    .take_while(u8::is_ascii_hexdigit)
+                    .count();
+
+                match digits {
+                    0 => ('\\', 0),
+                    _ => (
+                        u32::from_str_radix(&rest[1..=digits], 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or(ParseError::ParsingUnicodeCodePoint)?,
+                        1 + digits,
+                    ),
+                }
+            }
+            _ => ('\\', 0),
+        };
+
+        unescaped.push(c);
+        rest = &rest[length..];
+    }
+
+    unescaped.push_str(rest);
+    Ok(Cow::Owned(unescaped))
+}
+
+/// Decodes the escape sequences of a `$search` phrase, which are a
+/// backslash followed by a double quote or another backslash.
+fn unescape_phrase(content: &str) -> String {
+    let mut unescaped = String::with_capacity(content.len());
+    let mut chars = content.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
 peg::parser! {
-    /// Parses OData v4 `$filter` expressions.
-    grammar odata_filter() for str {
-        use super::{Expr, CompareOperator, LambdaOperator, Value, ParseError};
+    /// Parses OData v4 `$filter`, `$orderby` and `$search` expressions.
+    grammar odata_filter(limits: &Limits) for str {
+        use super::{Build, Parsed, CompareOperator, LambdaOperator, Value, ParseError, Span};
+        use super::{Direction, Expr, OrderBy, SearchExpr};
 
         /// Entry point for parsing a filter expression string.
-        pub(super) rule parse_str() -> Result<Expr, ParseError>
-            = filter()
+        pub(super) rule parse_str<N: Build<'input>>() -> Result<N, ParseError>
+            = reset() e:filter::<N>(Depth::ROOT) { e }
+
+        /// Entry point for parsing an order by expression string.
+        pub(super) rule order_by() -> Result<Vec<OrderBy>, ParseError>
+            = reset() items:order_by_item() ++ (_ "," _) { items.into_iter().collect() }
+
+        /// Parses an expression to order by, optionally followed by a direction.
+        rule order_by_item() -> Result<OrderBy, ParseError>
+            = e:filter::<Expr>(Depth::ROOT) _ d:direction()? {
+                Ok(OrderBy { expr: e?, direction: d.unwrap_or_default() })
+            }
+
+        rule direction() -> Direction
+            = "asc" { Direction::Ascending }
+            / "desc" { Direction::Descending }
+
+        /// Entry point for parsing a search expression string.
+        pub(super) rule search() -> Result<SearchExpr, ParseError>
+            = reset() e:search_or(Depth::ROOT) { e }
+
+        /// Parses search expressions separated by `OR`, the loosest binding
+        /// operator, grouping them from the left.
+        rule search_or(depth: Depth) -> Result<SearchExpr, ParseError>
+            = nesting(depth) l:search_and(depth) r:(__ "OR" __ r:search_and(depth) node() { r })* {
+                r.into_iter().try_fold(l?, |l, r| Ok(SearchExpr::Or(Box::new(l), Box::new(r?))))
+            }
+
+        /// Parses search expressions separated by `AND` or only by
+        /// whitespace, grouping them from the left.
+        rule search_and(depth: Depth) -> Result<SearchExpr, ParseError>
+            = l:search_not(depth) r:(__ ("AND" __)? r:search_not(depth) node() { r })* {
+                r.into_iter().try_fold(l?, |l, r| Ok(SearchExpr::And(Box::new(l), Box::new(r?))))
+            }
+
+        /// Parses a negated, grouped or single search term.
+        rule search_not(depth: Depth) -> Result<SearchExpr, ParseError>
+            = "NOT" __ nesting(depth.deeper()) e:search_not(depth.deeper()) node() {
+                Ok(SearchExpr::Not(Box::new(e?)))
+            }
+            / "(" _ e:search_or(depth.deeper()) _ ")" { e }
+            / p:search_phrase() node() { p }
+            / w:search_word() node() { Ok(SearchExpr::Term(w.to_owned())) }
+
+        /// Parses a phrase enclosed in double quotes.
+        rule search_phrase() -> Result<SearchExpr, ParseError>
+            = "\"" s:$(phrase_character()*<1,{limits.max_string_length()}>) phrase_end() {
+                Ok(SearchExpr::Phrase(unescape_phrase(s)))
+            }
+
+        /// Matches the closing quote of a phrase, failing with a violation
+        /// when the phrase continues past the length limit.
+        rule phrase_end()
+            = "\""
+            / &phrase_character() {? limits.string_too_long() }
+
+        rule phrase_character()
+            = r"\" ['"' | '\\']
+            / [^'"' | '\\']
+
+        /// Parses a search word, which is anything up to whitespace, a
+        /// parenthesis or a quote other than the `AND`, `OR` and `NOT`
+        /// keywords.
+        rule search_word() -> &'input str
+            = w:$([^' ' | '\t' | '\n' | '\r' | '(' | ')' | '"']+) {?
+                match w {
+                    "AND" | "OR" | "NOT" => Err("search word"),
+                    w => Ok(w),
+                }
+            }
 
         /// Parses a filter expression.
-        rule filter() -> Result<Expr, ParseError>
-            = "not" _ e:filter() { Ok(Expr::Not(Box::new(e?))) }
-            / l:any_expr() _ "or" _ r:filter() { Ok(Expr::Or(Box::new(l?), Box::new(r?))) }
-            / l:any_expr() _ "and" _ r:filter() { Ok(Expr::And(Box::new(l?), Box::new(r?))) }
-            / any_expr()
+        rule filter<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = nesting(depth) e:logical_expr::<N>(depth) { e }
+
+        /// Parses a logical expression, sharing the left hand side between
+        /// the `or`, `and` and plain alternatives so it is only parsed once.
+        rule logical_expr<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = s:pos() "not" _ node() e:filter::<N>(depth.deeper()) end:pos() {
+                Ok(N::build(Span { start: s, end }, Parsed::Not(e?)))
+            }
+            / s:pos() l:any_expr::<N>(depth) r:logical_tail::<N>(depth) end:pos() {
+                Ok(match r? {
+                    LogicalTail::Or(r) => N::build(Span { start: s, end }, Parsed::Or(l?, r)),
+                    LogicalTail::And(r) => N::build(Span { start: s, end }, Parsed::And(l?, r)),
+                    LogicalTail::End => l?,
+                })
+            }
+
+        /// Parses what comes after the left hand side of a logical expression.
+        rule logical_tail<N: Build<'input>>(depth: Depth) -> Result<LogicalTail<N>, ParseError>
+            = _ "or" _ node() r:filter::<N>(depth.deeper()) { Ok(LogicalTail::Or(r?)) }
+            / _ "and" _ node() r:filter::<N>(depth.deeper()) { Ok(LogicalTail::And(r?)) }
+            / { Ok(LogicalTail::End) }
 
         /// Parses any expression, including grouped expressions and value expressions.
-        rule any_expr() -> Result<Expr, ParseError>
-            = "(" _ e:filter() _ ")" { e }
-            / l:value_expr() _ r:after_value_expr() { Ok(match r? {
-                AfterValueExpr::Compare(op, r) => Expr::Compare(Box::new(l?), op, r),
-                AfterValueExpr::In(r) => Expr::In(Box::new(l?), r),
-                AfterValueExpr::End => l?,
-            }) }
+        ///
+        /// Grouped expressions are given the span of their parentheses.
+        rule any_expr<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = s:pos() "(" _ e:filter::<N>(depth.deeper()) _ ")" end:pos() {
+                Ok(e?.group(Span { start: s, end }))
+            }
+            / s:pos() l:value_expr::<N>(depth) r:after_value_expr::<N>(depth) end:pos() {
+                Ok(match r? {
+                    AfterValueExpr::Compare(op, r) => N::build(Span { start: s, end }, Parsed::Compare(l?, op, r)),
+                    AfterValueExpr::In(r) => N::build(Span { start: s, end }, Parsed::In(l?, r)),
+                    AfterValueExpr::End => l?,
+                })
+            }
 
         /// Parses an expression that comes after a value.
-        rule after_value_expr() -> Result<AfterValueExpr, ParseError>
-            = op:comparison_op() _ r:value_expr() { Ok(AfterValueExpr::Compare(op, Box::new(r?))) }
-            / "in" _ "(" _ r:filter_list() _ ")" { Ok(AfterValueExpr::In(r?)) }
+        rule after_value_expr<N: Build<'input>>(depth: Depth) -> Result<AfterValueExpr<N>, ParseError>
+            = _ op:comparison_op() _ node() r:value_expr::<N>(depth) { Ok(AfterValueExpr::Compare(op, r?)) }
+            / _ "in" _ "(" _ node() r:in_list::<N>(depth) _ ")" { Ok(AfterValueExpr::In(r?)) }
             / { Ok(AfterValueExpr::End) }
 
         /// Parses a value expression, which can be a function call, a lambda, a value, an alias, or an identifier.
-        rule value_expr() -> Result<Expr, ParseError>
-            = function_call()
-            / lambda_expr()
-            / v:value() { Ok(Expr::Value(v?)) }
-            / alias_expr()
-            / i:identifier() { Ok(Expr::Identifier(i)) }
+        rule value_expr<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = e:function_call::<N>(depth) { e }
+            / e:lambda_expr::<N>(depth) { e }
+            / s:pos() v:value() end:pos() node() { Ok(N::build(Span { start: s, end }, Parsed::Value(v?))) }
+            / e:alias_expr::<N>() { e }
+            / s:pos() i:path() end:pos() node() { Ok(N::build(Span { start: s, end }, Parsed::Identifier(i))) }
 
         /// Parses a comparison operator.
         rule comparison_op() -> CompareOperator
@@ -77,13 +550,16 @@ peg::parser! {
             / "has" { CompareOperator::Has }
 
         /// Parses a function call with a name and arguments.
-        rule function_call() -> Result<Expr, ParseError>
-            = f:identifier() _ "(" _ l:filter_list() _ ")" { Ok(Expr::Function(f, l?)) }
+        rule function_call<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = s:pos() f:identifier() _ "(" _ node() l:filter_list::<N>(depth) _ ")" end:pos() {
+                Ok(N::build(Span { start: s, end }, Parsed::Function(f, l?)))
+            }
 
         /// Parses a lambda expression (any/all).
-        rule lambda_expr() -> Result<Expr, ParseError>
-            = i:identifier() "/" method:lambda_method() "(" _ v:identifier() _ ":" _ e:filter() _ ")" {
-                Ok(Expr::Lambda(Box::new(Expr::Identifier(i)), method, v, Box::new(e?)))
+        rule lambda_expr<N: Build<'input>>(depth: Depth) -> Result<N, ParseError>
+            = s:pos() i:path() ie:pos() "/" method:lambda_method() "(" lambda_nesting(depth) node() node() _ v:identifier() _ ":" _ e:filter::<N>(depth.lambda()) _ ")" end:pos() {
+                let collection = N::build(Span { start: s, end: ie }, Parsed::Identifier(i));
+                Ok(N::build(Span { start: s, end }, Parsed::Lambda(collection, method, v, e?)))
             }
 
         rule lambda_method() -> LambdaOperator
@@ -91,15 +567,20 @@ peg::parser! {
             / "all" { LambdaOperator::All }
 
         /// Parses a parameter alias.
-        rule alias_expr() -> Result<Expr, ParseError>
-            = "@" i:identifier() { Ok(Expr::Alias(format!("@{}", i))) }
+        rule alias_expr<N: Build<'input>>() -> Result<N, ParseError>
+            = s:pos() a:$("@" identifier()) end:pos() node() { Ok(N::build(Span { start: s, end }, Parsed::Alias(a))) }
+
+        /// Parses a property path of identifiers separated by slashes, such as
+        /// `owner/name`, stopping before a lambda method.
+        rule path() -> &'input str
+            = $(identifier() ("/" !(lambda_method() "(") identifier())*)
 
         /// Parses an identifier.
-        rule identifier() -> String
-            = s:$(['a'..='z'|'A'..='Z'|'_']['a'..='z'|'A'..='Z'|'_'|'0'..='9']+) { s.to_string() }
+        rule identifier() -> &'input str
+            = $(['a'..='z'|'A'..='Z'|'_']['a'..='z'|'A'..='Z'|'_'|'0'..='9']+)
 
         /// Parses a value, which can be a string, datetime, date, time, number, boolean, or null.
-        rule value() -> Result<Value, ParseError>
+        rule value() -> Result<Value<'input>, ParseError>
             = string_value()
             / datetime_value()
             / date_value()
@@ -110,16 +591,16 @@ peg::parser! {
             / v:null_value() { Ok(v) }
 
         /// Parses a boolean value.
-        rule bool_value() -> Value
+        rule bool_value() -> Value<'input>
             = ['t'|'T']['r'|'R']['u'|'U']['e'|'E'] { Value::Bool(true) }
             / ['f'|'F']['a'|'A']['l'|'L']['s'|'S']['e'|'E'] { Value::Bool(false) }
 
         /// Parses a numeric value.
-        rule number_value() -> Result<Value, ParseError>
+        rule number_value() -> Result<Value<'input>, ParseError>
             = n:$(['0'..='9']+ ("." ['0'..='9']*)?) { Ok(Value::Number(BigDecimal::from_str(n).map_err(|_| ParseError::ParsingNumber)?)) }
 
         /// Parses a uuid value.
-        rule uuid_value() -> Result<Value, ParseError>
+        rule uuid_value() -> Result<Value<'input>, ParseError>
             = id:$(hex()*<8> "-" hex()*<4> "-" hex()*<4> "-" hex()*<4> "-" hex()*<12> ) { Ok(Value::Uuid(Uuid::parse_str(id).map_err(|_| ParseError::ParsingUuid)?)) }
 
         /// Parses a single hexadecimal digit.
@@ -137,7 +618,7 @@ peg::parser! {
             }
 
         /// Parses a time value.
-        rule time_value() -> Result<Value, ParseError>
+        rule time_value() -> Result<Value<'input>, ParseError>
             = t:time() { Ok(Value::Time(t?)) }
 
         /// Parses a date value in the format `YYYY-MM-DD`.
@@ -145,7 +626,7 @@ peg::parser! {
             = d:$($(['0'..='9']*<4>) "-" $(['0'..='9']*<2>) "-" $(['0'..='9']*<2>)) { NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| ParseError::ParsingDate) }
 
         /// Parses a date value.
-        rule date_value() -> Result<Value, ParseError>
+        rule date_value() -> Result<Value<'input>, ParseError>
             = d:date() { Ok(Value::Date(d?)) }
 
         /// Parses a named timezone.
@@ -164,41 +645,77 @@ peg::parser! {
             / d:date() "T" t:time() z:timezone_name() { Ok(d?.and_time(t?).and_local_timezone(z?).earliest().ok_or(ParseError::ParsingDateTime)?.to_utc()) }
 
         /// Parses a datetime value.
-        rule datetime_value() -> Result<Value, ParseError>
+        rule datetime_value() -> Result<Value<'input>, ParseError>
             = dt:datetime() { Ok(Value::DateTime(dt?)) }
 
         /// Parses a string value enclosed in single quotes.
-        rule string_value() -> Result<Value, ParseError>
-            = "'" s:quote_escaped_string_content()* "'" { Ok(Value::String(s.into_iter().collect::<Result<Vec<_>, _>>()?.into_iter().collect())) }
-
-        rule quote_escaped_string_content() -> Result<char, ParseError>
-            = r"\" e:escape_character() { e }
-            / c:[^'\''] { Ok(c) }
-
-        rule escape_character() -> Result<char, ParseError>
-            = "'" { Ok('\'') }
-            / "n" { Ok('\n') }
-            / "r" { Ok('\r') }
-            / "t" { Ok('\t') }
-            / r"\" { Ok('\\') }
-            / "u" sequence:$(hex()*<1,8>) {
-                u32::from_str_radix(sequence, 16).ok().and_then(char::from_u32).ok_or(ParseError::ParsingUnicodeCodePoint)
-            }
+        rule string_value() -> Result<Value<'input>, ParseError>
+            = "'" s:$(string_character()*<,{limits.max_string_length()}>) string_end() { Ok(Value::String(unescape(s)?)) }
+
+        /// Matches the closing quote of a string value, failing with a
+        /// violation when the string continues past the length limit.
+        rule string_end()
+            = "'"
+            / &string_character() {? limits.string_too_long() }
+
+        /// Matches one character of a string value, which is decoded by
+        /// `unescape` once the whole value is matched.
+        rule string_character()
+            = r"\" escape_character()
+            / "''"
+            / [^'\'']
+
+        rule escape_character()
+            = ['\'' | 'n' | 'r' | 't' | '\\']
+            / "u" hex()*<1,8>
 
         /// Parses a null value.
-        rule null_value() -> Value
+        rule null_value() -> Value<'input>
             = ['n'|'N']['u'|'U']['l'|'L']['l'|'L'] { Value::Null }
 
         /// Parses a list of value expressions separated by commas.
-        rule value_list() -> Result<Vec<Expr>, ParseError>
-            = v:value_expr() ** ( _ "," _ ) { v.into_iter().collect() }
+        rule value_list<N: Build<'input>>(depth: Depth) -> Result<Vec<N>, ParseError>
+            = v:value_expr::<N>(depth) ** ( _ "," _ ) { v.into_iter().collect() }
 
         /// Parses a list of filter expressions separated by commas.
-        rule filter_list() -> Result<Vec<Expr>, ParseError>
-            = v:filter() ** ( _ "," _ ) { v.into_iter().collect() }
+        rule filter_list<N: Build<'input>>(depth: Depth) -> Result<Vec<N>, ParseError>
+            = v:filter::<N>(depth.deeper()) ** ( _ "," _ ) { v.into_iter().collect() }
+
+        /// Parses the items of an `in` list, failing with a violation when
+        /// the list continues past the size limit.
+        rule in_list<N: Build<'input>>(depth: Depth) -> Result<Vec<N>, ParseError>
+            = v:filter::<N>(depth.deeper()) **<,{limits.max_in_list()}> ( _ "," _ ) in_list_end() { v.into_iter().collect() }
+
+        rule in_list_end()
+            = &( _ "," ) {? limits.in_list_too_large() }
+            / { }
+
+        /// Checks that the nesting depth is within limits.
+        rule nesting(depth: Depth)
+            = {? limits.check_nesting(depth) }
+
+        /// Checks that another lambda can be nested at this depth.
+        rule lambda_nesting(depth: Depth)
+            = {? limits.check_lambda_nesting(depth) }
+
+        /// Starts counting against the limits from scratch.
+        rule reset()
+            = {? limits.reset() }
+
+        /// Counts one node of the expression tree against the node limit.
+        rule node()
+            = {? limits.count_node() }
+
+        /// Returns the current position in the untrimmed query.
+        rule pos() -> usize
+            = p:position!() { p + limits.offset }
 
         /// Matches zero or more whitespace characters.
         rule _()
             = [' '|'\t'|'\n'|'\r']*
+
+        /// Matches one or more whitespace characters.
+        rule __()
+            = [' '|'\t'|'\n'|'\r']+
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,202 @@
+use super::visit::{prefixes, root};
+use super::{CompareOperator, Expr, FilterPolicy, LambdaOperator, PolicyViolation, Visitor};
+
+impl Expr {
+    /// Checks the expression against a `FilterPolicy`.
+    ///
+    /// A `Result` which is `Ok(())` if the expression only uses what the
+    /// policy allows, or an `Err` with every distinct `PolicyViolation` found,
+    /// in the order they appear in the expression.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, FilterPolicy, LambdaOperator, PolicyViolation};
+    ///
+    /// let policy = FilterPolicy {
+    ///     lambda_operators: Some([LambdaOperator::Any].into()),
+    ///     ..FilterPolicy::default()
+    /// };
+    ///
+    /// let expr = parse_str("tags/all(tag: tag ne 'hidden')").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     expr.check_policy(&policy),
+    ///     Err(vec![PolicyViolation::LambdaNotAllowed {
+    ///         operator: LambdaOperator::All,
+    ///     }]),
+    /// );
+    /// ```
+    pub fn check_policy(&self, policy: &FilterPolicy) -> Result<(), Vec<PolicyViolation>> {
+        let mut checker = PolicyChecker {
+            policy,
+            scope: Vec::new(),
+            violations: Vec::new(),
+        };
+
+        checker.visit_expr(self);
+
+        if checker.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(checker.violations)
+        }
+    }
+}
+
+struct PolicyChecker<'a> {
+    policy: &'a FilterPolicy,
+
+    /// Lambda variables in scope along with the path of the collection they
+    /// range over, if it is a property path.
+    scope: Vec<(&'a str, Option<String>)>,
+
+    violations: Vec<PolicyViolation>,
+}
+
+impl<'a> Visitor<'a> for PolicyChecker<'a> {
+    fn visit_compare(&mut self, lhs: &'a Expr, op: &'a CompareOperator, rhs: &'a Expr) {
+        if !self.is_operator_allowed(op) {
+            self.report(PolicyViolation::OperatorNotAllowed {
+                operator: op.clone(),
+            });
+        }
+
+        for side in [lhs, rhs] {
+            if let Some(identifier) = self.compared_identifier(side) {
+                if !self.is_operator_allowed_on(&identifier, op) {
+                    self.report(PolicyViolation::OperatorNotAllowedOn {
+                        identifier,
+                        operator: op.clone(),
+                    });
+                }
+            }
+        }
+
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    fn visit_in(&mut self, lhs: &'a Expr, values: &'a [Expr]) {
+        // An `in` list is a shorthand for `eq` comparisons joined by `or`.
+        let op = CompareOperator::Equal;
+
+        if !self.is_operator_allowed(&op) {
+            self.report(PolicyViolation::InNotAllowed);
+        }
+
+        if let Some(identifier) = self.compared_identifier(lhs) {
+            if !self.is_operator_allowed_on(&identifier, &op) {
+                self.report(PolicyViolation::InNotAllowedOn { identifier });
+            }
+        }
+
+        self.visit_expr(lhs);
+
+        for value in values {
+            self.visit_expr(value);
+        }
+    }
+
+    fn visit_function(&mut self, name: &'a str, args: &'a [Expr]) {
+        let is_allowed = self
+            .policy
+            .functions
+            .as_ref()
+            .is_none_or(|functions| functions.contains(name));
+
+        if !is_allowed {
+            self.report(PolicyViolation::FunctionNotAllowed {
+                name: name.to_owned(),
+            });
+        }
+
+        for arg in args {
+            self.visit_expr(arg);
+        }
+    }
+
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        op: &'a LambdaOperator,
+        variable: &'a str,
+        body: &'a Expr,
+    ) {
+        let is_allowed = self
+            .policy
+            .lambda_operators
+            .as_ref()
+            .is_none_or(|operators| operators.contains(op));
+
+        if !is_allowed {
+            self.report(PolicyViolation::LambdaNotAllowed {
+                operator: op.clone(),
+            });
+        }
+
+        self.visit_expr(collection);
+
+        let resolved = self.compared_identifier(collection);
+        self.scope.push((variable, resolved));
+        self.visit_expr(body);
+        self.scope.pop();
+    }
+
+    fn visit_identifier(&mut self, name: &'a str) {
+        let Some(identifier) = self.resolve(name) else {
+            return;
+        };
+
+        // An identifier is not filterable along with everything under it.
+        let is_filterable =
+            prefixes(&identifier).all(|prefix| !self.policy.non_filterable.contains(prefix));
+
+        if !is_filterable {
+            self.report(PolicyViolation::IdentifierNotFilterable { identifier });
+        }
+    }
+}
+
+impl<'a> PolicyChecker<'a> {
+    /// Resolves the identifier an operand refers to, looking through lambda
+    /// variables to the collection they range over.
+    fn compared_identifier(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Identifier(name) => self.resolve(name),
+            _ => None,
+        }
+    }
+
+    /// Resolves a path starting with a lambda variable to the same path under
+    /// the collection the variable ranges over, or returns any other path
+    /// unchanged.
+    fn resolve(&self, name: &str) -> Option<String> {
+        let variable = root(name);
+
+        match self.scope.iter().rev().find(|(var, _)| *var == variable) {
+            Some((_, collection)) => collection
+                .as_ref()
+                .map(|collection| format!("{collection}{}", &name[variable.len()..])),
+            None => Some(name.to_owned()),
+        }
+    }
+
+    fn is_operator_allowed(&self, op: &CompareOperator) -> bool {
+        self.policy
+            .operators
+            .as_ref()
+            .is_none_or(|operators| operators.contains(op))
+    }
+
+    fn is_operator_allowed_on(&self, identifier: &str, op: &CompareOperator) -> bool {
+        self.policy
+            .identifier_operators
+            .get(identifier)
+            .is_none_or(|operators| operators.contains(op))
+    }
+
+    fn report(&mut self, violation: PolicyViolation) {
+        if !self.violations.contains(&violation) {
+            self.violations.push(violation);
+        }
+    }
+}
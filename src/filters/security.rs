@@ -0,0 +1,125 @@
+use super::visit::{prefixes, root};
+use super::{Expr, LambdaOperator, RowFilter, SecurityViolation, Visitor};
+use std::collections::HashSet;
+
+impl RowFilter {
+    /// Creates a row filter protecting the identifiers of its predicate, so
+    /// that user filters cannot probe them.
+    pub fn new(predicate: Expr) -> Self {
+        let protected = predicate.identifiers().map(str::to_owned).collect();
+
+        Self {
+            predicate,
+            protected,
+        }
+    }
+
+    /// Adds an identifier user filters may not mention.
+    pub fn with_protected(mut self, identifier: impl Into<String>) -> Self {
+        self.protected.insert(identifier.into());
+        self
+    }
+
+    /// Restricts a user filter to the rows matching the predicate.
+    ///
+    /// The predicate is joined first with `and`, and the user filter kept as
+    /// a single grouped operand, so nothing within it can bind across the
+    /// `and`. With no user filter, the predicate is returned alone.
+    ///
+    /// A `Result` which is the merged expression, or an `Err` with every
+    /// distinct `SecurityViolation` found in the user filter, in the order
+    /// they appear. User filters are rejected when they mention a protected
+    /// identifier or a path under one, also when reached through a lambda
+    /// variable, or use a parameter alias since its value could change the
+    /// meaning of the filter once substituted.
+    pub fn merge(&self, user: Option<Expr>) -> Result<Expr, Vec<SecurityViolation>> {
+        let Some(user) = user else {
+            return Ok(self.predicate.clone());
+        };
+
+        let mut checker = SecurityChecker {
+            protected: &self.protected,
+            scope: Vec::new(),
+            violations: Vec::new(),
+        };
+
+        checker.visit_expr(&user);
+
+        if checker.violations.is_empty() {
+            Ok(self.predicate.clone().and_with(user))
+        } else {
+            Err(checker.violations)
+        }
+    }
+}
+
+struct SecurityChecker<'a> {
+    protected: &'a HashSet<String>,
+
+    /// Lambda variables in scope along with the path of the collection they
+    /// range over, if it is a property path.
+    scope: Vec<(&'a str, Option<String>)>,
+
+    violations: Vec<SecurityViolation>,
+}
+
+impl<'a> Visitor<'a> for SecurityChecker<'a> {
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        _op: &'a LambdaOperator,
+        variable: &'a str,
+        body: &'a Expr,
+    ) {
+        self.visit_expr(collection);
+
+        let resolved = match collection {
+            Expr::Identifier(name) => self.resolve(name),
+            _ => None,
+        };
+        self.scope.push((variable, resolved));
+        self.visit_expr(body);
+        self.scope.pop();
+    }
+
+    fn visit_identifier(&mut self, name: &'a str) {
+        let Some(path) = self.resolve(name) else {
+            return;
+        };
+
+        // A path is protected along with everything under it.
+        let is_protected = prefixes(&path).any(|prefix| self.protected.contains(prefix));
+
+        if is_protected {
+            self.report(SecurityViolation::ProtectedIdentifier { identifier: path });
+        }
+    }
+
+    fn visit_alias(&mut self, name: &'a str) {
+        self.report(SecurityViolation::AliasNotAllowed {
+            alias: name.to_owned(),
+        });
+    }
+}
+
+impl SecurityChecker<'_> {
+    /// Resolves a path starting with a lambda variable to the same path under
+    /// the collection the variable ranges over, or returns any other path
+    /// unchanged.
+    fn resolve(&self, name: &str) -> Option<String> {
+        let variable = root(name);
+
+        match self.scope.iter().rev().find(|(var, _)| *var == variable) {
+            Some((_, collection)) => collection
+                .as_ref()
+                .map(|collection| format!("{collection}{}", &name[variable.len()..])),
+            None => Some(name.to_owned()),
+        }
+    }
+
+    fn report(&mut self, violation: SecurityViolation) {
+        if !self.violations.contains(&violation) {
+            self.violations.push(violation);
+        }
+    }
+}
@@ -0,0 +1,743 @@
+use super::{CompareOperator, Expr, LambdaOperator, NormalFormError, Value};
+use std::cmp::Ordering;
+
+impl Expr {
+    /// Rewrites the expression into a smaller, equivalent one.
+    ///
+    /// The rewrites follow the OData semantics, where comparing with `null`
+    /// yields `false` rather than an unknown value:
+    ///
+    /// - Constant operands are folded, such as `true and X` into `X` or
+    ///   `1 eq 2` into `false`.
+    /// - `not` is pushed down with De Morgan's laws and cancels out in
+    ///   `not not X`. It is kept in front of ordering comparisons, since
+    ///   `not (a gt 1)` also holds when `a` is `null` while `a le 1` does not.
+    /// - Nested `and` and `or` are flattened, and repeated or absorbed terms
+    ///   removed, as in `X and (X or Y)` into `X`.
+    /// - Equality tests on the same identifier joined by `or` are merged into
+    ///   an `in` list, as in `a eq 1 or a eq 2` into `a in (1, 2)`.
+    /// - Bounds on the same identifier are collapsed, as in
+    ///   `a gt 1 and a gt 5` into `a gt 5` or `a gt 5 and a lt 1` into `false`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, to_query_string};
+    ///
+    /// let expr = parse_str("(age gt 18 and age ge 21) and (name eq 'Bob' or name eq 'Alice')")
+    ///     .expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     to_query_string(&expr.simplify()).expect("valid filter"),
+    ///     "age ge 21 and name in ('Bob', 'Alice')",
+    /// );
+    /// ```
+    pub fn simplify(self) -> Expr {
+        simplify(self)
+    }
+
+    /// Converts the expression to a conjunctive normal form, an `and` of
+    /// `or` clauses, after simplifying it.
+    ///
+    /// A `Result` which is the converted expression, or a
+    /// `NormalFormError::TooManyClauses` if it would have more than
+    /// `max_clauses` clauses, since the conversion may grow exponentially.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, to_query_string, NormalFormError};
+    ///
+    /// let expr = parse_str("(aa eq 1 and bb eq 2) or cc eq 3").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     to_query_string(&expr.clone().to_cnf(8).expect("small enough")).expect("valid filter"),
+    ///     "(aa eq 1 or cc eq 3) and (bb eq 2 or cc eq 3)",
+    /// );
+    /// assert_eq!(expr.to_cnf(1), Err(NormalFormError::TooManyClauses { max: 1 }));
+    /// ```
+    pub fn to_cnf(self, max_clauses: usize) -> Result<Expr, NormalFormError> {
+        normal_form(self, Junction::And, max_clauses)
+    }
+
+    /// Converts the expression to a disjunctive normal form, an `or` of
+    /// `and` clauses, after simplifying it.
+    ///
+    /// A `Result` which is the converted expression, or a
+    /// `NormalFormError::TooManyClauses` if it would have more than
+    /// `max_clauses` clauses, since the conversion may grow exponentially.
+    pub fn to_dnf(self, max_clauses: usize) -> Result<Expr, NormalFormError> {
+        normal_form(self, Junction::Or, max_clauses)
+    }
+}
+
+/// A logical operator joining terms.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(super) enum Junction {
+    And,
+    Or,
+}
+
+impl Junction {
+    fn of(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::And(..) => Some(Self::And),
+            Expr::Or(..) => Some(Self::Or),
+            _ => None,
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::And => Self::Or,
+            Self::Or => Self::And,
+        }
+    }
+
+    /// The value of an empty junction, which can be left out of it.
+    fn identity(self) -> bool {
+        self == Self::And
+    }
+
+    fn join(self, lhs: Expr, rhs: Expr) -> Expr {
+        match self {
+            Self::And => Expr::And(Box::new(lhs), Box::new(rhs)),
+            Self::Or => Expr::Or(Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    /// Joins terms the way the parser nests them, from the right.
+    pub(super) fn join_all(self, terms: Vec<Expr>) -> Expr {
+        terms
+            .into_iter()
+            .rev()
+            .reduce(|rhs, lhs| self.join(lhs, rhs))
+            .unwrap_or(Expr::Value(Value::Bool(self.identity())))
+    }
+}
+
+fn simplify(expr: Expr) -> Expr {
+    match expr {
+        Expr::Or(lhs, rhs) => simplify_junction(Junction::Or, *lhs, *rhs),
+        Expr::And(lhs, rhs) => simplify_junction(Junction::And, *lhs, *rhs),
+
+        // Negating may turn a junction into the other one, whose terms can
+        // then be merged again.
+        Expr::Not(inner) => match negate(simplify(*inner)) {
+            expr @ (Expr::And(..) | Expr::Or(..) | Expr::Lambda(..)) => simplify(expr),
+            expr => expr,
+        },
+
+        Expr::Compare(lhs, op, rhs) => simplify_compare(simplify(*lhs), op, simplify(*rhs)),
+
+        Expr::In(lhs, values) => {
+            let lhs = simplify(*lhs);
+            let mut list: Vec<Expr> = Vec::with_capacity(values.len());
+
+            for value in values.into_iter().map(simplify) {
+                if !list.contains(&value) {
+                    list.push(value);
+                }
+            }
+
+            match list.len() {
+                0 => Expr::Value(Value::Bool(false)),
+                1 => simplify_compare(lhs, CompareOperator::Equal, list.remove(0)),
+                _ => Expr::In(Box::new(lhs), list),
+            }
+        }
+
+        Expr::Function(name, args) => {
+            Expr::Function(name, args.into_iter().map(simplify).collect())
+        }
+
+        Expr::Lambda(collection, op, variable, body) => match (op, simplify(*body)) {
+            // No member can match, even when there are some.
+            (LambdaOperator::Any, Expr::Value(Value::Bool(false))) => {
+                Expr::Value(Value::Bool(false))
+            }
+
+            // Every member matches, even when there are none.
+            (LambdaOperator::All, Expr::Value(Value::Bool(true))) => Expr::Value(Value::Bool(true)),
+
+            (op, body) => Expr::Lambda(collection, op, variable, Box::new(body)),
+        },
+
+        expr @ (Expr::Identifier(_) | Expr::Alias(_) | Expr::Value(_)) => expr,
+    }
+}
+
+/// Negates a simplified expression, pushing the negation as far down as it
+/// can go.
+fn negate(expr: Expr) -> Expr {
+    match expr {
+        Expr::Or(lhs, rhs) => Expr::And(Box::new(negate(*lhs)), Box::new(negate(*rhs))),
+        Expr::And(lhs, rhs) => Expr::Or(Box::new(negate(*lhs)), Box::new(negate(*rhs))),
+        Expr::Not(inner) => *inner,
+        Expr::Value(Value::Bool(value)) => Expr::Value(Value::Bool(!value)),
+
+        // Unlike ordering comparisons, equality comparisons are never false
+        // both ways, even with `null`.
+        Expr::Compare(lhs, CompareOperator::Equal, rhs) => {
+            Expr::Compare(lhs, CompareOperator::NotEqual, rhs)
+        }
+        Expr::Compare(lhs, CompareOperator::NotEqual, rhs) => {
+            Expr::Compare(lhs, CompareOperator::Equal, rhs)
+        }
+
+        Expr::Lambda(collection, LambdaOperator::Any, variable, body) => Expr::Lambda(
+            collection,
+            LambdaOperator::All,
+            variable,
+            Box::new(negate(*body)),
+        ),
+        Expr::Lambda(collection, LambdaOperator::All, variable, body) => Expr::Lambda(
+            collection,
+            LambdaOperator::Any,
+            variable,
+            Box::new(negate(*body)),
+        ),
+
+        expr => Expr::Not(Box::new(expr)),
+    }
+}
+
+fn simplify_junction(kind: Junction, lhs: Expr, rhs: Expr) -> Expr {
+    let mut terms = Vec::new();
+
+    flatten(kind, simplify(lhs), &mut terms);
+    flatten(kind, simplify(rhs), &mut terms);
+
+    combine(kind, terms)
+}
+
+/// Collects the terms of nested junctions of the same kind.
+pub(super) fn flatten(kind: Junction, expr: Expr, terms: &mut Vec<Expr>) {
+    match expr {
+        Expr::And(lhs, rhs) if kind == Junction::And => {
+            flatten(kind, *lhs, terms);
+            flatten(kind, *rhs, terms);
+        }
+        Expr::Or(lhs, rhs) if kind == Junction::Or => {
+            flatten(kind, *lhs, terms);
+            flatten(kind, *rhs, terms);
+        }
+        expr => terms.push(expr),
+    }
+}
+
+/// Joins simplified terms, leaving out constants and repeated terms.
+fn combine(kind: Junction, terms: Vec<Expr>) -> Expr {
+    let absorbing = Expr::Value(Value::Bool(!kind.identity()));
+    let mut kept: Vec<Expr> = Vec::with_capacity(terms.len());
+
+    for term in terms {
+        match term {
+            Expr::Value(Value::Bool(value)) if value == kind.identity() => continue,
+            Expr::Value(Value::Bool(_)) => return absorbing,
+            _ => {}
+        }
+
+        if kept.contains(&term) {
+            continue;
+        }
+
+        // `X and not X` is always false, and `X or not X` always true.
+        if kept.iter().any(|other| negate(other.clone()) == term) {
+            return absorbing;
+        }
+
+        kept.push(term);
+    }
+
+    // `X and (X or Y)` is `X`, and `X or (X and Y)` is `X` as well.
+    let absorbed: Vec<bool> = kept
+        .iter()
+        .map(|term| {
+            let mut inner = Vec::new();
+
+            if Junction::of(term) == Some(kind.other()) {
+                flatten(kind.other(), term.clone(), &mut inner);
+            }
+
+            inner.iter().any(|inner| kept.contains(inner))
+        })
+        .collect();
+
+    let kept = kept
+        .into_iter()
+        .zip(absorbed)
+        .filter_map(|(term, absorbed)| (!absorbed).then_some(term))
+        .collect();
+
+    let merged = match kind {
+        Junction::And => merge_groups(kept, bound, merge_conjunction),
+        Junction::Or => merge_groups(kept, equality_or_bound, merge_disjunction),
+    };
+
+    match merged {
+        Some(terms) if terms.len() < 2 => terms
+            .into_iter()
+            .next()
+            .unwrap_or(Expr::Value(Value::Bool(kind.identity()))),
+        Some(terms) => kind.join_all(terms),
+        None => absorbing,
+    }
+}
+
+fn simplify_compare(lhs: Expr, op: CompareOperator, rhs: Expr) -> Expr {
+    // Keep constants on the right, as in `a gt 5` rather than `5 lt a`.
+    let (lhs, op, rhs) = match (&lhs, &rhs, mirror(&op)) {
+        (Expr::Value(_), rhs_expr, Some(mirrored)) if !matches!(rhs_expr, Expr::Value(_)) => {
+            (rhs, mirrored, lhs)
+        }
+        _ => (lhs, op, rhs),
+    };
+
+    if let (Expr::Value(lhs), Expr::Value(rhs)) = (&lhs, &rhs) {
+        if let Some(result) = evaluate(lhs, &op, rhs) {
+            return Expr::Value(Value::Bool(result));
+        }
+    }
+
+    // An identifier always equals itself, `null` included.
+    if lhs == rhs && matches!(lhs, Expr::Identifier(_) | Expr::Alias(_)) {
+        match op {
+            CompareOperator::Equal => return Expr::Value(Value::Bool(true)),
+            CompareOperator::NotEqual
+            | CompareOperator::GreaterThan
+            | CompareOperator::LessThan => return Expr::Value(Value::Bool(false)),
+            _ => {}
+        }
+    }
+
+    Expr::Compare(Box::new(lhs), op, Box::new(rhs))
+}
+
+/// The operator giving the same result with its operands swapped.
+pub(super) fn mirror(op: &CompareOperator) -> Option<CompareOperator> {
+    match op {
+        CompareOperator::Equal => Some(CompareOperator::Equal),
+        CompareOperator::NotEqual => Some(CompareOperator::NotEqual),
+        CompareOperator::GreaterThan => Some(CompareOperator::LessThan),
+        CompareOperator::GreaterOrEqual => Some(CompareOperator::LessOrEqual),
+        CompareOperator::LessThan => Some(CompareOperator::GreaterThan),
+        CompareOperator::LessOrEqual => Some(CompareOperator::GreaterOrEqual),
+        CompareOperator::Has => None,
+    }
+}
+
+/// Compares two constants, or returns `None` when the result is unknown.
+fn evaluate(lhs: &Value, op: &CompareOperator, rhs: &Value) -> Option<bool> {
+    match op {
+        CompareOperator::Equal => Some(lhs == rhs),
+        CompareOperator::NotEqual => Some(lhs != rhs),
+        CompareOperator::Has => None,
+        _ if *lhs == Value::Null || *rhs == Value::Null => None,
+        _ => order(lhs, rhs).map(|ordering| satisfies(ordering, op)),
+    }
+}
+
+/// Orders two constants of the same type.
+pub(super) fn order(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Bool(lhs), Value::Bool(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Number(lhs), Value::Number(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Uuid(lhs), Value::Uuid(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::DateTime(lhs), Value::DateTime(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Date(lhs), Value::Date(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Time(lhs), Value::Time(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    }
+}
+
+/// Whether `lhs op rhs` holds given how `lhs` orders against `rhs`.
+pub(super) fn satisfies(ordering: Ordering, op: &CompareOperator) -> bool {
+    match op {
+        CompareOperator::Equal => ordering.is_eq(),
+        CompareOperator::NotEqual => ordering.is_ne(),
+        CompareOperator::GreaterThan => ordering.is_gt(),
+        CompareOperator::GreaterOrEqual => ordering.is_ge(),
+        CompareOperator::LessThan => ordering.is_lt(),
+        CompareOperator::LessOrEqual => ordering.is_le(),
+        CompareOperator::Has => false,
+    }
+}
+
+/// The identifier of a comparison with a non-null constant that can be
+/// merged with other ones on the same identifier.
+fn bound(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Compare(lhs, op, rhs) => match (lhs.as_ref(), op, rhs.as_ref()) {
+            (_, CompareOperator::NotEqual | CompareOperator::Has, _) => None,
+            (_, _, Expr::Value(Value::Null)) => None,
+            (Expr::Identifier(name), _, Expr::Value(_)) => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Like `bound`, but also accepting `in` lists of constants.
+fn equality_or_bound(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::In(lhs, values) => match lhs.as_ref() {
+            Expr::Identifier(name) if values.iter().all(|v| matches!(v, Expr::Value(_))) => {
+                Some(name)
+            }
+            _ => None,
+        },
+        expr => bound(expr),
+    }
+}
+
+/// The outcome of merging terms on the same identifier.
+enum Merged {
+    /// Terms replacing the merged ones.
+    Terms(Vec<Expr>),
+
+    /// The terms can never hold together.
+    Never,
+
+    /// The terms compare with constants of different types, and are kept.
+    Unchanged,
+}
+
+/// Replaces the terms sharing an identifier, as returned by `key`, with the
+/// terms returned by `merge` at the position of the first one.
+///
+/// `None` is returned as soon as `merge` finds the terms can never hold.
+fn merge_groups(
+    terms: Vec<Expr>,
+    key: fn(&Expr) -> Option<&str>,
+    merge: fn(&[Expr]) -> Merged,
+) -> Option<Vec<Expr>> {
+    let keys: Vec<Option<String>> = terms
+        .iter()
+        .map(|term| key(term).map(str::to_owned))
+        .collect();
+    let mut terms: Vec<Option<Expr>> = terms.into_iter().map(Some).collect();
+    let mut merged = Vec::with_capacity(terms.len());
+
+    for index in 0..terms.len() {
+        let Some(term) = terms[index].take() else {
+            continue;
+        };
+
+        let Some(name) = &keys[index] else {
+            merged.push(term);
+            continue;
+        };
+
+        let mut group = vec![term];
+
+        for other in index + 1..terms.len() {
+            if keys[other].as_ref() == Some(name) {
+                group.extend(terms[other].take());
+            }
+        }
+
+        if group.len() == 1 {
+            merged.extend(group);
+            continue;
+        }
+
+        match merge(&group) {
+            Merged::Terms(terms) => merged.extend(terms),
+            Merged::Never => return None,
+            Merged::Unchanged => merged.extend(group),
+        }
+    }
+
+    Some(merged)
+}
+
+/// A lower or upper limit, along with whether it is included.
+type Limit<'a> = (&'a Value, bool);
+
+/// The operator and constant of a term accepted by `bound`, along with the
+/// compared identifier.
+fn parts(expr: &Expr) -> (&Expr, &CompareOperator, &Value) {
+    match expr {
+        Expr::Compare(lhs, op, rhs) => match rhs.as_ref() {
+            Expr::Value(value) => (lhs, op, value),
+            _ => unreachable!("bounds compare with a constant"),
+        },
+        _ => unreachable!("bounds are comparisons"),
+    }
+}
+
+/// Picks between two limits, preferring the one ordering as `prefer` against
+/// the other. On ties, the limit is inclusive if both are when `tight`, or if
+/// either is otherwise.
+fn pick<'a>(
+    current: Option<Limit<'a>>,
+    candidate: Limit<'a>,
+    prefer: Ordering,
+    tight: bool,
+) -> Option<Limit<'a>> {
+    let Some(current) = current else {
+        return Some(candidate);
+    };
+
+    Some(match order(candidate.0, current.0)? {
+        Ordering::Equal if tight => (current.0, current.1 && candidate.1),
+        Ordering::Equal => (current.0, current.1 || candidate.1),
+        ordering if ordering == prefer => candidate,
+        _ => current,
+    })
+}
+
+/// Whether a constant is within a lower or upper limit.
+fn within(value: &Value, (limit, inclusive): Limit, side: Ordering) -> Option<bool> {
+    Some(match order(value, limit)? {
+        Ordering::Equal => inclusive,
+        ordering => ordering == side,
+    })
+}
+
+fn compare(lhs: &Expr, op: CompareOperator, value: &Value) -> Expr {
+    Expr::Compare(
+        Box::new(lhs.clone()),
+        op,
+        Box::new(Expr::Value(value.clone())),
+    )
+}
+
+fn lower_bound(lhs: &Expr, (value, inclusive): Limit) -> Expr {
+    match inclusive {
+        true => compare(lhs, CompareOperator::GreaterOrEqual, value),
+        false => compare(lhs, CompareOperator::GreaterThan, value),
+    }
+}
+
+fn upper_bound(lhs: &Expr, (value, inclusive): Limit) -> Expr {
+    match inclusive {
+        true => compare(lhs, CompareOperator::LessOrEqual, value),
+        false => compare(lhs, CompareOperator::LessThan, value),
+    }
+}
+
+/// Collapses comparisons on the same identifier joined by `and` into the
+/// tightest range.
+fn merge_conjunction(group: &[Expr]) -> Merged {
+    let Some(range) = intersect(group) else {
+        return Merged::Unchanged;
+    };
+
+    let Some((equal, lower, upper)) = range else {
+        return Merged::Never;
+    };
+
+    let (lhs, _, _) = parts(&group[0]);
+    let mut terms = Vec::new();
+
+    if let Some(value) = equal {
+        terms.push(compare(lhs, CompareOperator::Equal, value));
+    }
+
+    terms.extend(lower.map(|limit| lower_bound(lhs, limit)));
+    terms.extend(upper.map(|limit| upper_bound(lhs, limit)));
+
+    Merged::Terms(terms)
+}
+
+/// The range allowed by all terms, `Some(None)` if it is empty, or `None` if
+/// the constants cannot be ordered against each other.
+#[allow(clippy::type_complexity)]
+fn intersect(
+    group: &[Expr],
+) -> Option<Option<(Option<&Value>, Option<Limit<'_>>, Option<Limit<'_>>)>> {
+    let mut equal: Option<&Value> = None;
+    let mut lower = None;
+    let mut upper = None;
+
+    for term in group {
+        let (_, op, value) = parts(term);
+
+        match op {
+            CompareOperator::Equal => {
+                if let Some(other) = equal {
+                    if order(value, other)?.is_ne() {
+                        return Some(None);
+                    }
+                }
+
+                equal = Some(value);
+            }
+
+            CompareOperator::GreaterThan | CompareOperator::GreaterOrEqual => {
+                let inclusive = *op == CompareOperator::GreaterOrEqual;
+                lower = Some(pick(lower, (value, inclusive), Ordering::Greater, true)?);
+            }
+
+            _ => {
+                let inclusive = *op == CompareOperator::LessOrEqual;
+                upper = Some(pick(upper, (value, inclusive), Ordering::Less, true)?);
+            }
+        }
+    }
+
+    if let Some(value) = equal {
+        for (limit, side) in [(lower, Ordering::Greater), (upper, Ordering::Less)] {
+            if let Some(limit) = limit {
+                if !within(value, limit, side)? {
+                    return Some(None);
+                }
+            }
+        }
+
+        return Some(Some((equal, None, None)));
+    }
+
+    if let (Some((low, low_inclusive)), Some((high, high_inclusive))) = (lower, upper) {
+        match order(low, high)? {
+            Ordering::Greater => return Some(None),
+            Ordering::Equal if low_inclusive && high_inclusive => {
+                return Some(Some((Some(low), None, None)));
+            }
+            Ordering::Equal => return Some(None),
+            Ordering::Less => {}
+        }
+    }
+
+    Some(Some((None, lower, upper)))
+}
+
+/// Merges equality tests on the same identifier joined by `or` into an `in`
+/// list, and keeps the loosest of the bounds in each direction.
+fn merge_disjunction(group: &[Expr]) -> Merged {
+    let mut values: Vec<&Value> = Vec::new();
+    let mut lower = None;
+    let mut upper = None;
+    let mut lhs = None;
+
+    for term in group {
+        let (term_lhs, op, value) = match term {
+            Expr::In(term_lhs, list) => {
+                for value in list {
+                    if let Expr::Value(value) = value {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                }
+
+                lhs = Some(term_lhs.as_ref());
+                continue;
+            }
+            term => parts(term),
+        };
+
+        lhs = Some(term_lhs);
+
+        let limit = match op {
+            CompareOperator::Equal => {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+                continue;
+            }
+            CompareOperator::GreaterThan | CompareOperator::GreaterOrEqual => {
+                let inclusive = *op == CompareOperator::GreaterOrEqual;
+                pick(lower, (value, inclusive), Ordering::Less, false).map(|l| lower = Some(l))
+            }
+            _ => {
+                let inclusive = *op == CompareOperator::LessOrEqual;
+                pick(upper, (value, inclusive), Ordering::Greater, false).map(|l| upper = Some(l))
+            }
+        };
+
+        if limit.is_none() {
+            return Merged::Unchanged;
+        }
+    }
+
+    // Leave out the values already matched by one of the bounds.
+    let mut kept = Vec::with_capacity(values.len());
+
+    for value in values {
+        let mut covered = false;
+
+        for (limit, side) in [(lower, Ordering::Greater), (upper, Ordering::Less)] {
+            if let Some(limit) = limit {
+                match within(value, limit, side) {
+                    Some(is_within) => covered |= is_within,
+                    None => return Merged::Unchanged,
+                }
+            }
+        }
+
+        if !covered {
+            kept.push(value);
+        }
+    }
+
+    let Some(lhs) = lhs else {
+        return Merged::Unchanged;
+    };
+
+    let mut terms = Vec::new();
+
+    match kept.as_slice() {
+        [] => {}
+        [value] => terms.push(compare(lhs, CompareOperator::Equal, value)),
+        values => terms.push(Expr::In(
+            Box::new(lhs.clone()),
+            values
+                .iter()
+                .map(|value| Expr::Value((*value).clone()))
+                .collect(),
+        )),
+    }
+
+    terms.extend(lower.map(|limit| lower_bound(lhs, limit)));
+    terms.extend(upper.map(|limit| upper_bound(lhs, limit)));
+
+    Merged::Terms(terms)
+}
+
+fn normal_form(expr: Expr, outer: Junction, max_clauses: usize) -> Result<Expr, NormalFormError> {
+    let clauses = clauses(&simplify(expr), outer, max_clauses)?;
+
+    let clauses = clauses
+        .into_iter()
+        .map(|clause| outer.other().join_all(clause))
+        .collect();
+
+    Ok(simplify(outer.join_all(clauses)))
+}
+
+/// Distributes the expression into clauses joined by `outer`, each made of
+/// terms joined by the other junction.
+fn clauses(
+    expr: &Expr,
+    outer: Junction,
+    max_clauses: usize,
+) -> Result<Vec<Vec<Expr>>, NormalFormError> {
+    let (lhs, rhs) = match expr {
+        Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => (lhs, rhs),
+        expr => return Ok(vec![vec![expr.clone()]]),
+    };
+
+    let lhs = clauses(lhs, outer, max_clauses)?;
+    let rhs = clauses(rhs, outer, max_clauses)?;
+
+    let clauses: Vec<Vec<Expr>> = if Junction::of(expr) == Some(outer) {
+        if lhs.len() + rhs.len() > max_clauses {
+            return Err(NormalFormError::TooManyClauses { max: max_clauses });
+        }
+
+        lhs.into_iter().chain(rhs).collect()
+    } else {
+        if lhs.len() * rhs.len() > max_clauses {
+            return Err(NormalFormError::TooManyClauses { max: max_clauses });
+        }
+
+        lhs.iter()
+            .flat_map(|lhs| {
+                rhs.iter()
+                    .map(move |rhs| lhs.iter().chain(rhs).cloned().collect())
+            })
+            .collect()
+    };
+
+    Ok(clauses)
+}
@@ -0,0 +1,129 @@
+use super::validate::{validate_scoped, Node, Validate};
+use super::{
+    Expr, FunctionsTypeMap, IdentifiersTypeMap, Span, SpannedExpr, SpannedExprKind,
+    SpannedValidationError, Type, TypedExpr,
+};
+use std::fmt;
+use std::ops::Range;
+
+impl Span {
+    /// The byte range of the span, for slicing the `$filter` string.
+    pub fn range(self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.range()
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+impl SpannedExpr {
+    /// Drops the spans, converting the tree into a plain `Expr`.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, parse_str_spanned};
+    ///
+    /// let filter = "tags/any(tag: tag eq 'vip') and age gt 30";
+    /// let expr = parse_str_spanned(filter).expect("valid filter tree");
+    ///
+    /// assert_eq!(expr.into_expr(), parse_str(filter).expect("valid filter tree"));
+    /// ```
+    pub fn into_expr(self) -> Expr {
+        let boxed = |expr: Box<SpannedExpr>| Box::new(expr.into_expr());
+        let list = |exprs: Vec<SpannedExpr>| exprs.into_iter().map(Self::into_expr).collect();
+
+        match self.kind {
+            SpannedExprKind::Or(lhs, rhs) => Expr::Or(boxed(lhs), boxed(rhs)),
+            SpannedExprKind::And(lhs, rhs) => Expr::And(boxed(lhs), boxed(rhs)),
+            SpannedExprKind::Not(expr) => Expr::Not(boxed(expr)),
+            SpannedExprKind::Compare(lhs, op, rhs) => Expr::Compare(boxed(lhs), op, boxed(rhs)),
+            SpannedExprKind::In(lhs, values) => Expr::In(boxed(lhs), list(values)),
+            SpannedExprKind::Function(name, args) => Expr::Function(name, list(args)),
+            SpannedExprKind::Lambda(collection, op, variable, body) => {
+                Expr::Lambda(boxed(collection), op, variable, boxed(body))
+            }
+            SpannedExprKind::Identifier(name) => Expr::Identifier(name),
+            SpannedExprKind::Alias(name) => Expr::Alias(name),
+            SpannedExprKind::Value(value) => Expr::Value(value),
+        }
+    }
+
+    /// Validates the types within the expression like [`Expr::validate`],
+    /// reporting errors with the span of the node they were found at.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use odata_params::filters::{
+    ///     parse_str_spanned, FunctionsTypeMap, IdentifiersTypeMap, Type, ValidationError,
+    /// };
+    ///
+    /// let mut id_map = HashMap::new();
+    /// id_map.insert("name".to_string(), Type::String);
+    /// let identifiers = IdentifiersTypeMap::from(id_map);
+    ///
+    /// let functions = FunctionsTypeMap::from(HashMap::new());
+    ///
+    /// let filter = "name eq 'Bob' and agee gt 30";
+    /// let expr = parse_str_spanned(filter).expect("valid filter tree");
+    /// let error = expr.validate(&identifiers, &functions).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.error,
+    ///     ValidationError::UndefinedIdentifier { name: "agee".to_string() },
+    /// );
+    /// assert_eq!(&filter[error.span.range()], "agee");
+    /// ```
+    pub fn validate(
+        &self,
+        identifiers: &IdentifiersTypeMap,
+        functions: &FunctionsTypeMap,
+    ) -> Result<Type, SpannedValidationError> {
+        validate_scoped(self, identifiers, functions, &mut Vec::new())
+    }
+
+    /// Validates the types within the expression like
+    /// [`Expr::validate_typed`], reporting errors with the span of the node
+    /// they were found at.
+    pub fn validate_typed(
+        &self,
+        identifiers: &IdentifiersTypeMap,
+        functions: &FunctionsTypeMap,
+    ) -> Result<TypedExpr, SpannedValidationError> {
+        validate_scoped(self, identifiers, functions, &mut Vec::new())
+    }
+}
+
+impl From<SpannedExpr> for Expr {
+    fn from(expr: SpannedExpr) -> Self {
+        expr.into_expr()
+    }
+}
+
+impl Validate for SpannedExpr {
+    fn node(&self) -> Node<'_, Self> {
+        match &self.kind {
+            SpannedExprKind::Or(lhs, rhs) => Node::Or(lhs, rhs),
+            SpannedExprKind::And(lhs, rhs) => Node::And(lhs, rhs),
+            SpannedExprKind::Not(inner) => Node::Not(inner),
+            SpannedExprKind::Compare(lhs, op, rhs) => Node::Compare(lhs, op, rhs),
+            SpannedExprKind::In(lhs, values) => Node::In(lhs, values),
+            SpannedExprKind::Function(name, args) => Node::Function(name, args),
+            SpannedExprKind::Lambda(lhs, op, var, expr) => Node::Lambda(lhs, op, var, expr),
+            SpannedExprKind::Identifier(name) => Node::Identifier(name),
+            SpannedExprKind::Alias(name) => Node::Alias(name),
+            SpannedExprKind::Value(value) => Node::Value(value),
+        }
+    }
+
+    fn span(&self) -> Span {
+        self.span
+    }
+}
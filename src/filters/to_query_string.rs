@@ -1,4 +1,5 @@
-use super::{Expr, Value};
+use super::visit::walk_expr;
+use super::{CompareOperator, Expr, LambdaOperator, Value, Visitor};
 use chrono::SecondsFormat::Millis;
 use std::fmt::{self, Write};
 
@@ -30,109 +31,123 @@ pub fn to_query_string(expr: &Expr) -> Result<String, fmt::Error> {
 ///
 /// A `fmt::Result` indicating the success or failure of the write operation.
 pub fn write_query_string<W: Write>(writer: &mut W, expr: &Expr) -> fmt::Result {
-    write_string(writer, expr, false)
+    let mut writer = QueryStringWriter {
+        writer,
+        nested: false,
+        result: Ok(()),
+    };
+
+    writer.visit_expr(expr);
+    writer.result
 }
 
-fn write_string<W: Write>(writer: &mut W, expr: &Expr, recursive_call: bool) -> fmt::Result {
-    match expr {
-        // Handle logical OR expressions.
-        Expr::Or(lhs, rhs) => {
-            // The recursive_call value is used here to not wrap the entire
-            // output query in parentheses when the root `Expr` is an `Or`.
-            if recursive_call {
-                write!(writer, "(")?;
-            }
+/// Writes the expressions it visits, keeping the first error.
+struct QueryStringWriter<'w, W: Write> {
+    writer: &'w mut W,
 
-            write_string(writer, lhs, true)?;
-            write!(writer, " or ")?;
+    /// Whether the expression being visited is below the root one.
+    nested: bool,
 
-            if recursive_call {
-                write_string(writer, rhs, true)?;
-                write!(writer, ")")
-            } else {
-                write_string(writer, rhs, true)
-            }
+    result: fmt::Result,
+}
+
+impl<W: Write> QueryStringWriter<'_, W> {
+    fn write(&mut self, args: fmt::Arguments<'_>) {
+        if self.result.is_ok() {
+            self.result = self.writer.write_fmt(args);
         }
+    }
 
-        // Handle logical AND expressions.
-        Expr::And(lhs, rhs) => {
-            // The recursive_call value is used here to not wrap the entire
-            // output query in parentheses when the root `Expr` is an `Or`.
-            if recursive_call {
-                write!(writer, "(")?;
+    fn list(&mut self, exprs: &[Expr]) {
+        for (i, expr) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.write(format_args!(", "));
             }
 
-            write_string(writer, lhs, true)?;
-            write!(writer, " and ")?;
-
-            if recursive_call {
-                write_string(writer, rhs, true)?;
-                write!(writer, ")")
-            } else {
-                write_string(writer, rhs, true)
-            }
-        }
-
-        // Handle comparison expressions.
-        Expr::Compare(lhs, op, rhs) => {
-            write_string(writer, lhs, true)?;
-            write!(writer, " {op} ")?;
-            write_string(writer, rhs, true)
+            self.visit_expr(expr);
         }
+    }
+}
 
-        // Handle IN expressions.
-        Expr::In(lhs, values) => {
-            write_string(writer, lhs, true)?;
-            write!(writer, " in (")?;
+impl<'a, W: Write> Visitor<'a> for QueryStringWriter<'_, W> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        // Logical joins are wrapped in parentheses, except at the root so
+        // that the entire output query is not.
+        let nested = std::mem::replace(&mut self.nested, true);
+        let grouped = nested && matches!(expr, Expr::Or(..) | Expr::And(..));
 
-            for (i, value) in values.iter().enumerate() {
-                if i > 0 {
-                    write!(writer, ", ")?;
-                }
+        if grouped {
+            self.write(format_args!("("));
+        }
 
-                write_string(writer, value, true)?;
-            }
+        walk_expr(self, expr);
 
-            write!(writer, ")")
+        if grouped {
+            self.write(format_args!(")"));
         }
+    }
 
-        // Handle logical NOT expressions.
-        Expr::Not(expr) => {
-            write!(writer, "not ")?;
-            write_string(writer, expr, true)
-        }
+    fn visit_or(&mut self, lhs: &'a Expr, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.write(format_args!(" or "));
+        self.visit_expr(rhs);
+    }
 
-        // Handle function calls.
-        Expr::Function(name, args) => {
-            write!(writer, "{name}(")?;
+    fn visit_and(&mut self, lhs: &'a Expr, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.write(format_args!(" and "));
+        self.visit_expr(rhs);
+    }
 
-            for (i, arg) in args.iter().enumerate() {
-                if i > 0 {
-                    write!(writer, ", ")?;
-                }
+    fn visit_not(&mut self, expr: &'a Expr) {
+        self.write(format_args!("not "));
+        self.visit_expr(expr);
+    }
 
-                write_string(writer, arg, true)?;
-            }
+    fn visit_compare(&mut self, lhs: &'a Expr, op: &'a CompareOperator, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.write(format_args!(" {op} "));
+        self.visit_expr(rhs);
+    }
 
-            write!(writer, ")")
-        }
+    fn visit_in(&mut self, lhs: &'a Expr, values: &'a [Expr]) {
+        self.visit_expr(lhs);
+        self.write(format_args!(" in ("));
+        self.list(values);
+        self.write(format_args!(")"));
+    }
 
-        // Handle lambda expressions.
-        Expr::Lambda(lhs, op, var, expr) => {
-            write_string(writer, lhs, true)?;
-            write!(writer, "/{op}({var}:")?;
-            write_string(writer, expr, true)?;
-            write!(writer, ")")
-        }
+    fn visit_function(&mut self, name: &'a str, args: &'a [Expr]) {
+        self.write(format_args!("{name}("));
+        self.list(args);
+        self.write(format_args!(")"));
+    }
 
-        // Handle identifiers.
-        Expr::Identifier(name) => write!(writer, "{name}"),
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        op: &'a LambdaOperator,
+        variable: &'a str,
+        body: &'a Expr,
+    ) {
+        self.visit_expr(collection);
+        self.write(format_args!("/{op}({variable}:"));
+        self.visit_expr(body);
+        self.write(format_args!(")"));
+    }
 
-        // Handle parameter aliases.
-        Expr::Alias(name) => write!(writer, "{name}"),
+    fn visit_identifier(&mut self, name: &'a str) {
+        self.write(format_args!("{name}"));
+    }
 
-        // Handle values.
-        Expr::Value(value) => write_value(writer, value),
+    fn visit_alias(&mut self, name: &'a str) {
+        self.write(format_args!("{name}"));
+    }
+
+    fn visit_value(&mut self, value: &'a Value) {
+        if self.result.is_ok() {
+            self.result = write_value(self.writer, value);
+        }
     }
 }
 
@@ -165,4 +180,4 @@ fn write_value<W: Write>(writer: &mut W, value: &Value) -> fmt::Result {
         // Handle string values, escaping single quotes.
         Value::String(s) => write!(writer, "'{}'", s.replace('\'', "''")),
     }
-}
\ No newline at end of file
+}
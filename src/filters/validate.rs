@@ -1,4 +1,8 @@
-use super::{Expr, FunctionsTypeMap, IdentifiersTypeMap, Type, ValidationError, Value};
+use super::visit::root;
+use super::{
+    CompareOperator, Expr, FunctionSignature, FunctionsTypeMap, IdentifiersTypeMap, LambdaOperator,
+    Span, SpannedValidationError, Type, TypedExpr, TypedExprKind, ValidationError, Value,
+};
 use std::iter::repeat;
 
 impl Expr {
@@ -61,161 +65,500 @@ impl Expr {
         identifiers: &IdentifiersTypeMap,
         functions: &FunctionsTypeMap,
     ) -> Result<Type, ValidationError> {
+        validate_scoped(self, identifiers, functions, &mut Vec::new()).map_err(|error| error.error)
+    }
+
+    /// Validates the types within the expression and returns the expression
+    /// annotated with the type resolved for every node.
+    ///
+    /// Identifiers are resolved against the identifiers map unless they are
+    /// bound by an enclosing lambda, function calls record the signature they
+    /// were checked against, and operands that are implicitly converted to the
+    /// type of the other side are wrapped in `TypedExprKind::Convert`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use odata_params::filters::{
+    ///     parse_str, FunctionsTypeMap, IdentifiersTypeMap, Type, TypedExprKind,
+    /// };
+    ///
+    /// let mut id_map = HashMap::new();
+    /// id_map.insert("deletedAt".to_string(), Type::DateTime);
+    /// let identifiers = IdentifiersTypeMap::from(id_map);
+    ///
+    /// let functions = FunctionsTypeMap::from(HashMap::new());
+    ///
+    /// let expr = parse_str("deletedAt eq null").expect("valid filter tree");
+    /// let typed = expr.validate_typed(&identifiers, &functions).expect("valid types");
+    ///
+    /// assert_eq!(typed.ty, Type::Boolean);
+    ///
+    /// let TypedExprKind::Compare(lhs, _, rhs) = typed.kind else { unreachable!() };
+    /// assert!(matches!(lhs.kind, TypedExprKind::Identifier(_)));
+    /// assert!(matches!(rhs.kind, TypedExprKind::Convert(_)));
+    /// assert!(matches!(rhs.ty, Type::DateTime));
+    /// ```
+    pub fn validate_typed(
+        &self,
+        identifiers: &IdentifiersTypeMap,
+        functions: &FunctionsTypeMap,
+    ) -> Result<TypedExpr, ValidationError> {
+        validate_scoped(self, identifiers, functions, &mut Vec::new()).map_err(|error| error.error)
+    }
+}
+
+/// A borrowed view of one node of an expression tree, so the same
+/// validation runs over both `Expr` and `SpannedExpr`.
+///
+/// `Visitor` only walks `Expr`, and validation stops at the first error
+/// while building its result through `Typing`, so it matches on these
+/// nodes instead.
+pub(super) enum Node<'a, T> {
+    Or(&'a T, &'a T),
+    And(&'a T, &'a T),
+    Not(&'a T),
+    Compare(&'a T, &'a CompareOperator, &'a T),
+    In(&'a T, &'a [T]),
+    Function(&'a str, &'a [T]),
+    Lambda(&'a T, &'a LambdaOperator, &'a str, &'a T),
+    Identifier(&'a str),
+    Alias(&'a str),
+    Value(&'a Value),
+}
+
+/// An expression tree that can be validated.
+pub(super) trait Validate: Sized {
+    fn node(&self) -> Node<'_, Self>;
+
+    /// Where the node was parsed from, reported with validation errors.
+    fn span(&self) -> Span;
+}
+
+impl Validate for Expr {
+    fn node(&self) -> Node<'_, Self> {
         match self {
-            Expr::Or(lhs, rhs) | Expr::And(lhs, rhs) => {
-                let lhs_type = Self::validate(lhs, identifiers, functions)?;
-                let rhs_type = Self::validate(rhs, identifiers, functions)?;
-
-                if lhs_type == Type::Boolean && rhs_type == Type::Boolean {
-                    Ok(Type::Boolean)
-                } else {
-                    Err(ValidationError::LogicalJoinRequiresBooleans {
-                        lhs: lhs_type,
-                        rhs: rhs_type,
-                    })
-                }
+            Expr::Or(lhs, rhs) => Node::Or(lhs, rhs),
+            Expr::And(lhs, rhs) => Node::And(lhs, rhs),
+            Expr::Not(inner) => Node::Not(inner),
+            Expr::Compare(lhs, op, rhs) => Node::Compare(lhs, op, rhs),
+            Expr::In(lhs, values) => Node::In(lhs, values),
+            Expr::Function(name, args) => Node::Function(name, args),
+            Expr::Lambda(lhs, op, var, expr) => Node::Lambda(lhs, op, var, expr),
+            Expr::Identifier(name) => Node::Identifier(name),
+            Expr::Alias(name) => Node::Alias(name),
+            Expr::Value(value) => Node::Value(value),
+        }
+    }
+
+    /// Plain expressions have no spans, so errors found in them are given an
+    /// empty one that is dropped before they are returned.
+    fn span(&self) -> Span {
+        Span::default()
+    }
+}
+
+/// What validating a node produces, so that validation only builds a typed
+/// tree when one was asked for and otherwise just resolves types.
+pub(super) trait Typing: Sized {
+    /// Typed operands of an `in` list or function call, in order.
+    type List: Default;
+
+    fn ty(&self) -> Type;
+
+    fn push(list: &mut Self::List, item: Self);
+
+    fn or(lhs: Self, rhs: Self) -> Self;
+
+    fn and(lhs: Self, rhs: Self) -> Self;
+
+    fn not(inner: Self) -> Self;
+
+    fn compare(lhs: Self, op: &CompareOperator, rhs: Self) -> Self;
+
+    fn in_list(lhs: Self, values: Self::List) -> Self;
+
+    fn function(name: &str, signature: &(Vec<Type>, Option<Type>, Type), args: Self::List) -> Self;
+
+    fn lambda(lhs: Self, op: &LambdaOperator, var: &str, body: Self) -> Self;
+
+    fn lambda_variable(name: &str) -> Self;
+
+    fn identifier(name: &str, ty: Type) -> Self;
+
+    fn alias(name: &str, ty: Type) -> Self;
+
+    fn value(value: &Value, ty: Type) -> Self;
+
+    /// Converts an operand without a concrete type to `target`.
+    fn convert(self, target: Type) -> Self;
+}
+
+/// Only resolves the type of every node.
+impl Typing for Type {
+    type List = ();
+
+    fn ty(&self) -> Type {
+        *self
+    }
+
+    fn push(_: &mut (), _: Self) {}
+
+    fn or(_: Self, _: Self) -> Self {
+        Type::Boolean
+    }
+
+    fn and(_: Self, _: Self) -> Self {
+        Type::Boolean
+    }
+
+    fn not(_: Self) -> Self {
+        Type::Boolean
+    }
+
+    fn compare(_: Self, _: &CompareOperator, _: Self) -> Self {
+        Type::Boolean
+    }
+
+    fn in_list(_: Self, _: ()) -> Self {
+        Type::Boolean
+    }
+
+    fn function(_: &str, (_, _, ret): &(Vec<Type>, Option<Type>, Type), _: ()) -> Self {
+        *ret
+    }
+
+    fn lambda(_: Self, _: &LambdaOperator, _: &str, _: Self) -> Self {
+        Type::Boolean
+    }
+
+    fn lambda_variable(_: &str) -> Self {
+        Type::Null
+    }
+
+    fn identifier(_: &str, ty: Type) -> Self {
+        ty
+    }
+
+    fn alias(_: &str, ty: Type) -> Self {
+        ty
+    }
+
+    fn value(_: &Value, ty: Type) -> Self {
+        ty
+    }
+
+    fn convert(self, _: Type) -> Self {
+        self
+    }
+}
+
+/// Builds the typed tree of the expression.
+impl Typing for TypedExpr {
+    type List = Vec<TypedExpr>;
+
+    fn ty(&self) -> Type {
+        self.ty
+    }
+
+    fn push(list: &mut Vec<TypedExpr>, item: Self) {
+        list.push(item);
+    }
+
+    fn or(lhs: Self, rhs: Self) -> Self {
+        typed(
+            Type::Boolean,
+            TypedExprKind::Or(Box::new(lhs), Box::new(rhs)),
+        )
+    }
+
+    fn and(lhs: Self, rhs: Self) -> Self {
+        typed(
+            Type::Boolean,
+            TypedExprKind::And(Box::new(lhs), Box::new(rhs)),
+        )
+    }
+
+    fn not(inner: Self) -> Self {
+        typed(Type::Boolean, TypedExprKind::Not(Box::new(inner)))
+    }
+
+    fn compare(lhs: Self, op: &CompareOperator, rhs: Self) -> Self {
+        let (lhs_type, rhs_type) = (lhs.ty, rhs.ty);
+
+        typed(
+            Type::Boolean,
+            TypedExprKind::Compare(
+                Box::new(lhs.convert(rhs_type)),
+                op.clone(),
+                Box::new(rhs.convert(lhs_type)),
+            ),
+        )
+    }
+
+    fn in_list(lhs: Self, values: Vec<TypedExpr>) -> Self {
+        // Every operand is compared against the first concrete type
+        // found, starting with the left hand side.
+        let common_type = std::iter::once(lhs.ty)
+            .chain(values.iter().map(|value| value.ty))
+            .find(|ty| !matches!(ty, Type::Null))
+            .unwrap_or(Type::Null);
+
+        typed(
+            Type::Boolean,
+            TypedExprKind::In(
+                Box::new(lhs.convert(common_type)),
+                values
+                    .into_iter()
+                    .map(|value| value.convert(common_type))
+                    .collect(),
+            ),
+        )
+    }
+
+    fn function(
+        name: &str,
+        (types, variadic, ret): &(Vec<Type>, Option<Type>, Type),
+        args: Vec<TypedExpr>,
+    ) -> Self {
+        let signature = FunctionSignature {
+            arguments: types.clone(),
+            variadic: *variadic,
+            returns: *ret,
+        };
+
+        typed(
+            *ret,
+            TypedExprKind::Function(name.to_owned(), signature, args),
+        )
+    }
+
+    fn lambda(lhs: Self, op: &LambdaOperator, var: &str, body: Self) -> Self {
+        typed(
+            Type::Boolean,
+            TypedExprKind::Lambda(Box::new(lhs), op.clone(), var.to_owned(), Box::new(body)),
+        )
+    }
+
+    fn lambda_variable(name: &str) -> Self {
+        typed(Type::Null, TypedExprKind::LambdaVariable(name.to_owned()))
+    }
+
+    fn identifier(name: &str, ty: Type) -> Self {
+        typed(ty, TypedExprKind::Identifier(name.to_owned()))
+    }
+
+    fn alias(name: &str, ty: Type) -> Self {
+        typed(ty, TypedExprKind::Alias(name.to_owned()))
+    }
+
+    fn value(value: &Value, ty: Type) -> Self {
+        typed(ty, TypedExprKind::Value(value.clone()))
+    }
+
+    /// Wraps an operand without a concrete type in a conversion to `target`.
+    fn convert(self, target: Type) -> Self {
+        if matches!(self.ty, Type::Null) && !matches!(target, Type::Null) {
+            typed(target, TypedExprKind::Convert(Box::new(self)))
+        } else {
+            self
+        }
+    }
+}
+
+fn typed(ty: Type, kind: TypedExprKind) -> TypedExpr {
+    TypedExpr { ty, kind }
+}
+
+/// Validates an expression while tracking the lambda variables in scope.
+pub(super) fn validate_scoped<'a, T: Validate, O: Typing>(
+    expr: &'a T,
+    identifiers: &IdentifiersTypeMap,
+    functions: &FunctionsTypeMap,
+    scope: &mut Vec<&'a str>,
+) -> Result<O, SpannedValidationError> {
+    let at = |error| SpannedValidationError {
+        error,
+        span: expr.span(),
+    };
+
+    match expr.node() {
+        node @ (Node::Or(lhs, rhs) | Node::And(lhs, rhs)) => {
+            let lhs: O = validate_scoped(lhs, identifiers, functions, scope)?;
+            let rhs: O = validate_scoped(rhs, identifiers, functions, scope)?;
+
+            if lhs.ty() == Type::Boolean && rhs.ty() == Type::Boolean {
+                Ok(match node {
+                    Node::Or(..) => O::or(lhs, rhs),
+                    _ => O::and(lhs, rhs),
+                })
+            } else {
+                Err(at(ValidationError::LogicalJoinRequiresBooleans {
+                    lhs: lhs.ty(),
+                    rhs: rhs.ty(),
+                }))
             }
+        }
 
-            Expr::Not(inner) => {
-                let inner_type = Self::validate(inner, identifiers, functions)?;
+        Node::Not(inner) => {
+            let inner: O = validate_scoped(inner, identifiers, functions, scope)?;
 
-                if inner_type == Type::Boolean {
-                    Ok(Type::Boolean)
-                } else {
-                    Err(ValidationError::LogicalNotRequiresBoolean { given: inner_type })
-                }
+            if inner.ty() == Type::Boolean {
+                Ok(O::not(inner))
+            } else {
+                Err(at(ValidationError::LogicalNotRequiresBoolean {
+                    given: inner.ty(),
+                }))
             }
+        }
 
-            Expr::Compare(lhs, _op, rhs) => {
-                let lhs_type = Self::validate(lhs, identifiers, functions)?;
-                let rhs_type = Self::validate(rhs, identifiers, functions)?;
-
-                if lhs_type == rhs_type {
-                    Ok(Type::Boolean)
-                } else {
-                    Err(ValidationError::ComparingIncompatibleTypes {
-                        lhs: lhs_type,
-                        rhs: rhs_type,
-                    })
-                }
+        Node::Compare(lhs, op, rhs) => {
+            let lhs: O = validate_scoped(lhs, identifiers, functions, scope)?;
+            let rhs: O = validate_scoped(rhs, identifiers, functions, scope)?;
+
+            if lhs.ty() == rhs.ty() {
+                Ok(O::compare(lhs, op, rhs))
+            } else {
+                Err(at(ValidationError::ComparingIncompatibleTypes {
+                    lhs: lhs.ty(),
+                    rhs: rhs.ty(),
+                }))
             }
+        }
 
-            Expr::In(lhs, values) => {
-                let lhs_type = Self::validate(lhs, identifiers, functions)?;
+        Node::In(lhs, values) => {
+            let lhs: O = validate_scoped(lhs, identifiers, functions, scope)?;
+            let mut typed_values = O::List::default();
 
-                for value in values {
-                    let value_type = Self::validate(value, identifiers, functions)?;
+            for value in values {
+                let value: O = validate_scoped(value, identifiers, functions, scope)?;
 
-                    if lhs_type != value_type {
-                        return Err(ValidationError::ComparingIncompatibleTypes {
-                            lhs: lhs_type,
-                            rhs: value_type,
-                        });
-                    }
+                if lhs.ty() != value.ty() {
+                    return Err(at(ValidationError::ComparingIncompatibleTypes {
+                        lhs: lhs.ty(),
+                        rhs: value.ty(),
+                    }));
                 }
 
-                Ok(Type::Boolean)
+                O::push(&mut typed_values, value);
             }
 
-            Expr::Function(function, args) => {
-                let (types, variadic, ret) = functions.0.get(function).ok_or_else(|| {
-                    ValidationError::UndefinedFunction {
-                        name: function.to_owned(),
-                    }
-                })?;
-
-                // println!(":: {types:?}, {variadic:?}, {args:?}");
-
-                if (variadic.is_none() && types.len() != args.len())
-                    || (variadic.is_some() && types.len() > args.len())
-                {
-                    return Err(ValidationError::IncorrectFunctionArgumentsCount {
-                        name: function.to_owned(),
-                        is_variadic: variadic.is_some(),
-                        expected: types.len(),
-                        given: args.len(),
-                    });
-                }
+            Ok(O::in_list(lhs, typed_values))
+        }
+
+        Node::Function(function, args) => {
+            let signature = functions.0.get(function).ok_or_else(|| {
+                at(ValidationError::UndefinedFunction {
+                    name: function.to_owned(),
+                })
+            })?;
+            let (types, variadic, _) = signature;
+
+            if (variadic.is_none() && types.len() != args.len())
+                || (variadic.is_some() && types.len() > args.len())
+            {
+                return Err(at(ValidationError::IncorrectFunctionArgumentsCount {
+                    name: function.to_owned(),
+                    is_variadic: variadic.is_some(),
+                    expected: types.len(),
+                    given: args.len(),
+                }));
+            }
+
+            // It should be safe to setup an infinite chain of nulls when
+            // `variadic` is not set since we should have already exited
+            // early when `variadic` is None and `types` have a different
+            // length than the given arguments.
+            //
+            // This is needed to have consistent types without needing to
+            // collect eagerly. The `.zip` is what keeps the infinite
+            // iterator fixed to the length of given arguments.
+            let expected_types = args.iter().zip(
+                types
+                    .iter()
+                    .copied()
+                    .chain(repeat(variadic.unwrap_or(Type::Null))),
+            );
 
-                // It should be safe to setup an infinite chain of nulls when
-                // `variadic` is not set since we should have already exited
-                // early when `variadic` is None and `types` have a different
-                // length than the given arguments.
-                //
-                // This is needed to have consistent types without needing to
-                // collect eagerly. The `.zip` is what keeps the infinite
-                // iterator fixed to the length of given arguments.
-                let types = args.iter().zip(
-                    types
-                        .iter()
-                        .copied()
-                        .chain(repeat(variadic.unwrap_or(Type::Null))),
-                );
-
-                for (index, (arg, expected_type)) in types.enumerate() {
-                    let arg_type = Self::validate(arg, identifiers, functions)?;
-
-                    if arg_type != expected_type {
-                        return Err(ValidationError::IncorrectFunctionArgumentType {
+            let mut typed_args = O::List::default();
+
+            for (index, (arg, expected_type)) in expected_types.enumerate() {
+                let span = arg.span();
+                let arg: O = validate_scoped(arg, identifiers, functions, scope)?;
+
+                if arg.ty() != expected_type {
+                    return Err(SpannedValidationError {
+                        error: ValidationError::IncorrectFunctionArgumentType {
                             name: function.to_owned(),
                             position: index + 1,
                             expected: expected_type,
-                            given: arg_type,
-                        });
-                    }
+                            given: arg.ty(),
+                        },
+                        span,
+                    });
                 }
 
-                Ok(*ret)
+                O::push(&mut typed_args, arg.convert(expected_type));
             }
 
-            Expr::Lambda(lhs, _, var, expr) => {
-                // Ensure LHS is valid (typically a collection, but we just check if it resolves)
-                let _lhs_type = Self::validate(lhs, identifiers, functions)?;
-
-                // Create a new scope for the lambda variable
-                let mut scoped_identifiers = identifiers.clone();
-                // We cannot easily determine the type of the lambda variable without schema knowledge
-                // of the collection. For now, we assume it's `Type::Null` (a placeholder for any)
-                // or we rely on the user to ensure structural correctness.
-                //
-                // In a full implementation, LHS would be a `Collection<T>` and `var` would be `T`.
-                // Here, we just insert it to avoid "UndefinedIdentifier" errors.
-                scoped_identifiers.0.insert(var.clone(), Type::Null);
-
-                let expr_type = Self::validate(expr, &scoped_identifiers, functions)?;
-
-                if expr_type == Type::Boolean {
-                    Ok(Type::Boolean)
-                } else {
-                    Err(ValidationError::LogicalNotRequiresBoolean { given: expr_type })
-                }
+            Ok(O::function(function, signature, typed_args))
+        }
+
+        Node::Lambda(lhs, op, var, body) => {
+            // Ensure LHS is valid (typically a collection, but we just check if it resolves)
+            let lhs: O = validate_scoped(lhs, identifiers, functions, scope)?;
+
+            // We cannot easily determine the type of the lambda variable without schema knowledge
+            // of the collection. For now, we assume it's `Type::Null` (a placeholder for any)
+            // or we rely on the user to ensure structural correctness.
+            //
+            // In a full implementation, LHS would be a `Collection<T>` and `var` would be `T`.
+            // Here, we just bring it into scope to avoid "UndefinedIdentifier" errors.
+            scope.push(var);
+            let body = validate_scoped::<T, O>(body, identifiers, functions, scope);
+            scope.pop();
+            let body = body?;
+
+            if body.ty() == Type::Boolean {
+                Ok(O::lambda(lhs, op, var, body))
+            } else {
+                Err(at(ValidationError::LogicalNotRequiresBoolean {
+                    given: body.ty(),
+                }))
             }
+        }
 
-            Expr::Identifier(identifier) => {
-                // If type is Type::Null, it matches everything (used for lambda vars without schema)
-                let t = identifiers.0.get(identifier).copied().ok_or_else(|| {
-                    ValidationError::UndefinedIdentifier {
-                        name: identifier.to_owned(),
-                    }
-                })?;
-                
-                // If the identifier maps to Null (wildcard), we might need to handle it carefully.
-                // For now, we return Null as the type, which needs to be compatible with others
-                // in Compare check. The `Type::eq` impl handles `Type::Null`.
-                Ok(t)
+        Node::Identifier(identifier) => {
+            // Lambda variables, and paths starting with one, have no known
+            // type so they are given `Type::Null`, which the `Type::eq` impl
+            // treats as compatible with everything.
+            if scope.contains(&root(identifier)) {
+                return Ok(O::lambda_variable(identifier));
             }
 
-            Expr::Alias(name) => {
-                // Check if alias is defined in the identifiers map.
-                // Aliases like @p1 should be treated similarly to identifiers for validation purposes.
-                identifiers.0.get(name).copied().ok_or_else(|| {
-                    ValidationError::UndefinedIdentifier {
-                        name: name.to_owned(),
-                    }
+            let t = identifiers.0.get(identifier).copied().ok_or_else(|| {
+                at(ValidationError::UndefinedIdentifier {
+                    name: identifier.to_owned(),
                 })
-            }
+            })?;
+
+            Ok(O::identifier(identifier, t))
+        }
+
+        Node::Alias(name) => {
+            // Check if alias is defined in the identifiers map.
+            // Aliases like @p1 should be treated similarly to identifiers for validation purposes.
+            let t = identifiers.0.get(name).copied().ok_or_else(|| {
+                at(ValidationError::UndefinedIdentifier {
+                    name: name.to_owned(),
+                })
+            })?;
+
+            Ok(O::alias(name, t))
+        }
 
-            Expr::Value(value) => Ok(match value {
+        Node::Value(value) => Ok(O::value(
+            value,
+            match value {
                 Value::Null => Type::Null,
                 Value::Bool(_) => Type::Boolean,
                 Value::Number(_) => Type::Number,
@@ -224,7 +567,7 @@ impl Expr {
                 Value::Date(_) => Type::Date,
                 Value::Time(_) => Type::Time,
                 Value::String(_) => Type::String,
-            }),
-        }
+            },
+        )),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,481 @@
+use super::{CompareOperator, Expr, LambdaOperator, Value};
+
+/// Walks an `Expr` tree by reference.
+///
+/// Each method is called for the matching `Expr` variant and, by default,
+/// visits the children of the node. Override the methods for the variants of
+/// interest; an override that still wants to descend into the children calls
+/// `self.visit_expr` on them, or `walk_expr` from `visit_expr`.
+///
+/// ```
+/// use odata_params::filters::{parse_str, Expr, Visitor};
+///
+/// #[derive(Default)]
+/// struct Depth {
+///     current: usize,
+///     max: usize,
+/// }
+///
+/// impl Visitor<'_> for Depth {
+///     fn visit_not(&mut self, expr: &Expr) {
+///         self.current += 1;
+///         self.max = self.max.max(self.current);
+///         self.visit_expr(expr);
+///         self.current -= 1;
+///     }
+/// }
+///
+/// let expr = parse_str("not (not (not active eq true))").expect("valid filter tree");
+///
+/// let mut depth = Depth::default();
+/// depth.visit_expr(&expr);
+///
+/// assert_eq!(depth.max, 3);
+/// ```
+pub trait Visitor<'a> {
+    /// Visits any expression, dispatching on its variant.
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// Visits an `Expr::Or`.
+    fn visit_or(&mut self, lhs: &'a Expr, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    /// Visits an `Expr::And`.
+    fn visit_and(&mut self, lhs: &'a Expr, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    /// Visits an `Expr::Not`.
+    fn visit_not(&mut self, expr: &'a Expr) {
+        self.visit_expr(expr);
+    }
+
+    /// Visits an `Expr::Compare`.
+    fn visit_compare(&mut self, lhs: &'a Expr, _op: &'a CompareOperator, rhs: &'a Expr) {
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    /// Visits an `Expr::In`.
+    fn visit_in(&mut self, lhs: &'a Expr, values: &'a [Expr]) {
+        self.visit_expr(lhs);
+
+        for value in values {
+            self.visit_expr(value);
+        }
+    }
+
+    /// Visits an `Expr::Function`.
+    fn visit_function(&mut self, _name: &'a str, args: &'a [Expr]) {
+        for arg in args {
+            self.visit_expr(arg);
+        }
+    }
+
+    /// Visits an `Expr::Lambda`.
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        _op: &'a LambdaOperator,
+        _variable: &'a str,
+        body: &'a Expr,
+    ) {
+        self.visit_expr(collection);
+        self.visit_expr(body);
+    }
+
+    /// Visits an `Expr::Identifier`.
+    fn visit_identifier(&mut self, _name: &'a str) {}
+
+    /// Visits an `Expr::Alias`.
+    fn visit_alias(&mut self, _name: &'a str) {}
+
+    /// Visits an `Expr::Value`.
+    fn visit_value(&mut self, _value: &'a Value) {}
+}
+
+/// Calls the `Visitor` method matching the variant of `expr`.
+pub fn walk_expr<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, expr: &'a Expr) {
+    match expr {
+        Expr::Or(lhs, rhs) => visitor.visit_or(lhs, rhs),
+        Expr::And(lhs, rhs) => visitor.visit_and(lhs, rhs),
+        Expr::Not(inner) => visitor.visit_not(inner),
+        Expr::Compare(lhs, op, rhs) => visitor.visit_compare(lhs, op, rhs),
+        Expr::In(lhs, values) => visitor.visit_in(lhs, values),
+        Expr::Function(name, args) => visitor.visit_function(name, args),
+        Expr::Lambda(collection, op, variable, body) => {
+            visitor.visit_lambda(collection, op, variable, body)
+        }
+        Expr::Identifier(name) => visitor.visit_identifier(name),
+        Expr::Alias(name) => visitor.visit_alias(name),
+        Expr::Value(value) => visitor.visit_value(value),
+    }
+}
+
+/// Walks an `Expr` tree by mutable reference, to rewrite it in place.
+///
+/// This is the in-place counterpart of `Visitor`; the same rules apply for
+/// overriding methods and descending into children.
+///
+/// ```
+/// use odata_params::filters::{parse_str, to_query_string, VisitorMut};
+///
+/// struct Prefix;
+///
+/// impl VisitorMut for Prefix {
+///     fn visit_identifier_mut(&mut self, name: &mut String) {
+///         name.insert_str(0, "customer_");
+///     }
+/// }
+///
+/// let mut expr = parse_str("name eq 'Bob' and age gt 30").expect("valid filter tree");
+/// Prefix.visit_expr_mut(&mut expr);
+///
+/// assert_eq!(
+///     to_query_string(&expr).expect("valid filter"),
+///     "customer_name eq 'Bob' and customer_age gt 30",
+/// );
+/// ```
+pub trait VisitorMut {
+    /// Visits any expression, dispatching on its variant.
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    /// Visits an `Expr::Or`.
+    fn visit_or_mut(&mut self, lhs: &mut Expr, rhs: &mut Expr) {
+        self.visit_expr_mut(lhs);
+        self.visit_expr_mut(rhs);
+    }
+
+    /// Visits an `Expr::And`.
+    fn visit_and_mut(&mut self, lhs: &mut Expr, rhs: &mut Expr) {
+        self.visit_expr_mut(lhs);
+        self.visit_expr_mut(rhs);
+    }
+
+    /// Visits an `Expr::Not`.
+    fn visit_not_mut(&mut self, expr: &mut Expr) {
+        self.visit_expr_mut(expr);
+    }
+
+    /// Visits an `Expr::Compare`.
+    fn visit_compare_mut(&mut self, lhs: &mut Expr, _op: &mut CompareOperator, rhs: &mut Expr) {
+        self.visit_expr_mut(lhs);
+        self.visit_expr_mut(rhs);
+    }
+
+    /// Visits an `Expr::In`.
+    fn visit_in_mut(&mut self, lhs: &mut Expr, values: &mut Vec<Expr>) {
+        self.visit_expr_mut(lhs);
+
+        for value in values {
+            self.visit_expr_mut(value);
+        }
+    }
+
+    /// Visits an `Expr::Function`.
+    fn visit_function_mut(&mut self, _name: &mut String, args: &mut Vec<Expr>) {
+        for arg in args {
+            self.visit_expr_mut(arg);
+        }
+    }
+
+    /// Visits an `Expr::Lambda`.
+    fn visit_lambda_mut(
+        &mut self,
+        collection: &mut Expr,
+        _op: &mut LambdaOperator,
+        _variable: &mut String,
+        body: &mut Expr,
+    ) {
+        self.visit_expr_mut(collection);
+        self.visit_expr_mut(body);
+    }
+
+    /// Visits an `Expr::Identifier`.
+    fn visit_identifier_mut(&mut self, _name: &mut String) {}
+
+    /// Visits an `Expr::Alias`.
+    fn visit_alias_mut(&mut self, _name: &mut String) {}
+
+    /// Visits an `Expr::Value`.
+    fn visit_value_mut(&mut self, _value: &mut Value) {}
+}
+
+/// Calls the `VisitorMut` method matching the variant of `expr`.
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Or(lhs, rhs) => visitor.visit_or_mut(lhs, rhs),
+        Expr::And(lhs, rhs) => visitor.visit_and_mut(lhs, rhs),
+        Expr::Not(inner) => visitor.visit_not_mut(inner),
+        Expr::Compare(lhs, op, rhs) => visitor.visit_compare_mut(lhs, op, rhs),
+        Expr::In(lhs, values) => visitor.visit_in_mut(lhs, values),
+        Expr::Function(name, args) => visitor.visit_function_mut(name, args),
+        Expr::Lambda(collection, op, variable, body) => {
+            visitor.visit_lambda_mut(collection, op, variable, body)
+        }
+        Expr::Identifier(name) => visitor.visit_identifier_mut(name),
+        Expr::Alias(name) => visitor.visit_alias_mut(name),
+        Expr::Value(value) => visitor.visit_value_mut(value),
+    }
+}
+
+/// Rebuilds an `Expr` tree by value.
+///
+/// Each method takes the parts of the matching `Expr` variant and returns the
+/// expression replacing it, which may be of a different variant. By default
+/// the children are folded and the node is rebuilt unchanged.
+///
+/// ```
+/// use odata_params::filters::{parse_str, Expr, Fold};
+///
+/// /// Replaces `not not x` with `x`.
+/// struct DoubleNegation;
+///
+/// impl Fold for DoubleNegation {
+///     fn fold_not(&mut self, expr: Expr) -> Expr {
+///         match self.fold_expr(expr) {
+///             Expr::Not(inner) => *inner,
+///             expr => Expr::Not(Box::new(expr)),
+///         }
+///     }
+/// }
+///
+/// let expr = parse_str("not (not active eq true)").expect("valid filter tree");
+///
+/// assert_eq!(
+///     DoubleNegation.fold_expr(expr),
+///     parse_str("active eq true").expect("valid filter tree"),
+/// );
+/// ```
+pub trait Fold {
+    /// Folds any expression, dispatching on its variant.
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_fold_expr(self, expr)
+    }
+
+    /// Folds an `Expr::Or`.
+    fn fold_or(&mut self, lhs: Expr, rhs: Expr) -> Expr {
+        Expr::Or(Box::new(self.fold_expr(lhs)), Box::new(self.fold_expr(rhs)))
+    }
+
+    /// Folds an `Expr::And`.
+    fn fold_and(&mut self, lhs: Expr, rhs: Expr) -> Expr {
+        Expr::And(Box::new(self.fold_expr(lhs)), Box::new(self.fold_expr(rhs)))
+    }
+
+    /// Folds an `Expr::Not`.
+    fn fold_not(&mut self, expr: Expr) -> Expr {
+        Expr::Not(Box::new(self.fold_expr(expr)))
+    }
+
+    /// Folds an `Expr::Compare`.
+    fn fold_compare(&mut self, lhs: Expr, op: CompareOperator, rhs: Expr) -> Expr {
+        Expr::Compare(
+            Box::new(self.fold_expr(lhs)),
+            op,
+            Box::new(self.fold_expr(rhs)),
+        )
+    }
+
+    /// Folds an `Expr::In`.
+    fn fold_in(&mut self, lhs: Expr, values: Vec<Expr>) -> Expr {
+        Expr::In(
+            Box::new(self.fold_expr(lhs)),
+            values
+                .into_iter()
+                .map(|value| self.fold_expr(value))
+                .collect(),
+        )
+    }
+
+    /// Folds an `Expr::Function`.
+    fn fold_function(&mut self, name: String, args: Vec<Expr>) -> Expr {
+        Expr::Function(
+            name,
+            args.into_iter().map(|arg| self.fold_expr(arg)).collect(),
+        )
+    }
+
+    /// Folds an `Expr::Lambda`.
+    fn fold_lambda(
+        &mut self,
+        collection: Expr,
+        op: LambdaOperator,
+        variable: String,
+        body: Expr,
+    ) -> Expr {
+        Expr::Lambda(
+            Box::new(self.fold_expr(collection)),
+            op,
+            variable,
+            Box::new(self.fold_expr(body)),
+        )
+    }
+
+    /// Folds an `Expr::Identifier`.
+    fn fold_identifier(&mut self, name: String) -> Expr {
+        Expr::Identifier(name)
+    }
+
+    /// Folds an `Expr::Alias`.
+    fn fold_alias(&mut self, name: String) -> Expr {
+        Expr::Alias(name)
+    }
+
+    /// Folds an `Expr::Value`.
+    fn fold_value(&mut self, value: Value) -> Expr {
+        Expr::Value(value)
+    }
+}
+
+/// Calls the `Fold` method matching the variant of `expr`.
+pub fn walk_fold_expr<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Or(lhs, rhs) => folder.fold_or(*lhs, *rhs),
+        Expr::And(lhs, rhs) => folder.fold_and(*lhs, *rhs),
+        Expr::Not(inner) => folder.fold_not(*inner),
+        Expr::Compare(lhs, op, rhs) => folder.fold_compare(*lhs, op, *rhs),
+        Expr::In(lhs, values) => folder.fold_in(*lhs, values),
+        Expr::Function(name, args) => folder.fold_function(name, args),
+        Expr::Lambda(collection, op, variable, body) => {
+            folder.fold_lambda(*collection, op, variable, *body)
+        }
+        Expr::Identifier(name) => folder.fold_identifier(name),
+        Expr::Alias(name) => folder.fold_alias(name),
+        Expr::Value(value) => folder.fold_value(value),
+    }
+}
+
+impl Expr {
+    /// Iterates over the identifiers referenced by the expression, in the
+    /// order they appear and including repeats.
+    ///
+    /// References to lambda variables, and paths starting from them, are left
+    /// out, while the collection a lambda ranges over is included.
+    ///
+    /// ```
+    /// use odata_params::filters::parse_str;
+    ///
+    /// let expr = parse_str("name eq 'Bob' and tags/any(tag: tag eq 'vip') and name ne ''")
+    ///     .expect("valid filter tree");
+    ///
+    /// assert_eq!(expr.identifiers().collect::<Vec<_>>(), ["name", "tags", "name"]);
+    /// ```
+    pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+        let mut collector = IdentifierCollector::default();
+        collector.visit_expr(self);
+        collector.identifiers.into_iter()
+    }
+
+    /// Iterates over the names of the functions called by the expression, in
+    /// the order they appear and including repeats.
+    ///
+    /// ```
+    /// use odata_params::filters::parse_str;
+    ///
+    /// let expr = parse_str("contains(tolower(name), 'bob')").expect("valid filter tree");
+    ///
+    /// assert_eq!(expr.functions().collect::<Vec<_>>(), ["contains", "tolower"]);
+    /// ```
+    pub fn functions(&self) -> impl Iterator<Item = &str> {
+        let mut collector = FunctionCollector::default();
+        collector.visit_expr(self);
+        collector.functions.into_iter()
+    }
+
+    /// Iterates over the constant values of the expression, in the order they
+    /// appear.
+    ///
+    /// ```
+    /// use odata_params::filters::{parse_str, Value};
+    ///
+    /// let expr = parse_str("name in ('Bob', 'Alice') or age eq null").expect("valid filter tree");
+    ///
+    /// assert_eq!(
+    ///     expr.values().collect::<Vec<_>>(),
+    ///     [
+    ///         &Value::String("Bob".to_owned()),
+    ///         &Value::String("Alice".to_owned()),
+    ///         &Value::Null,
+    ///     ],
+    /// );
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        let mut collector = ValueCollector::default();
+        collector.visit_expr(self);
+        collector.values.into_iter()
+    }
+}
+
+#[derive(Default)]
+struct IdentifierCollector<'a> {
+    scope: Vec<&'a str>,
+    identifiers: Vec<&'a str>,
+}
+
+impl<'a> Visitor<'a> for IdentifierCollector<'a> {
+    fn visit_lambda(
+        &mut self,
+        collection: &'a Expr,
+        _op: &'a LambdaOperator,
+        variable: &'a str,
+        body: &'a Expr,
+    ) {
+        self.visit_expr(collection);
+        self.scope.push(variable);
+        self.visit_expr(body);
+        self.scope.pop();
+    }
+
+    fn visit_identifier(&mut self, name: &'a str) {
+        if !self.scope.contains(&root(name)) {
+            self.identifiers.push(name);
+        }
+    }
+}
+
+/// The first identifier of a property path such as `owner/name`.
+pub(super) fn root(path: &str) -> &str {
+    path.split_once('/').map_or(path, |(root, _)| root)
+}
+
+/// A property path followed by every path it starts with, such as `a/b/c`,
+/// `a` and `a/b`.
+pub(super) fn prefixes(path: &str) -> impl Iterator<Item = &str> {
+    [path]
+        .into_iter()
+        .chain(path.match_indices('/').map(|(index, _)| &path[..index]))
+}
+
+#[derive(Default)]
+struct FunctionCollector<'a> {
+    functions: Vec<&'a str>,
+}
+
+impl<'a> Visitor<'a> for FunctionCollector<'a> {
+    fn visit_function(&mut self, name: &'a str, args: &'a [Expr]) {
+        self.functions.push(name);
+
+        for arg in args {
+            self.visit_expr(arg);
+        }
+    }
+}
+
+#[derive(Default)]
+struct ValueCollector<'a> {
+    values: Vec<&'a Value>,
+}
+
+impl<'a> Visitor<'a> for ValueCollector<'a> {
+    fn visit_value(&mut self, value: &'a Value) {
+        self.values.push(value);
+    }
+}
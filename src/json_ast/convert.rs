@@ -0,0 +1,183 @@
+use super::{JsonAstError, JsonExpr, JsonFilter};
+use crate::filters::{CompareOperator, Expr, LambdaOperator, Value};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use std::str::FromStr;
+use uuid::Uuid;
+
+impl From<&Expr> for JsonExpr {
+    fn from(expr: &Expr) -> Self {
+        let boxed = |expr: &Expr| Box::new(JsonExpr::from(expr));
+        let list = |exprs: &[Expr]| exprs.iter().map(JsonExpr::from).collect();
+
+        match expr {
+            Expr::And(left, right) => JsonExpr::And {
+                left: boxed(left),
+                right: boxed(right),
+            },
+            Expr::Or(left, right) => JsonExpr::Or {
+                left: boxed(left),
+                right: boxed(right),
+            },
+            Expr::Not(operand) => JsonExpr::Not {
+                operand: boxed(operand),
+            },
+            Expr::Compare(left, op, right) => {
+                let (left, right) = (boxed(left), boxed(right));
+
+                match op {
+                    CompareOperator::Equal => JsonExpr::Eq { left, right },
+                    CompareOperator::NotEqual => JsonExpr::Ne { left, right },
+                    CompareOperator::GreaterThan => JsonExpr::Gt { left, right },
+                    CompareOperator::GreaterOrEqual => JsonExpr::Ge { left, right },
+                    CompareOperator::LessThan => JsonExpr::Lt { left, right },
+                    CompareOperator::LessOrEqual => JsonExpr::Le { left, right },
+                    CompareOperator::Has => JsonExpr::Has { left, right },
+                }
+            }
+            Expr::In(left, values) => JsonExpr::In {
+                left: boxed(left),
+                values: list(values),
+            },
+            Expr::Function(name, args) => JsonExpr::Call {
+                name: name.clone(),
+                args: list(args),
+            },
+            Expr::Lambda(collection, op, variable, body) => {
+                let (collection, variable, body) =
+                    (boxed(collection), variable.clone(), boxed(body));
+
+                match op {
+                    LambdaOperator::Any => JsonExpr::Any {
+                        collection,
+                        variable,
+                        body,
+                    },
+                    LambdaOperator::All => JsonExpr::All {
+                        collection,
+                        variable,
+                        body,
+                    },
+                }
+            }
+            Expr::Identifier(name) => JsonExpr::Identifier { name: name.clone() },
+            Expr::Alias(name) => JsonExpr::Alias { name: name.clone() },
+            Expr::Value(value) => match value {
+                Value::Null => JsonExpr::Null,
+                Value::Bool(value) => JsonExpr::Boolean { value: *value },
+                Value::Number(value) => JsonExpr::Number {
+                    value: value.to_plain_string(),
+                },
+                Value::String(value) => JsonExpr::String {
+                    value: value.clone(),
+                },
+                Value::Uuid(value) => JsonExpr::Uuid {
+                    value: value.to_string(),
+                },
+                Value::DateTime(value) => JsonExpr::DateTime {
+                    value: value.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                },
+                Value::Date(value) => JsonExpr::Date {
+                    value: value.to_string(),
+                },
+                Value::Time(value) => JsonExpr::Time {
+                    value: value.to_string(),
+                },
+            },
+        }
+    }
+}
+
+impl TryFrom<JsonExpr> for Expr {
+    type Error = JsonAstError;
+
+    fn try_from(expr: JsonExpr) -> Result<Self, Self::Error> {
+        let boxed = |expr: Box<JsonExpr>| Expr::try_from(*expr).map(Box::new);
+        let list = |exprs: Vec<JsonExpr>| {
+            exprs
+                .into_iter()
+                .map(Expr::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let compare = |left, op, right| Ok(Expr::Compare(boxed(left)?, op, boxed(right)?));
+        let lambda = |collection, op, variable, body| {
+            Ok(Expr::Lambda(boxed(collection)?, op, variable, boxed(body)?))
+        };
+
+        match expr {
+            JsonExpr::And { left, right } => Ok(Expr::And(boxed(left)?, boxed(right)?)),
+            JsonExpr::Or { left, right } => Ok(Expr::Or(boxed(left)?, boxed(right)?)),
+            JsonExpr::Not { operand } => Ok(Expr::Not(boxed(operand)?)),
+            JsonExpr::Eq { left, right } => compare(left, CompareOperator::Equal, right),
+            JsonExpr::Ne { left, right } => compare(left, CompareOperator::NotEqual, right),
+            JsonExpr::Gt { left, right } => compare(left, CompareOperator::GreaterThan, right),
+            JsonExpr::Ge { left, right } => compare(left, CompareOperator::GreaterOrEqual, right),
+            JsonExpr::Lt { left, right } => compare(left, CompareOperator::LessThan, right),
+            JsonExpr::Le { left, right } => compare(left, CompareOperator::LessOrEqual, right),
+            JsonExpr::Has { left, right } => compare(left, CompareOperator::Has, right),
+            JsonExpr::In { left, values } => Ok(Expr::In(boxed(left)?, list(values)?)),
+            JsonExpr::Call { name, args } => Ok(Expr::Function(name, list(args)?)),
+            JsonExpr::Any {
+                collection,
+                variable,
+                body,
+            } => lambda(collection, LambdaOperator::Any, variable, body),
+            JsonExpr::All {
+                collection,
+                variable,
+                body,
+            } => lambda(collection, LambdaOperator::All, variable, body),
+            JsonExpr::Identifier { name } => Ok(Expr::Identifier(name)),
+            JsonExpr::Alias { name } => Ok(Expr::Alias(name)),
+            JsonExpr::Null => Ok(Expr::Value(Value::Null)),
+            JsonExpr::Boolean { value } => Ok(Expr::Value(Value::Bool(value))),
+            JsonExpr::Number { value } => literal("number", value, |value| {
+                BigDecimal::from_str(value).ok().map(Value::Number)
+            }),
+            JsonExpr::String { value } => Ok(Expr::Value(Value::String(value))),
+            JsonExpr::Uuid { value } => literal("uuid", value, |value| {
+                Uuid::parse_str(value).ok().map(Value::Uuid)
+            }),
+            JsonExpr::DateTime { value } => literal("datetime", value, |value| {
+                DateTime::parse_from_rfc3339(value)
+                    .ok()
+                    .map(|value| Value::DateTime(value.with_timezone(&Utc)))
+            }),
+            JsonExpr::Date { value } => literal("date", value, |value| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .ok()
+                    .map(Value::Date)
+            }),
+            JsonExpr::Time { value } => literal("time", value, |value| {
+                NaiveTime::from_str(value).ok().map(Value::Time)
+            }),
+        }
+    }
+}
+
+impl TryFrom<JsonFilter> for Expr {
+    type Error = JsonAstError;
+
+    fn try_from(filter: JsonFilter) -> Result<Self, Self::Error> {
+        filter.into_expr()
+    }
+}
+
+impl From<&Expr> for JsonFilter {
+    fn from(expr: &Expr) -> Self {
+        JsonFilter::new(expr)
+    }
+}
+
+/// Reads a literal value, failing with its `op` and text when it is not in
+/// the expected format.
+fn literal(
+    op: &'static str,
+    value: String,
+    read: impl FnOnce(&str) -> Option<Value>,
+) -> Result<Expr, JsonAstError> {
+    match read(&value) {
+        Some(value) => Ok(Expr::Value(value)),
+        None => Err(JsonAstError::InvalidLiteral { op, value }),
+    }
+}
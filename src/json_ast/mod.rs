@@ -0,0 +1,257 @@
+//! A documented, versioned JSON representation of `$filter` trees, for
+//! sharing structured filters with other languages.
+//!
+//! Unlike the derived serde layout of `Expr`, which follows the Rust types
+//! and may change between releases, this format is pinned by tests and
+//! described by a JSON Schema. A document wraps the tree with the version of
+//! the format:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "expr": {
+//!     "op": "and",
+//!     "left": {
+//!       "op": "gt",
+//!       "left": { "op": "identifier", "name": "age" },
+//!       "right": { "op": "number", "value": "30" }
+//!     },
+//!     "right": {
+//!       "op": "call",
+//!       "name": "startswith",
+//!       "args": [
+//!         { "op": "identifier", "name": "name" },
+//!         { "op": "string", "value": "J" }
+//!       ]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Every node is an object tagged by `op`:
+//!
+//! | `op`                                     | Members                             |
+//! |------------------------------------------|-------------------------------------|
+//! | `and`, `or`                              | `left`, `right`                     |
+//! | `not`                                    | `operand`                           |
+//! | `eq`, `ne`, `gt`, `ge`, `lt`, `le`, `has`| `left`, `right`                     |
+//! | `in`                                     | `left`, `values`                    |
+//! | `call`                                   | `name`, `args`                      |
+//! | `any`, `all`                             | `collection`, `variable`, `body`    |
+//! | `identifier`                             | `name`, a property path such as `owner/name` |
+//! | `alias`                                  | `name`, including the `@` as in `@p1` |
+//! | `null`                                   |                                     |
+//! | `boolean`                                | `value`, a JSON boolean             |
+//! | `number`                                 | `value`, a decimal string such as `"-12.50"` |
+//! | `string`                                 | `value`                             |
+//! | `uuid`                                   | `value`, such as `"da820b39-5ad2-4441-b664-c902dbd377d8"` |
+//! | `datetime`                               | `value`, in RFC 3339 such as `"2024-01-02T03:04:05.5Z"` |
+//! | `date`                                   | `value`, such as `"2024-01-02"`     |
+//! | `time`                                   | `value`, such as `"12:30:00"`       |
+//!
+//! Numbers are strings so that no precision is lost in languages reading
+//! JSON numbers as doubles. Date and times are written in UTC.
+//!
+//! Any change that could break a reader of the format increases `VERSION`,
+//! and documents of other versions are rejected when read. With the
+//! `json-schema` feature, `json_schema` generates the JSON Schema of the
+//! format, which is also kept in `schema/json-ast-v1.schema.json`.
+
+mod convert;
+
+use crate::filters::Expr;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the format written by this crate, and the only one read.
+pub const VERSION: u32 = 1;
+
+/// Represents errors that can occur while converting the JSON format to an
+/// `Expr`.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum JsonAstError {
+    /// The document is of another version of the format.
+    #[error("Unsupported JSON AST version {version}, expected {VERSION}.")]
+    UnsupportedVersion { version: u32 },
+
+    /// A literal value is not in the format of its `op`.
+    #[error("Invalid {op} literal '{value}'.")]
+    InvalidLiteral { op: &'static str, value: String },
+}
+
+/// A filter in the versioned JSON format.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct JsonFilter {
+    /// The version of the format the document follows.
+    #[cfg_attr(feature = "json-schema", schemars(extend("const" = 1)))]
+    pub version: u32,
+
+    /// The filter expression.
+    pub expr: JsonExpr,
+}
+
+/// A node of a filter expression in the versioned JSON format.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+pub enum JsonExpr {
+    /// Logical AND between two expressions.
+    And {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Logical OR between two expressions.
+    Or {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Logical NOT of an expression.
+    Not { operand: Box<JsonExpr> },
+
+    /// Equal comparison.
+    Eq {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Not equal comparison.
+    Ne {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Greater than comparison.
+    Gt {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Greater than or equal comparison.
+    Ge {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Less than comparison.
+    Lt {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Less than or equal comparison.
+    Le {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Enum flags comparison.
+    Has {
+        left: Box<JsonExpr>,
+        right: Box<JsonExpr>,
+    },
+
+    /// Whether an expression is one of a list of values.
+    In {
+        left: Box<JsonExpr>,
+        values: Vec<JsonExpr>,
+    },
+
+    /// Function call.
+    Call { name: String, args: Vec<JsonExpr> },
+
+    /// Whether any item of a collection matches the body.
+    Any {
+        collection: Box<JsonExpr>,
+        variable: String,
+        body: Box<JsonExpr>,
+    },
+
+    /// Whether all items of a collection match the body.
+    All {
+        collection: Box<JsonExpr>,
+        variable: String,
+        body: Box<JsonExpr>,
+    },
+
+    /// Identifier or property path, such as `owner/name`.
+    Identifier { name: String },
+
+    /// Parameter alias, including its `@`.
+    Alias { name: String },
+
+    /// The null value.
+    Null,
+
+    /// Boolean value.
+    Boolean { value: bool },
+
+    /// Number value as a decimal string, such as `-12.50`.
+    Number { value: String },
+
+    /// String value.
+    String { value: String },
+
+    /// UUID value, such as `da820b39-5ad2-4441-b664-c902dbd377d8`.
+    Uuid { value: String },
+
+    /// Date and time value in RFC 3339, such as `2024-01-02T03:04:05Z`.
+    DateTime { value: String },
+
+    /// Date value, such as `2024-01-02`.
+    Date { value: String },
+
+    /// Time value, such as `12:30:00` or `12:30:00.250`.
+    Time { value: String },
+}
+
+/// Generates the JSON Schema of the format, describing a `JsonFilter`.
+///
+/// ```
+/// let schema = odata_params::json_ast::json_schema();
+///
+/// assert_eq!(schema.get("title"), Some(&"JsonFilter".into()));
+/// ```
+#[cfg(feature = "json-schema")]
+pub fn json_schema() -> schemars::Schema {
+    schemars::schema_for!(JsonFilter)
+}
+
+impl JsonFilter {
+    /// Converts an expression to a document of the current version.
+    ///
+    /// ```
+    /// use odata_params::filters::parse_str;
+    /// use odata_params::json_ast::JsonFilter;
+    ///
+    /// let expr = parse_str("age gt 30 and startswith(name, 'J')").expect("valid filter tree");
+    /// let json = serde_json::to_value(JsonFilter::new(&expr)).unwrap();
+    ///
+    /// assert_eq!(json["version"], 1);
+    /// assert_eq!(json["expr"]["op"], "and");
+    /// assert_eq!(json["expr"]["left"]["right"], serde_json::json!({ "op": "number", "value": "30" }));
+    ///
+    /// let read: JsonFilter = serde_json::from_value(json).unwrap();
+    /// assert_eq!(read.into_expr(), Ok(expr));
+    /// ```
+    pub fn new(expr: &Expr) -> Self {
+        Self {
+            version: VERSION,
+            expr: JsonExpr::from(expr),
+        }
+    }
+
+    /// Converts the document to an expression, checking its version.
+    pub fn into_expr(self) -> Result<Expr, JsonAstError> {
+        if self.version != VERSION {
+            return Err(JsonAstError::UnsupportedVersion {
+                version: self.version,
+            });
+        }
+
+        Expr::try_from(self.expr)
+    }
+}
@@ -1,8 +1,17 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "csdl")]
+pub mod csdl;
 pub mod filters;
+#[cfg(feature = "serde")]
+pub mod json_ast;
+pub mod messages;
+pub mod orderby;
+pub mod query;
+pub mod search;
 
 // Re-exports
 pub use bigdecimal;
 pub use chrono;
 pub use chrono_tz;
+pub use uuid;
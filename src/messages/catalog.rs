@@ -0,0 +1,115 @@
+use super::{Localize, MessageCatalog};
+
+impl MessageCatalog {
+    /// Creates a catalogue without templates, rendering every error with its
+    /// English `Display` message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the template of a message key in a locale, replacing any
+    /// previous one.
+    pub fn with_message(
+        mut self,
+        locale: impl Into<String>,
+        key: impl Into<String>,
+        template: impl Into<String>,
+    ) -> Self {
+        self.locales
+            .entry(locale.into())
+            .or_default()
+            .insert(key.into(), template.into());
+        self
+    }
+
+    /// Sets the templates of several message keys in a locale.
+    pub fn with_messages<K: Into<String>, T: Into<String>>(
+        mut self,
+        locale: impl Into<String>,
+        messages: impl IntoIterator<Item = (K, T)>,
+    ) -> Self {
+        self.locales.entry(locale.into()).or_default().extend(
+            messages
+                .into_iter()
+                .map(|(key, template)| (key.into(), template.into())),
+        );
+        self
+    }
+
+    /// The template of a message key in a locale or its language.
+    pub fn template(&self, locale: &str, key: &str) -> Option<&str> {
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+
+        [locale, language]
+            .into_iter()
+            .find_map(|locale| self.locales.get(locale)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Renders an error in a locale.
+    ///
+    /// Without a template in the locale, errors use their `Display` message,
+    /// still rendering the nested errors it includes in the locale.
+    pub fn render<E: Localize + ?Sized>(&self, error: &E, locale: &str) -> String {
+        let args = error.message_args(self, locale);
+
+        if let Some(template) = self.template(locale, error.message_key()) {
+            return interpolate(template, &args);
+        }
+
+        // Arguments differing from their English rendering are nested errors
+        // rendered in the locale, which replace their text in the message.
+        let mut message = error.to_string();
+        let english = error.message_args(&MessageCatalog::new(), locale);
+
+        for ((_, localized), (_, english)) in args.iter().zip(&english) {
+            if localized != english {
+                if let Some(start) = message.rfind(english.as_str()) {
+                    message.replace_range(start..start + english.len(), localized);
+                }
+            }
+        }
+
+        message
+    }
+}
+
+/// Replaces the `{name}` placeholders of a template with the value of the
+/// argument of the same name, leaving unknown placeholders as they are.
+fn interpolate(template: &str, args: &[(&str, String)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            output.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+
+        let placeholder = rest
+            .find('}')
+            .filter(|_| rest.starts_with('{'))
+            .and_then(|end| {
+                let (_, value) = args.iter().find(|(name, _)| *name == &rest[1..end])?;
+                Some((end, value))
+            });
+
+        match placeholder {
+            Some((end, value)) => {
+                output.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
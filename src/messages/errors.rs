@@ -0,0 +1,204 @@
+use super::{Localize, MessageCatalog};
+use crate::filters::{ParseError, PolicyViolation, ValidationError};
+use crate::query::QueryError;
+
+impl Localize for ParseError {
+    fn message_key(&self) -> &'static str {
+        match self {
+            ParseError::Parsing { .. } => "ParseError.Parsing",
+            ParseError::ParsingUuid => "ParseError.ParsingUuid",
+            ParseError::ParsingNumber => "ParseError.ParsingNumber",
+            ParseError::ParsingDate => "ParseError.ParsingDate",
+            ParseError::ParsingTime => "ParseError.ParsingTime",
+            ParseError::ParsingDateTime => "ParseError.ParsingDateTime",
+            ParseError::ParsingTimeZone => "ParseError.ParsingTimeZone",
+            ParseError::ParsingTimeZoneNamed => "ParseError.ParsingTimeZoneNamed",
+            ParseError::ParsingUnicodeCodePoint => "ParseError.ParsingUnicodeCodePoint",
+            ParseError::InputTooLong { .. } => "ParseError.InputTooLong",
+            ParseError::NestingTooDeep { .. } => "ParseError.NestingTooDeep",
+            ParseError::TooManyNodes { .. } => "ParseError.TooManyNodes",
+            ParseError::InListTooLarge { .. } => "ParseError.InListTooLarge",
+            ParseError::LambdaNestingTooDeep { .. } => "ParseError.LambdaNestingTooDeep",
+            ParseError::StringLiteralTooLong { .. } => "ParseError.StringLiteralTooLong",
+        }
+    }
+
+    fn message_args(
+        &self,
+        _catalog: &MessageCatalog,
+        _locale: &str,
+    ) -> Vec<(&'static str, String)> {
+        match self {
+            ParseError::Parsing { position } => vec![("position", position.to_string())],
+            ParseError::InputTooLong { length, max } => {
+                vec![("length", length.to_string()), ("max", max.to_string())]
+            }
+            ParseError::NestingTooDeep { max }
+            | ParseError::TooManyNodes { max }
+            | ParseError::InListTooLarge { max }
+            | ParseError::LambdaNestingTooDeep { max }
+            | ParseError::StringLiteralTooLong { max } => vec![("max", max.to_string())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Localize for ValidationError {
+    fn message_key(&self) -> &'static str {
+        match self {
+            ValidationError::LogicalJoinRequiresBooleans { .. } => {
+                "ValidationError.LogicalJoinRequiresBooleans"
+            }
+            ValidationError::LogicalNotRequiresBoolean { .. } => {
+                "ValidationError.LogicalNotRequiresBoolean"
+            }
+            ValidationError::ComparingIncompatibleTypes { .. } => {
+                "ValidationError.ComparingIncompatibleTypes"
+            }
+            ValidationError::UndefinedIdentifier { .. } => "ValidationError.UndefinedIdentifier",
+            ValidationError::UndefinedFunction { .. } => "ValidationError.UndefinedFunction",
+            ValidationError::IncorrectFunctionArgumentsCount {
+                is_variadic: false, ..
+            } => "ValidationError.IncorrectFunctionArgumentsCount",
+            ValidationError::IncorrectFunctionArgumentsCount {
+                is_variadic: true, ..
+            } => "ValidationError.IncorrectFunctionArgumentsCountVariadic",
+            ValidationError::IncorrectFunctionArgumentType { .. } => {
+                "ValidationError.IncorrectFunctionArgumentType"
+            }
+        }
+    }
+
+    fn message_args(
+        &self,
+        _catalog: &MessageCatalog,
+        _locale: &str,
+    ) -> Vec<(&'static str, String)> {
+        match self {
+            ValidationError::LogicalJoinRequiresBooleans { lhs, rhs }
+            | ValidationError::ComparingIncompatibleTypes { lhs, rhs } => {
+                vec![("lhs", format!("{lhs:?}")), ("rhs", format!("{rhs:?}"))]
+            }
+            ValidationError::LogicalNotRequiresBoolean { given } => {
+                vec![("given", format!("{given:?}"))]
+            }
+            ValidationError::UndefinedIdentifier { name }
+            | ValidationError::UndefinedFunction { name } => vec![("name", name.clone())],
+            ValidationError::IncorrectFunctionArgumentsCount {
+                name,
+                expected,
+                given,
+                ..
+            } => vec![
+                ("name", name.clone()),
+                ("expected", expected.to_string()),
+                ("given", given.to_string()),
+            ],
+            ValidationError::IncorrectFunctionArgumentType {
+                name,
+                position,
+                expected,
+                given,
+            } => vec![
+                ("name", name.clone()),
+                ("position", position.to_string()),
+                ("expected", format!("{expected:?}")),
+                ("given", format!("{given:?}")),
+            ],
+        }
+    }
+}
+
+impl Localize for PolicyViolation {
+    fn message_key(&self) -> &'static str {
+        match self {
+            PolicyViolation::OperatorNotAllowed { .. } => "PolicyViolation.OperatorNotAllowed",
+            PolicyViolation::OperatorNotAllowedOn { .. } => "PolicyViolation.OperatorNotAllowedOn",
+            PolicyViolation::InNotAllowed => "PolicyViolation.InNotAllowed",
+            PolicyViolation::InNotAllowedOn { .. } => "PolicyViolation.InNotAllowedOn",
+            PolicyViolation::FunctionNotAllowed { .. } => "PolicyViolation.FunctionNotAllowed",
+            PolicyViolation::LambdaNotAllowed { .. } => "PolicyViolation.LambdaNotAllowed",
+            PolicyViolation::IdentifierNotFilterable { .. } => {
+                "PolicyViolation.IdentifierNotFilterable"
+            }
+        }
+    }
+
+    fn message_args(
+        &self,
+        _catalog: &MessageCatalog,
+        _locale: &str,
+    ) -> Vec<(&'static str, String)> {
+        match self {
+            PolicyViolation::OperatorNotAllowed { operator } => {
+                vec![("operator", operator.to_string())]
+            }
+            PolicyViolation::OperatorNotAllowedOn {
+                identifier,
+                operator,
+            } => vec![
+                ("identifier", identifier.clone()),
+                ("operator", operator.to_string()),
+            ],
+            PolicyViolation::InNotAllowed => Vec::new(),
+            PolicyViolation::InNotAllowedOn { identifier }
+            | PolicyViolation::IdentifierNotFilterable { identifier } => {
+                vec![("identifier", identifier.clone())]
+            }
+            PolicyViolation::FunctionNotAllowed { name } => vec![("name", name.clone())],
+            PolicyViolation::LambdaNotAllowed { operator } => {
+                vec![("operator", operator.to_string())]
+            }
+        }
+    }
+}
+
+impl Localize for QueryError {
+    fn message_key(&self) -> &'static str {
+        match self {
+            QueryError::Filter { .. } => "QueryError.Filter",
+            QueryError::OrderBy { .. } => "QueryError.OrderBy",
+            QueryError::Search { .. } => "QueryError.Search",
+            QueryError::InvalidTop { .. } => "QueryError.InvalidTop",
+            QueryError::InvalidSkip { .. } => "QueryError.InvalidSkip",
+            QueryError::InvalidCount { .. } => "QueryError.InvalidCount",
+            QueryError::InvalidSelect { .. } => "QueryError.InvalidSelect",
+            QueryError::DuplicateOption { .. } => "QueryError.DuplicateOption",
+            QueryError::UnsupportedOption { .. } => "QueryError.UnsupportedOption",
+            QueryError::Validation { .. } => "QueryError.Validation",
+            QueryError::FilterNotBoolean { .. } => "QueryError.FilterNotBoolean",
+            QueryError::FilterNotAllowed { .. } => "QueryError.FilterNotAllowed",
+        }
+    }
+
+    fn message_args(&self, catalog: &MessageCatalog, locale: &str) -> Vec<(&'static str, String)> {
+        match self {
+            QueryError::Filter { error }
+            | QueryError::OrderBy { error }
+            | QueryError::Search { error } => {
+                vec![("error", catalog.render(error, locale))]
+            }
+            QueryError::InvalidTop { value }
+            | QueryError::InvalidSkip { value }
+            | QueryError::InvalidCount { value }
+            | QueryError::InvalidSelect { value } => vec![("value", value.clone())],
+            QueryError::DuplicateOption { name } | QueryError::UnsupportedOption { name } => {
+                vec![("name", name.clone())]
+            }
+            QueryError::Validation { option, error } => vec![
+                ("option", option.clone()),
+                ("error", catalog.render(error, locale)),
+            ],
+            QueryError::FilterNotBoolean { given } => vec![("given", format!("{given:?}"))],
+            QueryError::FilterNotAllowed { violations } => {
+                let violations = violations
+                    .iter()
+                    .map(|violation| catalog.render(violation, locale))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                vec![("violations", violations)]
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Localized rendering of the errors of this crate.
+//!
+//! Every error keeps its English `Display` message, which stays the default.
+//! A `MessageCatalog` holds message templates by locale, under the message
+//! key of each error such as `ValidationError.UndefinedIdentifier`, and
+//! renders errors with them. Templates interpolate the fields of an error by
+//! name, as in `Identifiant '{name}' non défini.`, and `{{` and `}}` write
+//! literal braces.
+//!
+//! | Key                                                | Fields                                |
+//! |----------------------------------------------------|---------------------------------------|
+//! | `ParseError.Parsing`                               | `position`                            |
+//! | `ParseError.ParsingUuid` and other unit variants   |                                       |
+//! | `ParseError.InputTooLong`                          | `length`, `max`                       |
+//! | `ParseError.NestingTooDeep` and other limits       | `max`                                 |
+//! | `ValidationError.LogicalJoinRequiresBooleans`      | `lhs`, `rhs`                          |
+//! | `ValidationError.LogicalNotRequiresBoolean`        | `given`                               |
+//! | `ValidationError.ComparingIncompatibleTypes`       | `lhs`, `rhs`                          |
+//! | `ValidationError.UndefinedIdentifier`              | `name`                                |
+//! | `ValidationError.UndefinedFunction`                | `name`                                |
+//! | `ValidationError.IncorrectFunctionArgumentsCount`  | `name`, `expected`, `given`           |
+//! | `ValidationError.IncorrectFunctionArgumentsCountVariadic` | `name`, `expected`, `given`    |
+//! | `ValidationError.IncorrectFunctionArgumentType`    | `name`, `position`, `expected`, `given` |
+//! | `PolicyViolation.OperatorNotAllowed`               | `operator`                            |
+//! | `PolicyViolation.OperatorNotAllowedOn`             | `identifier`, `operator`              |
+//! | `PolicyViolation.InNotAllowed`                     |                                       |
+//! | `PolicyViolation.InNotAllowedOn`                   | `identifier`                          |
+//! | `PolicyViolation.FunctionNotAllowed`               | `name`                                |
+//! | `PolicyViolation.LambdaNotAllowed`                 | `operator`                            |
+//! | `PolicyViolation.IdentifierNotFilterable`          | `identifier`                          |
+//! | `QueryError.Filter` and other invalid expressions  | `error`                               |
+//! | `QueryError.InvalidTop` and other invalid values   | `value`                               |
+//! | `QueryError.DuplicateOption`                       | `name`                                |
+//! | `QueryError.UnsupportedOption`                     | `name`                                |
+//! | `QueryError.Validation`                            | `option`, `error`                     |
+//! | `QueryError.FilterNotBoolean`                      | `given`                               |
+//! | `QueryError.FilterNotAllowed`                      | `violations`                          |
+//!
+//! Nested errors, such as the `error` of `QueryError.Filter` and the
+//! `violations` of `QueryError.FilterNotAllowed`, are rendered in the same
+//! locale before being interpolated.
+
+mod catalog;
+mod errors;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// An error that can be rendered by a `MessageCatalog`.
+pub trait Localize: Display {
+    /// The key of the message template of the error, such as
+    /// `ValidationError.UndefinedIdentifier`.
+    fn message_key(&self) -> &'static str;
+
+    /// The fields of the error templates can interpolate, with nested errors
+    /// rendered by `catalog` in `locale`.
+    fn message_args(&self, catalog: &MessageCatalog, locale: &str) -> Vec<(&'static str, String)>;
+}
+
+/// Message templates by locale.
+///
+/// Rendering in a locale such as `fr-CA` uses its templates, then those of
+/// its language `fr`, and falls back to the English message of the error
+/// when neither has one.
+///
+/// ```
+/// use odata_params::filters::{PolicyViolation, ValidationError};
+/// use odata_params::messages::MessageCatalog;
+/// use odata_params::query::QueryError;
+///
+/// let catalog = MessageCatalog::new()
+///     .with_messages(
+///         "fr",
+///         [
+///             ("ValidationError.UndefinedIdentifier", "Identifiant '{name}' non défini."),
+///             ("QueryError.Validation", "{option} invalide : {error}"),
+///         ],
+///     )
+///     .with_message("en", "ValidationError.UndefinedIdentifier", "Unknown field '{name}'.");
+///
+/// let error = QueryError::Validation {
+///     option: "$filter".to_string(),
+///     error: ValidationError::UndefinedIdentifier {
+///         name: "height".to_string(),
+///     },
+/// };
+///
+/// assert_eq!(catalog.render(&error, "fr-CA"), "$filter invalide : Identifiant 'height' non défini.");
+/// assert_eq!(catalog.render(&error, "en"), "Invalid $filter: Unknown field 'height'.");
+/// assert_eq!(
+///     catalog.render(&PolicyViolation::InNotAllowed, "ja"),
+///     "The 'in' operator is not allowed.",
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MessageCatalog {
+    locales: HashMap<String, HashMap<String, String>>,
+}
@@ -0,0 +1,43 @@
+mod parse;
+mod to_query_string;
+
+use crate::filters::Expr;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub use parse::{parse_str, parse_str_with};
+pub use to_query_string::{
+    to_query_string, to_query_string_with, write_query_string, write_query_string_with,
+};
+
+/// Represents one item of an `$orderby` query option.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OrderBy {
+    /// Expression to order by, usually an identifier.
+    pub expr: Expr,
+
+    /// Direction to order in.
+    pub direction: Direction,
+}
+
+/// Represents the direction of an `$orderby` item.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    /// Ascending order, the default when no direction is given.
+    #[default]
+    Ascending,
+
+    /// Descending order.
+    Descending,
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Ascending => write!(f, "asc"),
+            Direction::Descending => write!(f, "desc"),
+        }
+    }
+}
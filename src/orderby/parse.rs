@@ -0,0 +1,35 @@
+use super::OrderBy;
+use crate::filters::{parse_order_by_with, ParseError, ParseOptions};
+
+/// Parses an OData v4 `$orderby` expression string into a list of items.
+///
+/// Each item is an expression as found in `$filter`, optionally followed by
+/// `asc` or `desc`.
+///
+/// ```
+/// use odata_params::filters::Expr;
+/// use odata_params::orderby::{parse_str, Direction, OrderBy};
+///
+/// let items = parse_str("owner/name desc, length(title)").expect("valid order by");
+///
+/// assert_eq!(
+///     items[0],
+///     OrderBy {
+///         expr: Expr::Identifier("owner/name".to_owned()),
+///         direction: Direction::Descending,
+///     },
+/// );
+/// assert_eq!(items[1].direction, Direction::Ascending);
+/// ```
+pub fn parse_str(query: impl AsRef<str>) -> Result<Vec<OrderBy>, ParseError> {
+    parse_str_with(query, &ParseOptions::default())
+}
+
+/// Parses an OData v4 `$orderby` expression string into a list of items
+/// while enforcing the limits set in `options`.
+pub fn parse_str_with(
+    query: impl AsRef<str>,
+    options: &ParseOptions,
+) -> Result<Vec<OrderBy>, ParseError> {
+    parse_order_by_with(query.as_ref(), options)
+}
@@ -0,0 +1,113 @@
+use super::{Direction, OrderBy};
+use crate::filters::{
+    write_query_string as write_expr, write_query_string_with as write_expr_with,
+};
+use crate::filters::{FormatOptions, PercentEncoder};
+use std::fmt::{self, Write};
+
+/// Converts `$orderby` items to their corresponding OData string
+/// representation, leaving out the default `asc` direction.
+///
+/// ```
+/// use odata_params::orderby::{parse_str, to_query_string};
+///
+/// let items = parse_str("name asc,age   desc").expect("valid order by");
+///
+/// assert_eq!(to_query_string(&items).expect("valid order by"), "name, age desc");
+/// ```
+pub fn to_query_string(items: &[OrderBy]) -> Result<String, fmt::Error> {
+    let mut output = String::new();
+    write_query_string(&mut output, items)?;
+    Ok(output)
+}
+
+/// Writes `$orderby` items to a writer as their corresponding OData string
+/// representation.
+pub fn write_query_string<W: Write>(writer: &mut W, items: &[OrderBy]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(writer, ", ")?;
+        }
+
+        write_expr(writer, &item.expr)?;
+
+        if item.direction == Direction::Descending {
+            write!(writer, " {}", item.direction)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts `$orderby` items to their corresponding OData string
+/// representation, written as set in `options`.
+///
+/// Items are always written on a single line.
+///
+/// ```
+/// use odata_params::filters::{FormatOptions, KeywordCase};
+/// use odata_params::orderby::{parse_str, to_query_string_with};
+///
+/// let items = parse_str("name, created desc").expect("valid order by");
+/// let options = FormatOptions {
+///     keyword_case: KeywordCase::Upper,
+///     percent_encode: true,
+///     ..FormatOptions::default()
+/// };
+///
+/// assert_eq!(
+///     to_query_string_with(&items, &options).expect("valid order by"),
+///     "name%2C%20created%20DESC",
+/// );
+/// ```
+pub fn to_query_string_with(
+    items: &[OrderBy],
+    options: &FormatOptions,
+) -> Result<String, fmt::Error> {
+    let mut output = String::new();
+    write_query_string_with(&mut output, items, options)?;
+    Ok(output)
+}
+
+/// Writes `$orderby` items to a writer as their corresponding OData string
+/// representation, written as set in `options`.
+pub fn write_query_string_with<W: Write>(
+    writer: &mut W,
+    items: &[OrderBy],
+    options: &FormatOptions,
+) -> fmt::Result {
+    // Expressions are written inline and encoded along with the items.
+    let inline = &FormatOptions {
+        indent: None,
+        percent_encode: false,
+        ..*options
+    };
+
+    match options.percent_encode {
+        true => write_items(&mut PercentEncoder(writer), items, inline),
+        false => write_items(writer, items, inline),
+    }
+}
+
+fn write_items<W: Write>(
+    writer: &mut W,
+    items: &[OrderBy],
+    options: &FormatOptions,
+) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(writer, ", ")?;
+        }
+
+        write_expr_with(writer, &item.expr, options)?;
+
+        if item.direction == Direction::Descending {
+            write!(writer, " ")?;
+            options
+                .keyword_case
+                .write(writer, &item.direction.to_string())?;
+        }
+    }
+
+    Ok(())
+}
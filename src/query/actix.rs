@@ -0,0 +1,100 @@
+//! Extraction of `QueryOptions` from the URI of an actix-web request.
+//!
+//! Errors respond with status 400 and their `ODataError` as an OData JSON
+//! error body.
+
+use super::{ODataError, QueryError, QueryOptions, QuerySchema};
+use crate::filters::{ParseError, ParseOptions, ValidationError};
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, ResponseError};
+use std::future::{ready, Ready};
+
+/// Extracts the query options of a request.
+///
+/// When a `web::Data<QuerySchema>` is registered with `app_data`, on the
+/// app, a scope or a single resource, the options are parsed with its
+/// limits and validated against it, including its `FilterPolicy`. Otherwise
+/// they are parsed with the limits of `ParseOptions::untrusted` and not
+/// validated.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use actix_web::{web, App};
+/// use odata_params::filters::{FilterPolicy, FunctionsTypeMap, IdentifiersTypeMap, Type};
+/// use odata_params::query::{QueryOptions, QuerySchema};
+///
+/// async fn list_users(options: QueryOptions) -> String {
+///     format!("top = {:?}", options.top)
+/// }
+///
+/// let schema = QuerySchema::new(
+///     IdentifiersTypeMap::from(HashMap::from([("age".to_string(), Type::Number)])),
+///     FunctionsTypeMap::from(HashMap::new()),
+/// )
+/// .with_policy(FilterPolicy {
+///     functions: Some(Default::default()),
+///     ..FilterPolicy::default()
+/// });
+///
+/// let app = App::new()
+///     .service(
+///         web::resource("/users")
+///             .app_data(web::Data::new(schema))
+///             .route(web::get().to(list_users)),
+///     )
+///     .route("/logs", web::get().to(list_users));
+/// ```
+impl FromRequest for QueryOptions {
+    type Error = QueryError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let query = req.query_string();
+
+        ready(match req.app_data::<web::Data<QuerySchema>>() {
+            Some(schema) => QueryOptions::parse_with(query, &schema.limits).and_then(|options| {
+                options.validate(schema)?;
+                Ok(options)
+            }),
+            None => QueryOptions::parse_with(query, &ParseOptions::untrusted()),
+        })
+    }
+}
+
+impl ResponseError for QueryError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        odata_error_response(ODataError::from(self.clone()))
+    }
+}
+
+impl ResponseError for ParseError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        odata_error_response(ODataError::from(self.clone()))
+    }
+}
+
+impl ResponseError for ValidationError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        odata_error_response(ODataError::from(self.clone()))
+    }
+}
+
+fn odata_error_response(error: ODataError) -> HttpResponse {
+    HttpResponse::BadRequest()
+        .content_type("application/json")
+        .insert_header(("OData-Version", "4.0"))
+        .body(error.to_body())
+}
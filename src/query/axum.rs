@@ -0,0 +1,110 @@
+//! Extractors reading `QueryOptions` from the URI of an axum request.
+//!
+//! Bad query options reject the request with `ODataQueryRejection`, which
+//! responds with status 400 and an OData JSON error body.
+
+use super::{ODataError, QueryError, QueryOptions, QuerySchema};
+use crate::filters::ParseOptions;
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::header::{HeaderName, CONTENT_TYPE};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+const ODATA_VERSION: HeaderName = HeaderName::from_static("odata-version");
+
+/// Extracts the query options of a request without validating them.
+///
+/// Filters are parsed with the limits of `ParseOptions::untrusted`.
+///
+/// ```
+/// use odata_params::query::axum::ODataQuery;
+///
+/// async fn list_users(ODataQuery(options): ODataQuery) -> String {
+///     format!("top = {:?}", options.top)
+/// }
+///
+/// let app: axum::Router = axum::Router::new().route("/users", axum::routing::get(list_users));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ODataQuery(pub QueryOptions);
+
+/// Extracts the query options of a request and validates them against the
+/// `QuerySchema` of the router state.
+///
+/// The state must provide an `Arc<QuerySchema>` through `FromRef`, whose
+/// limits are applied while parsing.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+/// use odata_params::filters::{FunctionsTypeMap, IdentifiersTypeMap, Type};
+/// use odata_params::query::axum::ValidatedODataQuery;
+/// use odata_params::query::QuerySchema;
+///
+/// async fn list_users(ValidatedODataQuery(options): ValidatedODataQuery) -> String {
+///     format!("filter = {:?}", options.filter)
+/// }
+///
+/// let schema = QuerySchema::new(
+///     IdentifiersTypeMap::from(HashMap::from([("age".to_string(), Type::Number)])),
+///     FunctionsTypeMap::from(HashMap::new()),
+/// );
+///
+/// let app: axum::Router = axum::Router::new()
+///     .route("/users", axum::routing::get(list_users))
+///     .with_state(Arc::new(schema));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatedODataQuery(pub QueryOptions);
+
+/// Rejection of a request with bad query options.
+///
+/// It responds with status 400 and the `ODataError` of the `QueryError` as
+/// an OData JSON error body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ODataQueryRejection(pub QueryError);
+
+impl<S: Send + Sync> FromRequestParts<S> for ODataQuery {
+    type Rejection = ODataQueryRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+
+        QueryOptions::parse_with(query, &ParseOptions::untrusted())
+            .map(ODataQuery)
+            .map_err(ODataQueryRejection)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ValidatedODataQuery
+where
+    Arc<QuerySchema>: FromRef<S>,
+{
+    type Rejection = ODataQueryRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let schema = Arc::<QuerySchema>::from_ref(state);
+        let query = parts.uri.query().unwrap_or_default();
+
+        let options =
+            QueryOptions::parse_with(query, &schema.limits).map_err(ODataQueryRejection)?;
+        options.validate(&schema).map_err(ODataQueryRejection)?;
+
+        Ok(ValidatedODataQuery(options))
+    }
+}
+
+impl IntoResponse for ODataQueryRejection {
+    fn into_response(self) -> Response {
+        let body = ODataError::from(self.0).to_body();
+
+        (
+            StatusCode::BAD_REQUEST,
+            [(CONTENT_TYPE, "application/json"), (ODATA_VERSION, "4.0")],
+            body,
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,121 @@
+use super::{ODataError, ODataErrorDetail, QueryError};
+use crate::filters::{ParseError, PolicyViolation, SpannedValidationError, ValidationError};
+
+impl ODataError {
+    /// Creates an error without target or details.
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            target: None,
+            details: Vec::new(),
+        }
+    }
+
+    /// Sets what the error is about.
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Adds errors to the details.
+    pub fn with_details<E: Into<ODataErrorDetail>>(
+        mut self,
+        details: impl IntoIterator<Item = E>,
+    ) -> Self {
+        self.details.extend(details.into_iter().map(Into::into));
+        self
+    }
+
+    /// Aggregates errors into one.
+    ///
+    /// A single error is returned as is, while several are listed in the
+    /// details of a `MultipleErrors` error.
+    pub fn from_errors<E: Into<ODataErrorDetail>>(errors: impl IntoIterator<Item = E>) -> Self {
+        let mut details: Vec<ODataErrorDetail> = errors.into_iter().map(Into::into).collect();
+
+        if details.len() == 1 {
+            let detail = details.remove(0);
+
+            return Self {
+                code: detail.code,
+                message: detail.message,
+                target: detail.target,
+                details,
+            };
+        }
+
+        Self::new(
+            "MultipleErrors",
+            format!("{} errors occurred.", details.len()),
+        )
+        .with_details(details)
+    }
+
+    /// Renders the error as an OData JSON response body, such as
+    /// `{"error":{"code":"InvalidTop","message":"...","target":"$top"}}`.
+    #[cfg(any(feature = "actix", feature = "axum"))]
+    pub(crate) fn to_body(&self) -> String {
+        serde_json::json!({ "error": self }).to_string()
+    }
+}
+
+/// Lists the parse, validation or policy errors behind the query error in
+/// its details.
+impl From<QueryError> for ODataError {
+    fn from(error: QueryError) -> Self {
+        let odata_error =
+            ODataError::new(error.code(), error.to_string()).with_target(error.target());
+
+        match error {
+            QueryError::Filter { error }
+            | QueryError::OrderBy { error }
+            | QueryError::Search { error } => {
+                odata_error.with_details([error])
+            }
+            QueryError::Validation { error, .. } => odata_error.with_details([error]),
+            QueryError::FilterNotAllowed { violations } => odata_error.with_details(violations),
+            _ => odata_error,
+        }
+    }
+}
+
+impl From<ParseError> for ODataErrorDetail {
+    fn from(error: ParseError) -> Self {
+        Self {
+            code: error.code().to_owned(),
+            message: error.to_string(),
+            target: error.position().map(|position| position.to_string()),
+        }
+    }
+}
+
+impl From<ParseError> for ODataError {
+    fn from(error: ParseError) -> Self {
+        Self::from_errors([error])
+    }
+}
+
+macro_rules! impl_from_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for ODataErrorDetail {
+                fn from(error: $error) -> Self {
+                    Self {
+                        code: error.code().to_owned(),
+                        message: error.to_string(),
+                        target: error.target().map(Into::into),
+                    }
+                }
+            }
+
+            impl From<$error> for ODataError {
+                fn from(error: $error) -> Self {
+                    Self::from_errors([error])
+                }
+            }
+        )*
+    };
+}
+
+impl_from_error!(ValidationError, SpannedValidationError, PolicyViolation);
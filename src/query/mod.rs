@@ -0,0 +1,312 @@
+//! System query options of an OData request URL, such as `$filter`,
+//! `$orderby`, `$top`, `$skip`, `$select`, `$count` and `$search`.
+//!
+//! `QueryOptions` parses them from the query string of a request and can
+//! validate them against the types of a `QuerySchema`. With the `axum`
+//! feature, the `axum` module provides extractors doing both, and with the
+//! `actix` feature, `QueryOptions` can be extracted from actix-web requests.
+//!
+//! Errors can be reported to clients in the standard OData JSON format
+//! through `ODataError`.
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
+mod error;
+mod parse;
+
+use crate::filters::{
+    Expr, FilterPolicy, FunctionsTypeMap, IdentifiersTypeMap, ParseError, ParseOptions,
+    PolicyViolation, Type, ValidationError,
+};
+use crate::orderby::OrderBy;
+use crate::search::SearchExpr;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Represents various errors that can occur while reading query options.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum QueryError {
+    /// The `$filter` option is not a valid filter expression.
+    #[error("Invalid $filter: {error}")]
+    Filter { error: ParseError },
+
+    /// The `$orderby` option is not a valid list of items.
+    #[error("Invalid $orderby: {error}")]
+    OrderBy { error: ParseError },
+
+    /// The `$search` option is not a valid search expression.
+    #[error("Invalid $search: {error}")]
+    Search { error: ParseError },
+
+    /// The `$top` option is not a non-negative integer.
+    #[error("Invalid $top '{value}', expected a non-negative integer.")]
+    InvalidTop { value: String },
+
+    /// The `$skip` option is not a non-negative integer.
+    #[error("Invalid $skip '{value}', expected a non-negative integer.")]
+    InvalidSkip { value: String },
+
+    /// The `$count` option is neither `true` nor `false`.
+    #[error("Invalid $count '{value}', expected true or false.")]
+    InvalidCount { value: String },
+
+    /// An item of the `$select` option is not a property path or `*`.
+    #[error("Invalid $select item '{value}'.")]
+    InvalidSelect { value: String },
+
+    /// A system query option is given more than once.
+    #[error("The query option '{name}' is given more than once.")]
+    DuplicateOption { name: String },
+
+    /// A query option starting with `$` that is not a system query option
+    /// of the specification.
+    #[error("The query option '{name}' is not supported.")]
+    UnsupportedOption { name: String },
+
+    /// An option does not match the types of the `QuerySchema`.
+    #[error("Invalid {option}: {error}")]
+    Validation {
+        option: String,
+        error: ValidationError,
+    },
+
+    /// The `$filter` option is not a boolean expression.
+    #[error("The $filter expression must be a boolean but got {given:?}.")]
+    FilterNotBoolean { given: Type },
+
+    /// The `$filter` option uses something the `FilterPolicy` of the
+    /// `QuerySchema` does not allow.
+    #[error(
+        "The $filter expression is not allowed: {}",
+        join_violations(violations)
+    )]
+    FilterNotAllowed { violations: Vec<PolicyViolation> },
+}
+
+impl QueryError {
+    /// A stable, machine-readable code for the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            QueryError::Filter { .. } => "InvalidFilter",
+            QueryError::OrderBy { .. } => "InvalidOrderBy",
+            QueryError::Search { .. } => "InvalidSearch",
+            QueryError::InvalidTop { .. } => "InvalidTop",
+            QueryError::InvalidSkip { .. } => "InvalidSkip",
+            QueryError::InvalidCount { .. } => "InvalidCount",
+            QueryError::InvalidSelect { .. } => "InvalidSelect",
+            QueryError::DuplicateOption { .. } => "DuplicateQueryOption",
+            QueryError::UnsupportedOption { .. } => "UnsupportedQueryOption",
+            QueryError::Validation { .. } => "ValidationFailed",
+            QueryError::FilterNotBoolean { .. } => "FilterNotBoolean",
+            QueryError::FilterNotAllowed { .. } => "FilterNotAllowed",
+        }
+    }
+
+    /// The name of the query option the error is about.
+    pub fn target(&self) -> &str {
+        match self {
+            QueryError::Filter { .. }
+            | QueryError::FilterNotBoolean { .. }
+            | QueryError::FilterNotAllowed { .. } => "$filter",
+            QueryError::OrderBy { .. } => "$orderby",
+            QueryError::Search { .. } => "$search",
+            QueryError::InvalidTop { .. } => "$top",
+            QueryError::InvalidSkip { .. } => "$skip",
+            QueryError::InvalidCount { .. } => "$count",
+            QueryError::InvalidSelect { .. } => "$select",
+            QueryError::DuplicateOption { name } | QueryError::UnsupportedOption { name } => name,
+            QueryError::Validation { option, .. } => option,
+        }
+    }
+}
+
+/// The system query options of a request.
+///
+/// Options that are not given keep their default value. Custom query options
+/// and parameter aliases, which do not start with `$`, are ignored. The other
+/// system query options of the specification, such as `$expand` or
+/// `$apply`, are kept as given in `unhandled`, for the service to handle or
+/// reject them.
+///
+/// ```
+/// use odata_params::filters::parse_str;
+/// use odata_params::query::QueryOptions;
+///
+/// let options = QueryOptions::parse(
+///     "$filter=age%20gt%2030&$top=10&$select=name,age&$count=true&$expand=orders",
+/// )
+/// .expect("valid query options");
+///
+/// assert_eq!(options.filter, Some(parse_str("age gt 30").expect("valid filter tree")));
+/// assert_eq!(options.top, Some(10));
+/// assert_eq!(options.skip, None);
+/// assert_eq!(options.select, ["name", "age"]);
+/// assert!(options.count);
+/// assert_eq!(options.unhandled, [("$expand".to_string(), "orders".to_string())]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// Expression from `$filter`.
+    pub filter: Option<Expr>,
+
+    /// Items from `$orderby`, empty when not given.
+    pub order_by: Vec<OrderBy>,
+
+    /// Number of items from `$top`.
+    pub top: Option<u64>,
+
+    /// Number of items from `$skip`.
+    pub skip: Option<u64>,
+
+    /// Property paths from `$select`, empty when not given. The `*` item
+    /// selects all properties.
+    pub select: Vec<String>,
+
+    /// Whether `$count=true` is given.
+    pub count: bool,
+
+    /// Expression from `$search`.
+    pub search: Option<SearchExpr>,
+
+    /// Other system query options, in the order they are given, with their
+    /// percent-decoded value.
+    pub unhandled: Vec<(String, String)>,
+}
+
+/// The types query options are validated against, along with the policy
+/// `$filter` must follow and the limits applied while parsing them.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use odata_params::filters::{FunctionsTypeMap, IdentifiersTypeMap, Type, ValidationError};
+/// use odata_params::query::{QueryError, QueryOptions, QuerySchema};
+///
+/// let schema = QuerySchema::new(
+///     IdentifiersTypeMap::from(HashMap::from([("age".to_string(), Type::Number)])),
+///     FunctionsTypeMap::from(HashMap::new()),
+/// );
+///
+/// let options = QueryOptions::parse("$filter=age gt 3&$orderby=age desc").expect("valid query options");
+/// assert_eq!(options.validate(&schema), Ok(()));
+///
+/// let options = QueryOptions::parse("$select=name").expect("valid query options");
+/// assert_eq!(
+///     options.validate(&schema),
+///     Err(QueryError::Validation {
+///         option: "$select".to_string(),
+///         error: ValidationError::UndefinedIdentifier {
+///             name: "name".to_string(),
+///         },
+///     }),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct QuerySchema {
+    /// Types of the identifiers options may use.
+    pub identifiers: IdentifiersTypeMap,
+
+    /// Signatures of the functions options may call.
+    pub functions: FunctionsTypeMap,
+
+    /// What `$filter` expressions may use, allowing everything by default.
+    pub policy: FilterPolicy,
+
+    /// Limits applied while parsing `$filter`, `$orderby` and `$search`.
+    pub limits: ParseOptions,
+}
+
+fn join_violations(violations: &[PolicyViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An error in the standard OData JSON error format.
+///
+/// It can be made from a `QueryError`, `ParseError`, `ValidationError` or
+/// `PolicyViolation`, and from several of them at once with
+/// `ODataError::from_errors`, which lists each one in `details`. With the
+/// `serde` feature it serializes to the object the OData `error` member of a
+/// response body holds.
+///
+/// ```
+/// use odata_params::filters::{PolicyViolation, ValidationError};
+/// use odata_params::query::{ODataError, ODataErrorDetail};
+///
+/// let error = ODataError::from(ValidationError::UndefinedIdentifier {
+///     name: "height".to_string(),
+/// });
+///
+/// assert_eq!(error.code, "UndefinedIdentifier");
+/// assert_eq!(error.target.as_deref(), Some("height"));
+///
+/// let error = ODataError::from_errors([
+///     PolicyViolation::FunctionNotAllowed {
+///         name: "contains".to_string(),
+///     },
+///     PolicyViolation::InNotAllowed,
+/// ]);
+///
+/// assert_eq!(error.code, "MultipleErrors");
+/// assert_eq!(
+///     error.details,
+///     [
+///         ODataErrorDetail {
+///             code: "FunctionNotAllowed".to_string(),
+///             message: "The function 'contains' is not allowed.".to_string(),
+///             target: Some("contains".to_string()),
+///         },
+///         ODataErrorDetail {
+///             code: "InNotAllowed".to_string(),
+///             message: "The 'in' operator is not allowed.".to_string(),
+///             target: None,
+///         },
+///     ],
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ODataError {
+    /// Stable, machine-readable code of the error.
+    pub code: String,
+
+    /// Human-readable description of the error.
+    pub message: String,
+
+    /// The query option, identifier or function the error is about.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub target: Option<String>,
+
+    /// The errors this error is made of.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub details: Vec<ODataErrorDetail>,
+}
+
+/// One of the errors listed in the `details` of an `ODataError`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ODataErrorDetail {
+    /// Stable, machine-readable code of the error.
+    pub code: String,
+
+    /// Human-readable description of the error.
+    pub message: String,
+
+    /// The query option, identifier or function the error is about.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub target: Option<String>,
+}
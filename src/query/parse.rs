@@ -0,0 +1,196 @@
+use super::{QueryError, QueryOptions, QuerySchema};
+use crate::filters::{
+    parse_str_with, Expr, FilterPolicy, FunctionsTypeMap, IdentifiersTypeMap, ParseOptions, Type,
+};
+use crate::{orderby, search};
+use std::collections::HashSet;
+
+/// The system query options of the specification that are kept as given in
+/// `QueryOptions::unhandled`.
+const UNHANDLED_OPTIONS: [&str; 9] = [
+    "$apply",
+    "$compute",
+    "$deltatoken",
+    "$expand",
+    "$format",
+    "$id",
+    "$index",
+    "$schemaversion",
+    "$skiptoken",
+];
+
+impl QueryOptions {
+    /// Parses the system query options of a URL query string, such as
+    /// `$filter=age%20gt%2030&$top=10`, which is percent-decoded first.
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        Self::parse_with(query, &ParseOptions::default())
+    }
+
+    /// Parses the system query options of a URL query string while
+    /// enforcing the limits set in `limits` on `$filter`, `$orderby` and
+    /// `$search`.
+    pub fn parse_with(query: &str, limits: &ParseOptions) -> Result<Self, QueryError> {
+        let mut options = QueryOptions::default();
+        let mut seen = HashSet::new();
+
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            if !name.starts_with('$') {
+                continue;
+            }
+
+            if !seen.insert(name.clone()) {
+                return Err(QueryError::DuplicateOption {
+                    name: name.into_owned(),
+                });
+            }
+
+            match name.as_ref() {
+                "$filter" => {
+                    let filter = parse_str_with(&value, limits)
+                        .map_err(|error| QueryError::Filter { error })?;
+                    options.filter = Some(filter);
+                }
+                "$orderby" => {
+                    options.order_by = orderby::parse_str_with(&value, limits)
+                        .map_err(|error| QueryError::OrderBy { error })?;
+                }
+                "$search" => {
+                    let search = search::parse_str_with(&value, limits)
+                        .map_err(|error| QueryError::Search { error })?;
+                    options.search = Some(search);
+                }
+                "$top" => {
+                    let top = parse_integer(&value).ok_or_else(|| QueryError::InvalidTop {
+                        value: value.clone().into_owned(),
+                    })?;
+                    options.top = Some(top);
+                }
+                "$skip" => {
+                    let skip = parse_integer(&value).ok_or_else(|| QueryError::InvalidSkip {
+                        value: value.clone().into_owned(),
+                    })?;
+                    options.skip = Some(skip);
+                }
+                "$select" => options.select = parse_select(&value)?,
+                "$count" => {
+                    options.count = match value.as_ref() {
+                        "true" => true,
+                        "false" => false,
+                        _ => {
+                            return Err(QueryError::InvalidCount {
+                                value: value.into_owned(),
+                            })
+                        }
+                    }
+                }
+                name if UNHANDLED_OPTIONS.contains(&name) => {
+                    options
+                        .unhandled
+                        .push((name.to_owned(), value.into_owned()));
+                }
+                _ => {
+                    return Err(QueryError::UnsupportedOption {
+                        name: name.into_owned(),
+                    })
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Validates the options against the types of a schema.
+    ///
+    /// `$filter` must be a boolean expression following the policy of the
+    /// schema, `$orderby` items must be
+    /// valid expressions and `$select` items must be known identifiers.
+    pub fn validate(&self, schema: &QuerySchema) -> Result<(), QueryError> {
+        let validate = |option: &str, expr: &Expr| {
+            expr.validate(&schema.identifiers, &schema.functions)
+                .map_err(|error| QueryError::Validation {
+                    option: option.to_owned(),
+                    error,
+                })
+        };
+
+        if let Some(filter) = &self.filter {
+            let given = validate("$filter", filter)?;
+
+            if given != Type::Boolean {
+                return Err(QueryError::FilterNotBoolean { given });
+            }
+
+            filter
+                .check_policy(&schema.policy)
+                .map_err(|violations| QueryError::FilterNotAllowed { violations })?;
+        }
+
+        for item in &self.order_by {
+            validate("$orderby", &item.expr)?;
+        }
+
+        for path in self.select.iter().filter(|path| *path != "*") {
+            validate("$select", &Expr::Identifier(path.clone()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl QuerySchema {
+    /// Creates a schema from type maps, allowing every filter that matches
+    /// the types and parsing with the limits of `ParseOptions::untrusted`.
+    pub fn new(identifiers: IdentifiersTypeMap, functions: FunctionsTypeMap) -> Self {
+        Self {
+            identifiers,
+            functions,
+            policy: FilterPolicy::default(),
+            limits: ParseOptions::untrusted(),
+        }
+    }
+
+    /// Replaces the policy `$filter` must follow.
+    pub fn with_policy(mut self, policy: FilterPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replaces the limits applied while parsing.
+    pub fn with_limits(mut self, limits: ParseOptions) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// Parses a non-negative integer made only of digits.
+fn parse_integer(value: &str) -> Option<u64> {
+    match !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        true => value.parse().ok(),
+        false => None,
+    }
+}
+
+/// Parses the comma separated property paths of `$select`.
+fn parse_select(value: &str) -> Result<Vec<String>, QueryError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .map(|item| {
+            let is_path = item.split('/').all(|segment| {
+                let mut chars = segment.chars();
+
+                chars
+                    .next()
+                    .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+                    && chars.all(|rest| rest.is_ascii_alphanumeric() || rest == '_')
+            });
+
+            match item == "*" || is_path {
+                true => Ok(item.to_owned()),
+                false => Err(QueryError::InvalidSelect {
+                    value: item.to_owned(),
+                }),
+            }
+        })
+        .collect()
+}
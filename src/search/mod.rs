@@ -0,0 +1,53 @@
+//! The `$search` system query option, a boolean expression of search terms
+//! and phrases.
+//!
+//! `parse_str` reads the grammar of the OData specification, where `NOT`
+//! binds tighter than `AND` and `AND` tighter than `OR`, and terms only
+//! separated by whitespace are combined with `AND`. The keywords are
+//! uppercase, so `and` or `Or` are plain terms.
+//!
+//! A `SearchExpr` can be written back with `to_query_string`, or translated
+//! for full-text search engines with `SearchExpr::to_tsquery` for PostgreSQL
+//! and `SearchExpr::to_fts5` for SQLite.
+
+mod parse;
+mod to_query_string;
+mod translate;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use parse::{parse_str, parse_str_with};
+pub use to_query_string::{to_query_string, write_query_string};
+
+/// Represents a `$search` expression.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SearchExpr {
+    /// Logical OR between two expressions.
+    Or(Box<SearchExpr>, Box<SearchExpr>),
+
+    /// Logical AND between two expressions, written with `AND` or only
+    /// whitespace.
+    And(Box<SearchExpr>, Box<SearchExpr>),
+
+    /// Logical NOT to invert an expression.
+    Not(Box<SearchExpr>),
+
+    /// A single word, such as `blue`.
+    Term(String),
+
+    /// Words to be found next to each other, such as `"blue green"`.
+    Phrase(String),
+}
+
+/// Represents errors translating a `SearchExpr` for a search engine.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum TranslateError {
+    /// A `NOT` that does not exclude documents from those matched by other
+    /// terms, such as `NOT blue` or `NOT blue OR green`, which SQLite FTS5
+    /// cannot search for.
+    #[error("SQLite FTS5 cannot search for '{expr}' without other terms to exclude it from.")]
+    UnboundNot { expr: String },
+}
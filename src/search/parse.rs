@@ -0,0 +1,49 @@
+use super::SearchExpr;
+use crate::filters::{parse_search_with, ParseError, ParseOptions};
+
+/// Parses an OData v4 `$search` expression string into a `SearchExpr` AST.
+///
+/// ```
+/// use odata_params::search::{parse_str, SearchExpr};
+///
+/// let expr = parse_str("blue OR green NOT red").expect("valid search");
+///
+/// assert_eq!(
+///     expr,
+///     SearchExpr::Or(
+///         Box::new(SearchExpr::Term("blue".to_owned())),
+///         Box::new(SearchExpr::And(
+///             Box::new(SearchExpr::Term("green".to_owned())),
+///             Box::new(SearchExpr::Not(Box::new(SearchExpr::Term("red".to_owned())))),
+///         )),
+///     ),
+/// );
+/// ```
+pub fn parse_str(query: impl AsRef<str>) -> Result<SearchExpr, ParseError> {
+    parse_str_with(query, &ParseOptions::default())
+}
+
+/// Parses an OData v4 `$search` expression string into a `SearchExpr` AST
+/// while enforcing the limits set in `options`.
+///
+/// Grouping parentheses and `NOT` count as levels of nesting, and phrases
+/// are limited like string literals.
+///
+/// ```
+/// use odata_params::filters::{ParseError, ParseOptions};
+/// use odata_params::search::parse_str_with;
+///
+/// let options = ParseOptions::untrusted();
+/// let hostile = format!("{}blue{}", "(".repeat(1000), ")".repeat(1000));
+///
+/// assert_eq!(
+///     parse_str_with(hostile, &options),
+///     Err(ParseError::NestingTooDeep { max: 32 }),
+/// );
+/// ```
+pub fn parse_str_with(
+    query: impl AsRef<str>,
+    options: &ParseOptions,
+) -> Result<SearchExpr, ParseError> {
+    parse_search_with(query.as_ref(), options)
+}
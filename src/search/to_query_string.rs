@@ -0,0 +1,121 @@
+use super::SearchExpr;
+use std::fmt::{self, Write};
+
+/// Converts a `SearchExpr` to its corresponding OData `$search` string
+/// representation, with only the parentheses needed to read it back.
+///
+/// Terms that would not be read back as a single word, such as `OR` or one
+/// holding whitespace, are written as phrases.
+///
+/// ```
+/// use odata_params::search::{parse_str, to_query_string};
+///
+/// let expr = parse_str("(blue  AND green) OR (NOT (red))").expect("valid search");
+///
+/// assert_eq!(to_query_string(&expr).expect("valid search"), "blue AND green OR NOT red");
+/// ```
+pub fn to_query_string(expr: &SearchExpr) -> Result<String, fmt::Error> {
+    let mut output = String::new();
+    write_query_string(&mut output, expr)?;
+    Ok(output)
+}
+
+/// Writes a `SearchExpr` to a writer as its corresponding OData `$search`
+/// string representation.
+pub fn write_query_string<W: Write>(writer: &mut W, expr: &SearchExpr) -> fmt::Result {
+    write_expr(writer, expr, &ODATA)
+}
+
+/// How the operators and operands of a search expression are written.
+pub(super) struct Syntax {
+    pub(super) or: &'static str,
+    pub(super) and: &'static str,
+    pub(super) not: &'static str,
+    pub(super) term: fn(&mut dyn Write, &str) -> fmt::Result,
+    pub(super) phrase: fn(&mut dyn Write, &str) -> fmt::Result,
+}
+
+const ODATA: Syntax = Syntax {
+    or: " OR ",
+    and: " AND ",
+    not: "NOT ",
+    term: |writer, term| match is_word(term) {
+        true => writer.write_str(term),
+        false => write_phrase(writer, term),
+    },
+    phrase: write_phrase,
+};
+
+/// Writes a search expression in a syntax where `NOT` binds tighter than
+/// `AND`, `AND` tighter than `OR`, and both group from the left.
+pub(super) fn write_expr(
+    writer: &mut dyn Write,
+    expr: &SearchExpr,
+    syntax: &Syntax,
+) -> fmt::Result {
+    match expr {
+        SearchExpr::Or(lhs, rhs) => {
+            write_operand(writer, lhs, expr.precedence(), syntax)?;
+            writer.write_str(syntax.or)?;
+            write_operand(writer, rhs, expr.precedence() + 1, syntax)
+        }
+        SearchExpr::And(lhs, rhs) => {
+            write_operand(writer, lhs, expr.precedence(), syntax)?;
+            writer.write_str(syntax.and)?;
+            write_operand(writer, rhs, expr.precedence() + 1, syntax)
+        }
+        SearchExpr::Not(inner) => {
+            writer.write_str(syntax.not)?;
+            write_operand(writer, inner, expr.precedence(), syntax)
+        }
+        SearchExpr::Term(term) => (syntax.term)(writer, term),
+        SearchExpr::Phrase(phrase) => (syntax.phrase)(writer, phrase),
+    }
+}
+
+/// Writes an operand, grouping it when it binds looser than `min`.
+fn write_operand(
+    writer: &mut dyn Write,
+    expr: &SearchExpr,
+    min: u8,
+    syntax: &Syntax,
+) -> fmt::Result {
+    match expr.precedence() < min {
+        true => {
+            writer.write_char('(')?;
+            write_expr(writer, expr, syntax)?;
+            writer.write_char(')')
+        }
+        false => write_expr(writer, expr, syntax),
+    }
+}
+
+impl SearchExpr {
+    /// How tightly the expression binds, from `OR` to single terms.
+    fn precedence(&self) -> u8 {
+        match self {
+            SearchExpr::Or(..) => 1,
+            SearchExpr::And(..) => 2,
+            SearchExpr::Not(_) => 3,
+            SearchExpr::Term(_) | SearchExpr::Phrase(_) => 4,
+        }
+    }
+}
+
+/// Whether `parse_str` reads a term back as a single word.
+fn is_word(term: &str) -> bool {
+    !term.is_empty()
+        && !matches!(term, "AND" | "OR" | "NOT")
+        && !term.contains([' ', '\t', '\n', '\r', '(', ')', '"'])
+}
+
+fn write_phrase(writer: &mut dyn Write, phrase: &str) -> fmt::Result {
+    writer.write_char('"')?;
+    for c in phrase.chars() {
+        if matches!(c, '"' | '\\') {
+            writer.write_char('\\')?;
+        }
+        writer.write_char(c)?;
+    }
+    writer.write_char('"')
+}
@@ -0,0 +1,152 @@
+use super::to_query_string::{write_expr, Syntax};
+use super::{to_query_string, SearchExpr, TranslateError};
+use std::fmt::{self, Write};
+
+const TSQUERY: Syntax = Syntax {
+    or: " | ",
+    and: " & ",
+    not: "!",
+    term: write_tsquery_quoted,
+    phrase: write_tsquery_quoted,
+};
+
+impl SearchExpr {
+    /// Translates the expression to the syntax of PostgreSQL `to_tsquery`,
+    /// to be passed to it as a parameter.
+    ///
+    /// Terms and phrases are quoted, so characters meaning something to
+    /// `to_tsquery` are searched for as is, and `to_tsquery` reads phrases
+    /// as words following each other.
+    ///
+    /// ```
+    /// use odata_params::search::parse_str;
+    ///
+    /// let expr = parse_str(r#"(blue OR green) NOT "dark red""#).expect("valid search");
+    ///
+    /// assert_eq!(expr.to_tsquery(), "('blue' | 'green') & !'dark red'");
+    /// ```
+    pub fn to_tsquery(&self) -> String {
+        let mut output = String::new();
+        write_expr(&mut output, self, &TSQUERY).expect("writing to a string");
+        output
+    }
+
+    /// Translates the expression to the syntax of SQLite FTS5 `MATCH`.
+    ///
+    /// Terms and phrases are written as FTS5 strings. FTS5 only has a binary
+    /// `NOT` excluding documents from those matched on its left, so every
+    /// `NOT` must be combined with `AND` or implicit `AND` with other terms.
+    ///
+    /// ```
+    /// use odata_params::search::{parse_str, TranslateError};
+    ///
+    /// let expr = parse_str(r#"NOT "dark red" (blue OR green)"#).expect("valid search");
+    /// assert_eq!(expr.to_fts5(), Ok(r#"("blue" OR "green") NOT "dark red""#.to_owned()));
+    ///
+    /// let expr = parse_str("NOT red OR blue").expect("valid search");
+    /// assert_eq!(
+    ///     expr.to_fts5(),
+    ///     Err(TranslateError::UnboundNot { expr: "NOT red".to_owned() }),
+    /// );
+    /// ```
+    pub fn to_fts5(&self) -> Result<String, TranslateError> {
+        let mut output = String::new();
+        write_fts5(&mut output, self)?;
+        Ok(output)
+    }
+}
+
+/// Writes a term or phrase as a quoted `to_tsquery` lexeme.
+fn write_tsquery_quoted(writer: &mut dyn Write, text: &str) -> fmt::Result {
+    writer.write_char('\'')?;
+    for c in text.chars() {
+        if matches!(c, '\'' | '\\') {
+            writer.write_char('\\')?;
+        }
+        writer.write_char(c)?;
+    }
+    writer.write_char('\'')
+}
+
+fn write_fts5(output: &mut String, expr: &SearchExpr) -> Result<(), TranslateError> {
+    match expr {
+        SearchExpr::Or(lhs, rhs) => {
+            write_fts5_operand(output, lhs)?;
+            output.push_str(" OR ");
+            write_fts5_operand(output, rhs)
+        }
+        SearchExpr::And(..) => {
+            let mut included = Vec::new();
+            let mut excluded = Vec::new();
+            split_and(expr, &mut included, &mut excluded);
+
+            if included.is_empty() {
+                return Err(unbound_not(expr));
+            }
+
+            for (i, expr) in included.into_iter().enumerate() {
+                if i > 0 {
+                    output.push_str(" AND ");
+                }
+                write_fts5_operand(output, expr)?;
+            }
+
+            for expr in excluded {
+                output.push_str(" NOT ");
+                write_fts5_operand(output, expr)?;
+            }
+
+            Ok(())
+        }
+        SearchExpr::Not(inner) => match inner.as_ref() {
+            SearchExpr::Not(inner) => write_fts5(output, inner),
+            _ => Err(unbound_not(expr)),
+        },
+        SearchExpr::Term(text) | SearchExpr::Phrase(text) => {
+            output.push('"');
+            output.push_str(&text.replace('"', "\"\""));
+            output.push('"');
+            Ok(())
+        }
+    }
+}
+
+/// Writes an operand of an FTS5 operator, grouping it unless it is a single
+/// term or phrase.
+fn write_fts5_operand(output: &mut String, expr: &SearchExpr) -> Result<(), TranslateError> {
+    match expr {
+        SearchExpr::Term(_) | SearchExpr::Phrase(_) => write_fts5(output, expr),
+        _ => {
+            output.push('(');
+            write_fts5(output, expr)?;
+            output.push(')');
+            Ok(())
+        }
+    }
+}
+
+/// Splits the operands of nested `AND`s into those documents must match
+/// and those they must not, removing double negations.
+fn split_and<'a>(
+    expr: &'a SearchExpr,
+    included: &mut Vec<&'a SearchExpr>,
+    excluded: &mut Vec<&'a SearchExpr>,
+) {
+    match expr {
+        SearchExpr::And(lhs, rhs) => {
+            split_and(lhs, included, excluded);
+            split_and(rhs, included, excluded);
+        }
+        SearchExpr::Not(inner) => match inner.as_ref() {
+            SearchExpr::Not(inner) => split_and(inner, included, excluded),
+            inner => excluded.push(inner),
+        },
+        expr => included.push(expr),
+    }
+}
+
+fn unbound_not(expr: &SearchExpr) -> TranslateError {
+    TranslateError::UnboundNot {
+        expr: to_query_string(expr).unwrap_or_default(),
+    }
+}
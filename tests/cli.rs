@@ -0,0 +1,228 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_odata-params"))
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_odata-params"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary runs");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn format() {
+    let output = run(&[
+        "format",
+        "((age gt 30) and (name eq 'Bob' or name eq 'Bill'))",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "age gt 30 and (name eq 'Bob' or name eq 'Bill')\n"
+    );
+
+    let output = run(&[
+        "format",
+        "--pretty",
+        "--upper",
+        "age gt 30 and (name eq 'Bob' or name eq 'Bill')",
+    ]);
+    assert_eq!(
+        stdout(&output),
+        "age GT 30\nAND (\n  name EQ 'Bob'\n  OR name EQ 'Bill'\n)\n"
+    );
+
+    let output = run(&[
+        "format",
+        "--encode",
+        "--backslash-quotes",
+        r"name eq 'O\'Brien'",
+    ]);
+    assert_eq!(stdout(&output), "name%20eq%20%27O%5C%27Brien%27\n");
+}
+
+#[test]
+fn reads_stdin() {
+    let output = run_with_stdin(&["format"], "(age gt 30)\n");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "age gt 30\n");
+
+    let output = run_with_stdin(&["format", "-"], "not (active eq true)\r\n");
+    assert_eq!(stdout(&output), "not (active eq true)\n");
+}
+
+#[test]
+fn ast() {
+    let output = run(&["ast", "--compact", "age gt 30"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        r#"{"version":1,"expr":{"op":"gt","left":{"op":"identifier","name":"age"},"right":{"op":"number","value":"30"}}}"#
+            .to_owned()
+            + "\n"
+    );
+
+    let output = run(&["ast", "active eq true"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["expr"]["right"]["op"], "boolean");
+}
+
+#[test]
+fn syntax_errors() {
+    let output = run(&["format", "age gt"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "error[SyntaxError]: Error during general parsing at position 6.\n"
+    );
+
+    let filter = format!("{}age gt 1{}", "(".repeat(40), ")".repeat(40));
+    assert!(run(&["format", &filter]).status.success());
+
+    let output = run(&["--untrusted", "format", &filter]);
+    assert_eq!(
+        stderr(&output),
+        "error[NestingTooDeep]: Filter exceeds the maximum nesting depth of 32.\n"
+    );
+}
+
+#[test]
+fn validate_with_type_map() {
+    let types = fixture("cli/types.json");
+
+    let output = run(&[
+        "validate",
+        "--schema",
+        &types,
+        "tolower(concat(name, 'a', 'b')) eq 'x' and age gt 3",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "The filter is valid.\n");
+
+    let output = run(&["validate", "--schema", &types, "height gt 3"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr(&output),
+        "error[UndefinedIdentifier]: Undefined identifier 'height'.\n"
+    );
+
+    let output = run(&["validate", "--schema", &types, "tolower(name)"]);
+    assert_eq!(
+        stderr(&output),
+        "error[FilterNotBoolean]: The $filter expression must be a boolean but got String.\n"
+    );
+}
+
+#[test]
+fn validate_with_csdl() {
+    for schema in [fixture("csdl/shop.xml"), fixture("csdl/shop.json")] {
+        let output = run(&[
+            "validate",
+            "--schema",
+            &schema,
+            "--entity-set",
+            "Orders",
+            "total gt 10 and isPaid eq true",
+        ]);
+        assert!(output.status.success(), "{}", stderr(&output));
+
+        let output = run(&["validate", "--schema", &schema, "total gt 10"]);
+        assert_eq!(
+            stderr(&output),
+            "error: The --entity-set option is required with a CSDL schema.\n"
+        );
+
+        let output = run(&["validate", "-s", &schema, "-e", "Invoices", "total gt 10"]);
+        assert_eq!(stderr(&output), "error: Undefined entity set 'Invoices'.\n");
+    }
+}
+
+#[test]
+fn invalid_schema_files() {
+    let output = run(&["validate", "--schema", "missing.json", "age gt 3"]);
+    assert!(stderr(&output).starts_with("error: Cannot read 'missing.json': "));
+
+    let directory = std::env::temp_dir().join(format!("odata-params-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let types = directory.join("types.json");
+    std::fs::write(&types, r#"{ "identifiers": { "age": "Integer" } }"#).unwrap();
+
+    let output = run(&["validate", "--schema", types.to_str().unwrap(), "age gt 3"]);
+    assert_eq!(
+        stderr(&output),
+        "error: Member 'identifiers/age' is expected to be a type such as \"Number\" or \"String\".\n"
+    );
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn translate() {
+    let output = run(&[
+        "translate",
+        "--to",
+        "cnf",
+        "(aa eq 1 and bb eq 2) or cc eq 3",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "(aa eq 1 or cc eq 3) and (bb eq 2 or cc eq 3)\n"
+    );
+
+    let output = run(&[
+        "translate",
+        "--to",
+        "simplified",
+        "(age gt 18 and age ge 21) and (name eq 'Bob' or name eq 'Alice')",
+    ]);
+    assert_eq!(stdout(&output), "age ge 21 and name in ('Bob', 'Alice')\n");
+
+    let output = run(&["translate", "--to", "canonical", "name eq 'a' and 3 lt age"]);
+    assert_eq!(stdout(&output), "age gt 3 and name eq 'a'\n");
+
+    let output = run(&[
+        "translate",
+        "--to",
+        "dnf",
+        "--max-clauses",
+        "1",
+        "(aa eq 1 or bb eq 2) and cc eq 3",
+    ]);
+    assert_eq!(
+        stderr(&output),
+        "error: The normal form has more than 1 clauses.\n"
+    );
+}
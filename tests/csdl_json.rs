@@ -0,0 +1,194 @@
+#![cfg(feature = "csdl")]
+
+use odata_params::csdl::{
+    ComplexType, CsdlError, EntityType, EnumType, Function, Metadata, NavigationProperty,
+    Parameter, Property, ReturnType, Schema,
+};
+use odata_params::filters::parse_str;
+use serde_json::json;
+
+fn shop_xml() -> Metadata {
+    Metadata::from_xml(include_str!("fixtures/csdl/shop.xml")).expect("valid CSDL")
+}
+
+fn shop_json() -> Metadata {
+    Metadata::from_json(include_str!("fixtures/csdl/shop.json")).expect("valid CSDL")
+}
+
+fn shop_builder() -> Metadata {
+    Metadata::new("4.0").with_schema(
+        Schema::new("Example.Shop")
+            .with_alias("Shop")
+            .with_enum_type(
+                EnumType::new("Color")
+                    .with_underlying_type("Edm.Byte")
+                    .with_member("Red", 1)
+                    .with_member("Green", 2)
+                    .with_member("Blue", 4),
+            )
+            .with_enum_type(
+                EnumType::new("Tier")
+                    .with_member("Free", 0)
+                    .with_member("Paid", 1),
+            )
+            .with_complex_type(
+                ComplexType::new("Address")
+                    .with_property(Property::new("street", "Edm.String"))
+                    .with_property(Property::new("city", "Edm.String").with_nullable(false)),
+            )
+            .with_entity_type(
+                EntityType::new("Resource")
+                    .with_key(["id"])
+                    .with_property(Property::new("id", "Edm.Guid").with_nullable(false))
+                    .with_property(
+                        Property::new("createdAt", "Edm.DateTimeOffset").with_nullable(false),
+                    ),
+            )
+            .with_entity_type(
+                EntityType::new("Customer")
+                    .with_base_type("Shop.Resource")
+                    .with_property(Property::new("name", "Edm.String").with_nullable(false))
+                    .with_property(Property::new("birthday", "Edm.Date"))
+                    .with_property(Property::new("tier", "Example.Shop.Tier"))
+                    .with_property(Property::new("address", "Shop.Address"))
+                    .with_property(Property::new("tags", "Collection(Edm.String)"))
+                    .with_navigation_property(
+                        NavigationProperty::new("orders", "Collection(Shop.Order)")
+                            .with_partner("customer"),
+                    ),
+            )
+            .with_entity_type(
+                EntityType::new("Order")
+                    .with_base_type("Shop.Resource")
+                    .with_property(Property::new("total", "Edm.Decimal").with_nullable(false))
+                    .with_property(Property::new("color", "Shop.Color"))
+                    .with_property(Property::new("pickupTime", "Edm.TimeOfDay"))
+                    .with_property(Property::new("isPaid", "Edm.Boolean").with_nullable(false))
+                    .with_navigation_property(
+                        NavigationProperty::new("customer", "Shop.Customer")
+                            .with_nullable(false)
+                            .with_partner("orders"),
+                    ),
+            )
+            .with_function(
+                Function::new("discount")
+                    .with_parameter(Parameter::new("total", "Edm.Decimal").with_nullable(false))
+                    .with_parameter(Parameter::new("tier", "Shop.Tier"))
+                    .with_return_type(ReturnType::new("Edm.Decimal").with_nullable(false)),
+            )
+            .with_function(
+                Function::new("discount")
+                    .with_parameter(Parameter::new("total", "Edm.Decimal").with_nullable(false))
+                    .with_return_type(ReturnType::new("Edm.Decimal").with_nullable(false)),
+            )
+            .with_function(
+                Function::new("recentOrders")
+                    .with_bound(true)
+                    .with_parameter(Parameter::new("customer", "Shop.Customer"))
+                    .with_return_type(ReturnType::new("Collection(Shop.Order)")),
+            )
+            .with_function(
+                Function::new("bestCustomer").with_return_type(ReturnType::new("Shop.Customer")),
+            )
+            .with_entity_set("Customers", "Shop.Customer")
+            .with_entity_set("Orders", "Example.Shop.Order"),
+    )
+}
+
+#[test]
+fn builder_matches_xml_document() {
+    assert_eq!(shop_builder(), shop_xml());
+}
+
+#[test]
+fn json_document_matches_xml_document() {
+    assert_eq!(shop_json(), shop_xml());
+}
+
+#[test]
+fn xml_round_trip() {
+    let metadata = shop_builder();
+
+    assert_eq!(Metadata::from_xml(&metadata.to_xml()), Ok(metadata));
+}
+
+#[test]
+fn json_round_trip() {
+    let metadata = shop_builder();
+
+    assert_eq!(
+        Metadata::from_json(&metadata.to_json().to_string()),
+        Ok(metadata)
+    );
+}
+
+#[test]
+fn json_output_leaves_out_defaults() {
+    let metadata = Metadata::new("4.01").with_schema(
+        Schema::new("Shop")
+            .with_entity_type(
+                EntityType::new("Product")
+                    .with_key(["id"])
+                    .with_property(Property::new("id", "Edm.Int32").with_nullable(false))
+                    .with_property(Property::new("tags", "Collection(Edm.String)")),
+            )
+            .with_entity_set("Products", "Shop.Product"),
+    );
+
+    assert_eq!(
+        metadata.to_json(),
+        json!({
+            "$Version": "4.01",
+            "$EntityContainer": "Shop.Default",
+            "Shop": {
+                "Product": {
+                    "$Kind": "EntityType",
+                    "$Key": ["id"],
+                    "id": { "$Type": "Edm.Int32" },
+                    "tags": { "$Collection": true, "$Type": "Edm.String", "$Nullable": true }
+                },
+                "Default": {
+                    "$Kind": "EntityContainer",
+                    "Products": { "$Collection": true, "$Type": "Shop.Product" }
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn type_maps_from_json_document() {
+    let metadata = shop_json();
+    let identifiers = metadata
+        .identifiers_type_map("Orders")
+        .expect("known entity set");
+    let functions = metadata.functions_type_map();
+
+    let expr = parse_str("discount(total, 'Paid') lt total and color eq 'Red'")
+        .expect("valid filter tree");
+    assert_eq!(expr.are_types_valid(&identifiers, &functions), Ok(true));
+}
+
+#[test]
+fn invalid_json_documents() {
+    assert!(matches!(
+        Metadata::from_json("{"),
+        Err(CsdlError::Json { .. })
+    ));
+
+    assert_eq!(
+        Metadata::from_json("[]"),
+        Err(CsdlError::UnexpectedJson {
+            path: "$".to_owned(),
+            expected: "an object".to_owned(),
+        })
+    );
+
+    assert_eq!(
+        Metadata::from_json(r#"{ "Shop": { "Tier": { "$Kind": "EnumType", "Free": "zero" } } }"#),
+        Err(CsdlError::UnexpectedJson {
+            path: "Shop.Tier.Free".to_owned(),
+            expected: "an integer".to_owned(),
+        })
+    );
+}
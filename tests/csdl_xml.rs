@@ -0,0 +1,220 @@
+#![cfg(feature = "csdl")]
+
+use odata_params::csdl::{CsdlError, EnumMember, Metadata, NavigationProperty, Property};
+use odata_params::filters::{
+    parse_str, Expr, FunctionsTypeMap, IdentifiersTypeMap, Type, ValidationError,
+};
+use std::collections::HashMap;
+
+fn shop() -> Metadata {
+    Metadata::from_xml(include_str!("fixtures/csdl/shop.xml")).expect("valid CSDL")
+}
+
+fn type_of(identifiers: &IdentifiersTypeMap, name: &str) -> Result<Type, ValidationError> {
+    Expr::Identifier(name.to_owned()).validate(identifiers, &FunctionsTypeMap::from(HashMap::new()))
+}
+
+#[test]
+fn parses_schema() {
+    let metadata = shop();
+
+    assert_eq!(metadata.version, "4.0");
+    assert_eq!(metadata.schemas.len(), 1);
+
+    let schema = &metadata.schemas[0];
+
+    assert_eq!(schema.namespace, "Example.Shop");
+    assert_eq!(schema.alias.as_deref(), Some("Shop"));
+    assert_eq!(schema.entity_types.len(), 3);
+    assert_eq!(schema.complex_types.len(), 1);
+    assert_eq!(schema.enum_types.len(), 2);
+    assert_eq!(schema.functions.len(), 4);
+    assert_eq!(
+        schema
+            .entity_container
+            .as_ref()
+            .map(|container| container.entity_sets.len()),
+        Some(2)
+    );
+}
+
+#[test]
+fn parses_entity_types() {
+    let metadata = shop();
+
+    let resource = metadata
+        .entity_type("Shop.Resource")
+        .expect("declared type");
+    assert_eq!(resource.key, vec!["id".to_owned()]);
+
+    let customer = metadata
+        .entity_type("Example.Shop.Customer")
+        .expect("declared type");
+
+    assert_eq!(customer.base_type.as_deref(), Some("Shop.Resource"));
+    assert_eq!(
+        customer.properties[0],
+        Property {
+            name: "name".to_owned(),
+            type_name: "Edm.String".to_owned(),
+            nullable: false,
+        }
+    );
+    assert!(customer.properties[1].nullable);
+    assert_eq!(
+        customer.navigation_properties,
+        vec![NavigationProperty {
+            name: "orders".to_owned(),
+            type_name: "Collection(Shop.Order)".to_owned(),
+            nullable: true,
+            partner: Some("customer".to_owned()),
+        }]
+    );
+
+    let address = metadata
+        .complex_type("Shop.Address")
+        .expect("declared type");
+    assert_eq!(address.properties.len(), 2);
+}
+
+#[test]
+fn parses_enum_types() {
+    let metadata = shop();
+
+    let enums = metadata
+        .enum_types()
+        .map(|(name, enum_type)| (name, enum_type.members.len()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        enums,
+        vec![
+            ("Example.Shop.Color".to_owned(), 3),
+            ("Example.Shop.Tier".to_owned(), 2),
+        ]
+    );
+
+    let tier = metadata.enum_type("Shop.Tier").expect("declared type");
+
+    assert_eq!(
+        tier.members
+            .iter()
+            .map(|member| member.value)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+
+    let color = metadata.enum_type("Shop.Color").expect("declared type");
+
+    assert_eq!(color.underlying_type.as_deref(), Some("Edm.Byte"));
+    assert_eq!(
+        color.members[2],
+        EnumMember {
+            name: "Blue".to_owned(),
+            value: 4,
+        }
+    );
+}
+
+#[test]
+fn identifiers_type_map_includes_inherited_properties() {
+    let identifiers = shop()
+        .identifiers_type_map("Customers")
+        .expect("known entity set");
+
+    assert!(matches!(type_of(&identifiers, "id"), Ok(Type::Uuid)));
+    assert!(matches!(
+        type_of(&identifiers, "createdAt"),
+        Ok(Type::DateTime)
+    ));
+    assert!(matches!(type_of(&identifiers, "name"), Ok(Type::String)));
+    assert!(matches!(type_of(&identifiers, "birthday"), Ok(Type::Date)));
+    assert!(matches!(type_of(&identifiers, "tier"), Ok(Type::String)));
+    assert!(matches!(type_of(&identifiers, "tags"), Ok(Type::String)));
+    assert!(matches!(type_of(&identifiers, "orders"), Ok(Type::Null)));
+    assert!(type_of(&identifiers, "address").is_err());
+}
+
+#[test]
+fn identifiers_type_map_validates_filters() {
+    let metadata = shop();
+    let identifiers = metadata
+        .identifiers_type_map("Orders")
+        .expect("known entity set");
+    let functions = metadata.functions_type_map();
+
+    let expr =
+        parse_str("isPaid eq true and total gt 10.5 and color eq 'Red' and pickupTime lt 12:00")
+            .expect("valid filter tree");
+    assert_eq!(expr.are_types_valid(&identifiers, &functions), Ok(true));
+
+    let expr = parse_str("discount(total, 'Paid') lt total").expect("valid filter tree");
+    assert_eq!(expr.are_types_valid(&identifiers, &functions), Ok(true));
+
+    let expr = parse_str("total eq 'ten'").expect("valid filter tree");
+    assert_eq!(
+        expr.validate(&identifiers, &functions),
+        Err(ValidationError::ComparingIncompatibleTypes {
+            lhs: Type::Number,
+            rhs: Type::String,
+        })
+    );
+
+    let expr = parse_str("customer eq null").expect("valid filter tree");
+    assert_eq!(
+        expr.validate(&identifiers, &functions),
+        Err(ValidationError::UndefinedIdentifier {
+            name: "customer".to_owned()
+        })
+    );
+}
+
+#[test]
+fn functions_type_map_skips_bound_and_non_primitive_functions() {
+    let functions = shop().functions_type_map();
+    let identifiers = IdentifiersTypeMap::from(HashMap::new());
+
+    for name in ["recentOrders", "bestCustomer"] {
+        assert_eq!(
+            Expr::Function(name.to_owned(), vec![]).validate(&identifiers, &functions),
+            Err(ValidationError::UndefinedFunction {
+                name: name.to_owned()
+            })
+        );
+    }
+}
+
+#[test]
+fn unknown_entity_set() {
+    assert_eq!(
+        shop().identifiers_type_map("Products").err(),
+        Some(CsdlError::UndefinedEntitySet {
+            name: "Products".to_owned()
+        })
+    );
+}
+
+#[test]
+fn invalid_documents() {
+    assert!(matches!(
+        Metadata::from_xml("<edmx:Edmx"),
+        Err(CsdlError::Xml { .. })
+    ));
+
+    assert_eq!(
+        Metadata::from_xml("<Schema Namespace=\"Broken\"/>"),
+        Err(CsdlError::UnexpectedRoot {
+            expected: "Edmx".to_owned(),
+            found: "Schema".to_owned(),
+        })
+    );
+
+    assert_eq!(
+        Metadata::from_xml(include_str!("fixtures/csdl/invalid_enum_value.xml")),
+        Err(CsdlError::InvalidAttribute {
+            element: "Member".to_owned(),
+            attribute: "Value".to_owned(),
+            value: "one".to_owned(),
+        })
+    );
+}
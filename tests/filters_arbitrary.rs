@@ -0,0 +1,83 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use odata_params::chrono::SecondsFormat;
+use odata_params::filters::{
+    borrowed, parse_str, parse_str_spanned, to_query_string_with, CompareOperator, Expr,
+    FormatOptions, QuoteStyle, Value,
+};
+
+/// Deterministic bytes standing in for fuzzer input.
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn exprs() -> impl Iterator<Item = Expr> {
+    (0..500).filter_map(|seed| Expr::arbitrary(&mut Unstructured::new(&bytes(seed, 1024))).ok())
+}
+
+fn round_trip_options() -> FormatOptions {
+    FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    }
+}
+
+#[test]
+fn test_arbitrary_filters_parse_back() {
+    for expr in exprs() {
+        let filter = to_query_string_with(&expr, &round_trip_options()).unwrap();
+
+        assert_eq!(parse_str(&filter), Ok(expr), "{filter}");
+    }
+}
+
+#[test]
+fn test_arbitrary_filters_parse_alike() {
+    for expr in exprs() {
+        let filter = to_query_string_with(&expr, &round_trip_options()).unwrap();
+
+        assert_eq!(
+            borrowed::parse_str(&filter).map(|e| e.into_owned()),
+            Ok(expr.clone()),
+            "{filter}",
+        );
+        assert_eq!(
+            parse_str_spanned(&filter).map(|e| e.into_expr()),
+            Ok(expr),
+            "{filter}",
+        );
+    }
+}
+
+#[test]
+fn test_arbitrary_filters_are_varied() {
+    let filters: Vec<_> = exprs().collect();
+
+    assert!(filters.len() > 400);
+    assert!(filters.iter().any(|e| matches!(e, Expr::Compare(..))));
+    assert!(filters
+        .iter()
+        .any(|e| matches!(e, Expr::And(..) | Expr::Or(..))));
+    assert!(filters.iter().any(|e| matches!(e, Expr::Not(_))));
+    assert!(filters.iter().any(|e| matches!(e, Expr::In(..))));
+}
+
+#[test]
+fn test_empty_input_still_builds() {
+    let mut u = Unstructured::new(&[]);
+
+    assert!(Expr::arbitrary(&mut u).is_ok());
+    assert!(Value::arbitrary(&mut u).is_ok());
+    assert!(CompareOperator::arbitrary(&mut u).is_ok());
+}
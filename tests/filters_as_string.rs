@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use odata_params::filters::{as_string, parse_str, Expr, FilterString};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SavedSearch {
+    #[serde(with = "as_string")]
+    filter: Expr,
+
+    #[serde(with = "as_string::option", default)]
+    fallback: Option<Expr>,
+}
+
+fn round_trip(filter: &str) -> String {
+    let expr = parse_str(filter).expect("valid filter tree");
+    let json = serde_json::to_string(&FilterString(expr.clone())).unwrap();
+    let read: FilterString = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(read.0, expr);
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn serializes_readable_string() {
+    assert_eq!(
+        round_trip("(age gt 30) and (name eq 'Bob' or name eq 'Bill')"),
+        "age gt 30 and (name eq 'Bob' or name eq 'Bill')"
+    );
+    assert_eq!(
+        round_trip("not (contains(tolower(name), 'j'))"),
+        "not contains(tolower(name), 'j')"
+    );
+    assert_eq!(
+        round_trip("owner/tags/all(tag: tag in ('a', 'b')) and id eq @p1"),
+        "owner/tags/all(tag: tag in ('a', 'b')) and id eq @p1"
+    );
+}
+
+#[test]
+fn round_trips_escaped_strings() {
+    assert_eq!(
+        round_trip(r"name eq 'O\'Brien' or path eq 'C:\\temp'"),
+        r"name eq 'O\'Brien' or path eq 'C:\\temp'"
+    );
+}
+
+#[test]
+fn round_trips_values() {
+    assert_eq!(
+        round_trip("created gt 2024-01-02T03:04:05.123456789+01:00 and born eq 2000-02-29"),
+        "created gt 2024-01-02T02:04:05.123456789Z and born eq 2000-02-29"
+    );
+    assert_eq!(
+        round_trip("id eq da820b39-5ad2-4441-b664-c902dbd377d8 and at lt 12:30:00"),
+        "id eq da820b39-5ad2-4441-b664-c902dbd377d8 and at lt 12:30:00"
+    );
+    assert_eq!(
+        round_trip("price le 99.99 and active eq true and deleted eq null"),
+        "price le 99.99 and active eq true and deleted eq null"
+    );
+}
+
+#[test]
+fn with_attribute() {
+    let search = SavedSearch {
+        filter: parse_str("age ge 18").expect("valid filter tree"),
+        fallback: Some(parse_str("age ge 21").expect("valid filter tree")),
+    };
+
+    let json = serde_json::to_value(&search).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "filter": "age ge 18", "fallback": "age ge 21" })
+    );
+    assert_eq!(serde_json::from_value::<SavedSearch>(json).unwrap(), search);
+
+    assert_eq!(
+        serde_json::from_value::<SavedSearch>(serde_json::json!({ "filter": "age ge 18" }))
+            .unwrap()
+            .fallback,
+        None
+    );
+    assert_eq!(
+        serde_json::from_value::<SavedSearch>(
+            serde_json::json!({ "filter": "age ge 18", "fallback": null })
+        )
+        .unwrap()
+        .fallback,
+        None
+    );
+}
+
+#[test]
+fn rejects_invalid_filters() {
+    let error = serde_json::from_str::<FilterString>(r#""age ge""#).unwrap_err();
+    assert_eq!(error.to_string(), "Error during general parsing at position 6.");
+
+    assert!(serde_json::from_str::<FilterString>("42").is_err());
+    assert!(
+        serde_json::from_value::<SavedSearch>(serde_json::json!({ "filter": "x eq 1" })).is_err()
+    );
+}
+
+#[test]
+fn conversions() {
+    let expr = parse_str("age ge 18").expect("valid filter tree");
+    let filter = FilterString::from(expr.clone());
+
+    assert_eq!(Expr::from(filter), expr);
+}
@@ -0,0 +1,144 @@
+use odata_params::filters::borrowed::{parse_str, parse_str_with, Expr, Value};
+use odata_params::filters::{self, CompareOperator, ParseError, ParseOptions};
+use std::borrow::Cow;
+
+fn rhs<'a>(expr: &'a Expr<'a>) -> &'a Expr<'a> {
+    match expr {
+        Expr::Compare(_, _, rhs) => rhs,
+        _ => panic!("expected comparison, got {expr:?}"),
+    }
+}
+
+#[test]
+fn test_into_owned_matches_plain_parsing() {
+    let filters = [
+        "name eq 'John' and isActive eq true",
+        "price lt 99.99 or (age gt 30 and age lt 50)",
+        "not (owner/name eq null)",
+        "concat(concat(city, ', '), country) eq 'Berlin, Germany'",
+        "tags/any(tag: tag eq 'vip') and roles/all(role: role ne 'guest')",
+        "id in (da820b39-5ad2-4441-b664-c902dbd377d8, 1, 'aa')",
+        "createdAt ge 2024-01-01T10:00:00+02:00 and day eq 2024-01-01 and at lt 10:30:00",
+        "@p1 eq name and flags has 4",
+    ];
+
+    for filter in filters {
+        assert_eq!(
+            parse_str(filter).unwrap().into_owned(),
+            filters::parse_str(filter).unwrap(),
+            "{filter}",
+        );
+    }
+}
+
+#[test]
+fn test_names_are_borrowed() {
+    let expr = parse_str("startswith(owner/name, @prefix) and tags/any(tag: tag eq 1)").unwrap();
+
+    let Expr::And(function, lambda) = expr else {
+        panic!("expected and");
+    };
+    let Expr::Function(Cow::Borrowed("startswith"), args) = *function else {
+        panic!("expected function");
+    };
+    assert!(matches!(
+        args[0],
+        Expr::Identifier(Cow::Borrowed("owner/name"))
+    ));
+    assert!(matches!(args[1], Expr::Alias(Cow::Borrowed("@prefix"))));
+
+    let Expr::Lambda(collection, _, Cow::Borrowed("tag"), _) = *lambda else {
+        panic!("expected lambda");
+    };
+    assert!(matches!(
+        *collection,
+        Expr::Identifier(Cow::Borrowed("tags"))
+    ));
+}
+
+#[test]
+fn test_strings_without_escapes_are_borrowed() {
+    let expr = parse_str("name eq 'Bob Smith'").unwrap();
+
+    assert!(matches!(
+        rhs(&expr),
+        Expr::Value(Value::String(Cow::Borrowed("Bob Smith")))
+    ));
+}
+
+#[test]
+fn test_strings_with_escapes_are_decoded() {
+    let cases = [
+        (r"name eq 'O\'Brien'", "O'Brien"),
+        ("name eq 'O''Brien'", "O'Brien"),
+        (r"name eq '''quoted'' \\'", r"'quoted' \"),
+        (r"name eq 'aa\nbb\tcc\r'", "aa\nbb\tcc\r"),
+        (r"name eq 'back\\slash'", r"back\slash"),
+        (r"name eq 'caf\u00e9 \u1F600'", "caf\u{e9} \u{1F600}"),
+        (r"name eq 'kept \x and \u'", r"kept \x and \u"),
+    ];
+
+    for (filter, expected) in cases {
+        let expr = parse_str(filter).unwrap();
+
+        assert!(
+            matches!(rhs(&expr), Expr::Value(Value::String(Cow::Owned(s))) if s == expected),
+            "{filter}: {expr:?}",
+        );
+        assert_eq!(
+            expr.into_owned(),
+            filters::parse_str(filter).unwrap(),
+            "{filter}"
+        );
+    }
+}
+
+#[test]
+fn test_errors_match_plain_parsing() {
+    let filters = [
+        "name eq",
+        r"name eq '\uD800'",
+        "total eq 12:99",
+        "day eq 2024-02-30",
+    ];
+
+    for filter in filters {
+        assert_eq!(
+            parse_str(filter).unwrap_err(),
+            filters::parse_str(filter).unwrap_err(),
+            "{filter}",
+        );
+    }
+}
+
+#[test]
+fn test_limits_are_enforced() {
+    let options = ParseOptions {
+        max_string_length: Some(3),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with(r"name eq 'a\'c'", &options).is_ok());
+    assert!(parse_str_with("name eq 'a''c'", &options).is_ok());
+    assert_eq!(
+        parse_str_with("name eq 'abcd'", &options),
+        Err(ParseError::StringLiteralTooLong { max: 3 }),
+    );
+}
+
+#[test]
+fn test_borrowed_expressions_outlive_nothing_after_into_owned() {
+    let owned = {
+        let filter = String::from("age gt 30");
+        parse_str(&filter).unwrap().into_owned()
+    };
+
+    assert_eq!(
+        owned,
+        filters::Expr::Compare(
+            Box::new(filters::Expr::Identifier("age".to_string())),
+            CompareOperator::GreaterThan,
+            Box::new(filters::Expr::Value(filters::Value::Number(30.into()))),
+        ),
+    );
+}
@@ -0,0 +1,169 @@
+use bigdecimal::BigDecimal;
+use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use odata_params::filters::{
+    alias, field, function, parse_str, to_query_string, value, Expr, Value,
+};
+use std::str::FromStr;
+use uuid::Uuid;
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+#[test]
+fn comparisons() {
+    assert_eq!(Expr::from(field("age").eq(30)), parsed("age eq 30"));
+    assert_eq!(Expr::from(field("age").ne(30)), parsed("age ne 30"));
+    assert_eq!(Expr::from(field("age").gt(30)), parsed("age gt 30"));
+    assert_eq!(Expr::from(field("age").ge(30)), parsed("age ge 30"));
+    assert_eq!(Expr::from(field("age").lt(30)), parsed("age lt 30"));
+    assert_eq!(Expr::from(field("age").le(30)), parsed("age le 30"));
+    assert_eq!(
+        Expr::from(field("style").has("Sales.Pattern'Yellow'")),
+        Expr::Compare(
+            Box::new(Expr::Identifier("style".to_owned())),
+            odata_params::filters::CompareOperator::Has,
+            Box::new(Expr::Value(Value::String(
+                "Sales.Pattern'Yellow'".to_owned()
+            ))),
+        )
+    );
+    assert_eq!(
+        Expr::from(field("width").gt(field("height"))),
+        parsed("width gt height")
+    );
+    assert_eq!(Expr::from(value(5).lt(field("age"))), parsed("5 lt age"));
+    assert_eq!(
+        Expr::from(field("age").eq(alias("@age"))),
+        parsed("age eq @age")
+    );
+}
+
+#[test]
+fn logical_operators() {
+    let built = field("age")
+        .gt(30)
+        .and(field("name").starts_with("J"))
+        .or(!field("active").eq(true));
+
+    assert_eq!(
+        to_query_string(&built.into()).expect("valid filter"),
+        "(age gt 30 and startswith(name, 'J')) or not active eq true"
+    );
+
+    let built = field("aa")
+        .eq(1)
+        .and(field("bb").eq(2).or(field("cc").eq(3)));
+    assert_eq!(
+        Expr::from(built),
+        parsed("aa eq 1 and (bb eq 2 or cc eq 3)")
+    );
+}
+
+#[test]
+fn functions_and_lists() {
+    assert_eq!(
+        Expr::from(field("name").to_lower().contains("bob")),
+        parsed("contains(tolower(name), 'bob')")
+    );
+    assert_eq!(
+        Expr::from(field("name").to_upper().ends_with(field("suffix"))),
+        parsed("endswith(toupper(name), suffix)")
+    );
+    assert_eq!(
+        Expr::from(function("length", [field("name")]).gt(3)),
+        parsed("length(name) gt 3")
+    );
+    assert_eq!(
+        Expr::from(field("color").is_in(["red", "blue"])),
+        parsed("color in ('red', 'blue')")
+    );
+    assert_eq!(
+        Expr::from(field("id").is_in(Vec::<i32>::new())),
+        Expr::In(Box::new(Expr::Identifier("id".to_owned())), vec![])
+    );
+}
+
+#[test]
+fn lambdas() {
+    assert_eq!(
+        Expr::from(field("tags").any("tag", |tag| tag.eq("vip"))),
+        parsed("tags/any(tag: tag eq 'vip')")
+    );
+    assert_eq!(
+        Expr::from(field("orders").all("order", |order| {
+            field("order/total")
+                .gt(100)
+                .and(order.eq(null_value()).or(field("order/items").any("item", |item| item.ne(0))))
+        })),
+        parsed("orders/all(order: order/total gt 100 and (order eq null or order/items/any(item: item ne 0)))")
+    );
+}
+
+fn null_value() -> Value {
+    None::<i32>.into()
+}
+
+#[test]
+fn parsed_expressions() {
+    let parsed_condition = parsed("name eq 'Bob' or name eq 'Alice'");
+
+    assert_eq!(
+        Expr::from(field("age").gt(3).and(parsed_condition)),
+        parsed("age gt 3 and (name eq 'Bob' or name eq 'Alice')")
+    );
+    assert_eq!(
+        Expr::from(field("total").eq(parsed("price"))),
+        parsed("total eq price")
+    );
+}
+
+#[test]
+fn numeric_conversions() {
+    assert_eq!(Value::from(5_u8), Value::Number(BigDecimal::from(5)));
+    assert_eq!(Value::from(-5_i64), Value::Number(BigDecimal::from(-5)));
+    assert_eq!(
+        Value::from(u128::MAX),
+        Value::Number(BigDecimal::from_str(&u128::MAX.to_string()).unwrap())
+    );
+    assert_eq!(Value::from(7_usize), Value::Number(BigDecimal::from(7)));
+    assert_eq!(Value::from(-7_isize), Value::Number(BigDecimal::from(-7)));
+    assert_eq!(
+        Value::try_from(0.1_f64),
+        Ok(Value::Number(BigDecimal::from_str("0.1").unwrap()))
+    );
+    assert_eq!(
+        Value::try_from(2.5_f32),
+        Ok(Value::Number(BigDecimal::from_str("2.5").unwrap()))
+    );
+    assert!(Value::try_from(f64::NAN).is_err());
+    assert!(Value::try_from(f32::INFINITY).is_err());
+    assert_eq!(
+        Value::from(BigDecimal::from_str("1.50").unwrap()),
+        Value::Number(BigDecimal::from_str("1.50").unwrap())
+    );
+}
+
+#[test]
+fn other_conversions() {
+    let uuid = Uuid::from_u128(42);
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let time = NaiveTime::from_hms_opt(12, 30, 0).unwrap();
+    let datetime = Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+    let offset = FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+        .unwrap();
+
+    assert_eq!(Value::from(true), Value::Bool(true));
+    assert_eq!(Value::from("a"), Value::String("a".to_owned()));
+    assert_eq!(Value::from("a".to_owned()), Value::String("a".to_owned()));
+    assert_eq!(Value::from(uuid), Value::Uuid(uuid));
+    assert_eq!(Value::from(date), Value::Date(date));
+    assert_eq!(Value::from(time), Value::Time(time));
+    assert_eq!(Value::from(datetime), Value::DateTime(datetime));
+    assert_eq!(Value::from(offset), Value::DateTime(datetime));
+    assert_eq!(Value::from(datetime.naive_utc()), Value::DateTime(datetime));
+    assert_eq!(Value::from(Some(3)), Value::Number(BigDecimal::from(3)));
+    assert_eq!(Value::from(None::<&str>), Value::Null);
+}
@@ -0,0 +1,143 @@
+use odata_params::filters::{parse_str, Expr};
+use std::collections::{BTreeSet, HashMap};
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+fn canonical(filter: &str) -> Expr {
+    parsed(filter).canonicalize()
+}
+
+fn key(filter: &str) -> String {
+    parsed(filter).canonical_key().expect("valid filter")
+}
+
+#[test]
+fn orders_commutative_operands() {
+    assert_eq!(
+        canonical("name eq 'Bob' and (age gt 3 and city eq 'Paris')"),
+        canonical("(city eq 'Paris' and name eq 'Bob') and age gt 3")
+    );
+    assert_eq!(
+        canonical("name eq 'Bob' or age gt 3 or name eq 'Bob'"),
+        canonical("age gt 3 or name eq 'Bob'")
+    );
+    assert_eq!(
+        canonical("id in (3, 1, 2, 1)"),
+        canonical("id in (1, 2, 3)")
+    );
+
+    // `and` and `or` are not mixed together.
+    assert_ne!(
+        canonical("(aa eq 1 and bb eq 2) or cc eq 3"),
+        canonical("aa eq 1 and (bb eq 2 or cc eq 3)")
+    );
+}
+
+#[test]
+fn orients_comparisons() {
+    assert_eq!(canonical("18 lt age"), canonical("age gt 18"));
+    assert_eq!(canonical("18 ge age"), canonical("age le 18"));
+    assert_eq!(canonical("'Bob' eq name"), canonical("name eq 'Bob'"));
+    assert_eq!(canonical("name eq first"), canonical("first eq name"));
+    assert_eq!(canonical("width gt height"), canonical("height lt width"));
+
+    // Function arguments keep their order.
+    assert_ne!(
+        canonical("contains(name, 'Bob')"),
+        canonical("contains('Bob', name)")
+    );
+}
+
+#[test]
+fn normalizes_numbers() {
+    assert_eq!(canonical("price eq 1.50"), canonical("price eq 1.5"));
+    assert_eq!(canonical("price eq 100.00"), canonical("price eq 100"));
+    assert_eq!(key("price eq 100.00"), "price eq 100");
+    assert_eq!(key("price in (2.0, 1.10, 2)"), "price in (1.1, 2)");
+}
+
+#[test]
+fn keeps_datetime_precision() {
+    assert_ne!(
+        canonical("created gt 2024-01-01T10:00:00.1234567Z"),
+        canonical("created gt 2024-01-01T10:00:00.123Z")
+    );
+    assert_ne!(
+        canonical("created gt 2024-01-01T00:00:00.0001Z"),
+        canonical("created gt 2024-01-01T00:00:00Z")
+    );
+    assert_eq!(
+        canonical("created gt 2024-01-01T12:00:00.5+02:00"),
+        canonical("created gt 2024-01-01T10:00:00.500Z")
+    );
+    assert_ne!(
+        key("created gt 2024-01-01T10:00:00.000001Z"),
+        key("created gt 2024-01-01T10:00:00.000002Z")
+    );
+    assert_eq!(
+        key("created gt 2024-01-01T12:00:00.1234567+02:00"),
+        "created gt 2024-01-01T10:00:00.123456700Z"
+    );
+}
+
+#[test]
+fn renames_lambda_variables() {
+    assert_eq!(
+        canonical("tags/any(tag: tag eq 'vip')"),
+        canonical("tags/any(label: label eq 'vip')")
+    );
+    assert_eq!(
+        key("orders/any(order: items/all(item: item gt order) and order ne name)"),
+        key("orders/any(oo: items/all(ii: ii gt oo) and oo ne name)")
+    );
+    assert_eq!(
+        key("tags/any(tag: tag eq 'vip')"),
+        "tags/any(_0: _0 eq 'vip')"
+    );
+
+    // Identifiers that look like renamed variables are not captured.
+    assert_eq!(key("tags/any(tag: tag eq _0)"), "tags/any(__0: _0 eq __0)");
+}
+
+#[test]
+fn is_idempotent() {
+    for filter in [
+        "not (18 lt age) or name in ('b', 'a') and tags/all(tag: 'x' ne tag)",
+        "price eq 1.500 and created lt 2024-01-01T10:00:00.1234567Z",
+        "(aa eq 1 or bb eq 2) and (cc eq 3 or aa eq 1)",
+    ] {
+        let once = canonical(filter);
+        assert_eq!(once.clone().canonicalize(), once);
+    }
+}
+
+#[test]
+fn hashes_equivalent_filters_alike() {
+    let mut cache = HashMap::new();
+
+    cache.insert(canonical("name eq 'Bob' and 3 lt age"), "first");
+    cache.insert(canonical("age gt 3.0 and 'Bob' eq name"), "second");
+    cache.insert(canonical("age gt 3 or name eq 'Bob'"), "third");
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache[&canonical("age gt 3 and name eq 'Bob'")], "second");
+}
+
+#[test]
+fn orders_expressions() {
+    let sorted = ["name eq 'b'", "age gt 3", "name eq 'a'", "age gt 3"]
+        .into_iter()
+        .map(canonical)
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(
+        sorted.into_iter().collect::<Vec<_>>(),
+        [
+            canonical("age gt 3"),
+            canonical("name eq 'a'"),
+            canonical("name eq 'b'"),
+        ]
+    );
+}
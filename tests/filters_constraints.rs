@@ -0,0 +1,169 @@
+use odata_params::filters::{parse_str, Bound, Constraint, Value, ValueSet};
+use std::collections::BTreeMap;
+
+fn extracted(filter: &str) -> BTreeMap<String, Constraint> {
+    parse_str(filter).expect("valid filter tree").constraints()
+}
+
+fn number(value: i64) -> Value {
+    Value::Number(value.into())
+}
+
+fn string(value: &str) -> Value {
+    Value::String(value.to_owned())
+}
+
+fn bound(value: Value, inclusive: bool) -> Option<Bound> {
+    Some(Bound { value, inclusive })
+}
+
+fn values(sets: Vec<ValueSet>) -> Constraint {
+    Constraint {
+        nullable: false,
+        values: Some(sets),
+    }
+}
+
+#[test]
+fn equality_sets() {
+    let constraints = extracted("tenant eq 7 and region in ('EU', 'US', 'EU')");
+
+    assert_eq!(
+        constraints["tenant"],
+        values(vec![ValueSet::In(vec![number(7)])])
+    );
+    assert_eq!(
+        constraints["region"],
+        values(vec![ValueSet::In(vec![string("EU"), string("US")])])
+    );
+}
+
+#[test]
+fn ranges() {
+    let constraints = extracted("age gt 18 and age le 65");
+
+    assert_eq!(
+        constraints["age"],
+        values(vec![ValueSet::Range {
+            lower: bound(number(18), false),
+            upper: bound(number(65), true),
+        }])
+    );
+
+    // Contradictions are simplified away into `false` first.
+    assert!(extracted("age gt 18 and score lt 10 and score ge 10").is_empty());
+
+    let constraints = extracted("created ge 2024-01-01 and created lt 2024-02-01");
+    let created = &constraints["created"];
+
+    assert!(created.contains(&Value::Date("2024-01-31".parse().expect("valid date"))));
+    assert!(!created.contains(&Value::Date("2024-02-01".parse().expect("valid date"))));
+    assert!(!created.contains(&Value::Null));
+}
+
+#[test]
+fn null_checks() {
+    let constraints = extracted("deleted eq null and owner ne null and size ne 3");
+
+    assert_eq!(
+        constraints["deleted"],
+        Constraint {
+            nullable: true,
+            values: Some(vec![]),
+        }
+    );
+    assert_eq!(
+        constraints["owner"],
+        Constraint {
+            nullable: false,
+            values: None,
+        }
+    );
+
+    let size = &constraints["size"];
+    assert!(size.contains(&Value::Null));
+    assert!(size.contains(&number(4)));
+    assert!(!size.contains(&number(3)));
+
+    // A negated ordering also holds for `null`.
+    let age = &extracted("not (age gt 18)")["age"];
+    assert!(age.contains(&Value::Null));
+    assert!(age.contains(&number(18)));
+    assert!(!age.contains(&number(19)));
+
+    let code = &extracted("code in (1, null)")["code"];
+    assert!(code.contains(&Value::Null));
+    assert!(code.contains(&number(1)));
+    assert!(!code.contains(&number(2)));
+}
+
+#[test]
+fn prefixes() {
+    let constraints = extracted("startswith(sku, 'AB') and startswith(sku, 'ABC')");
+    assert_eq!(
+        constraints["sku"],
+        values(vec![ValueSet::Prefix("ABC".to_owned())])
+    );
+
+    assert!(extracted("startswith(sku, 'AB') and startswith(sku, 'XY')")["sku"].is_empty());
+    assert!(extracted("startswith(sku, 'AB') and sku ge 'AC'")["sku"].is_empty());
+    assert!(
+        extracted("startswith(sku, 'AB') and sku in ('ABX', 'XYZ')")["sku"]
+            .contains(&string("ABX"))
+    );
+    assert!(
+        !extracted("startswith(sku, 'AB') and sku in ('ABX', 'XYZ')")["sku"]
+            .contains(&string("XYZ"))
+    );
+}
+
+#[test]
+fn unions_through_or() {
+    let constraints = extracted("(tenant eq 1 and age gt 3) or (tenant eq 2 and name eq 'x')");
+
+    assert_eq!(
+        constraints["tenant"],
+        values(vec![ValueSet::In(vec![number(1), number(2)])])
+    );
+    assert!(!constraints.contains_key("age"));
+    assert!(!constraints.contains_key("name"));
+
+    let constraints = extracted("price lt 10 or price gt 100 or price eq 50");
+    let price = &constraints["price"];
+
+    assert!(price.contains(&number(5)));
+    assert!(price.contains(&number(50)));
+    assert!(!price.contains(&number(60)));
+    assert!(!price.contains(&Value::Null));
+}
+
+#[test]
+fn intersects_unions() {
+    let constraints =
+        extracted("(tenant eq 1 or tenant eq 2 or tenant eq 3) and (tenant ge 2 or flag eq true)");
+
+    // The second term does not constrain the tenant on its own.
+    assert_eq!(
+        constraints["tenant"],
+        values(vec![ValueSet::In(vec![number(1), number(2), number(3)])])
+    );
+
+    let constraints = extracted("(tenant eq 1 or tenant eq 2 or tenant eq 3) and tenant ge 2");
+
+    assert_eq!(
+        constraints["tenant"],
+        values(vec![ValueSet::In(vec![number(2), number(3)])])
+    );
+}
+
+#[test]
+fn ignores_other_terms() {
+    let constraints = extracted(
+        "tags/any(tag: tag eq 'x') and contains(name, 'a') and width gt height and kind has 1",
+    );
+
+    assert!(constraints.is_empty());
+
+    let constraints = extracted("endswith(name, 'a') and age eq 3");
+    assert_eq!(constraints.keys().collect::<Vec<_>>(), ["age"]);
+}
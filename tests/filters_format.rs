@@ -0,0 +1,221 @@
+use odata_params::chrono::{FixedOffset, SecondsFormat};
+use odata_params::filters::{
+    parse_str, to_query_string_with, CompareOperator, Expr, FormatOptions, KeywordCase, QuoteStyle,
+};
+use odata_params::orderby;
+
+fn formatted(filter: &str, options: &FormatOptions) -> String {
+    let expr = parse_str(filter).expect("valid filter tree");
+    to_query_string_with(&expr, options).expect("valid filter")
+}
+
+fn identifier(name: &str) -> Box<Expr> {
+    Box::new(Expr::Identifier(name.to_owned()))
+}
+
+fn truthy(name: &str) -> Expr {
+    Expr::Compare(
+        identifier(name),
+        CompareOperator::Equal,
+        Box::new(parse_str("true").expect("valid filter tree")),
+    )
+}
+
+#[test]
+fn minimal_parentheses() {
+    let options = FormatOptions::default();
+
+    assert_eq!(
+        formatted("aa eq 1 and bb eq 2 and cc eq 3", &options),
+        "aa eq 1 and bb eq 2 and cc eq 3"
+    );
+    assert_eq!(
+        formatted("aa eq 1 or (bb eq 2 and cc eq 3)", &options),
+        "aa eq 1 or bb eq 2 and cc eq 3"
+    );
+    assert_eq!(
+        formatted("aa eq 1 and (bb eq 2 or cc eq 3)", &options),
+        "aa eq 1 and (bb eq 2 or cc eq 3)"
+    );
+    assert_eq!(
+        formatted("(aa eq 1 and bb eq 2) or cc eq 3", &options),
+        "(aa eq 1 and bb eq 2) or cc eq 3"
+    );
+    assert_eq!(
+        formatted("((aa eq 1)) and ((bb eq 2))", &options),
+        "aa eq 1 and bb eq 2"
+    );
+    assert_eq!(
+        formatted(
+            "contains(name, 'a') and tags/any(tag: tag eq 'x' or tag eq 'y')",
+            &options
+        ),
+        "contains(name, 'a') and tags/any(tag: tag eq 'x' or tag eq 'y')"
+    );
+}
+
+#[test]
+fn not_operands() {
+    let options = FormatOptions::default();
+
+    assert_eq!(formatted("not (aa eq 1)", &options), "not (aa eq 1)");
+    assert_eq!(
+        formatted("not contains(name, 'a')", &options),
+        "not contains(name, 'a')"
+    );
+    assert_eq!(
+        formatted("not aa eq 1 and bb eq 2", &options),
+        "not (aa eq 1 and bb eq 2)"
+    );
+    assert_eq!(
+        formatted("aa eq 1 and not (bb eq 2)", &options),
+        "aa eq 1 and not (bb eq 2)"
+    );
+
+    let expr = Expr::And(
+        Box::new(Expr::Not(Box::new(truthy("aa")))),
+        Box::new(truthy("bb")),
+    );
+    let output = to_query_string_with(&expr, &options).expect("valid filter");
+
+    assert_eq!(output, "(not (aa eq true)) and bb eq true");
+    assert_eq!(parse_str(output), Ok(expr));
+}
+
+#[test]
+fn round_trips() {
+    let filters = [
+        "aa eq 1 and bb eq 2 or cc eq 3",
+        "(aa eq 1 or bb eq 2) and (cc eq 3 or not dd eq 4)",
+        "((aa eq 1 or bb eq 2) and cc eq 3) or dd in (1, 2, 3)",
+        "not (aa eq 1 or bb eq 2) and cc ne null",
+        "orders/all(ord: ord/total gt 5 and (ord/paid eq true or ord/total lt 1))",
+        "concat(first, concat(' ', last)) eq 'Jane Doe' and price le @max",
+        "name eq 'O\\'Brien \\\\ Co' and created lt 2024-03-01T10:00:00.123Z",
+    ];
+
+    let backslash = FormatOptions {
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    };
+    let pretty = FormatOptions {
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::pretty()
+    };
+
+    for filter in filters {
+        let expr = parse_str(filter).expect("valid filter tree");
+
+        for options in [&backslash, &pretty] {
+            let output = to_query_string_with(&expr, options).expect("valid filter");
+            assert_eq!(parse_str(&output).as_ref(), Ok(&expr), "{output}");
+        }
+    }
+}
+
+#[test]
+fn multi_line() {
+    let output = formatted(
+        "(aa eq 1 or bb eq 2) and (cc eq 3 or dd/any(item: item eq 1 or item eq 2)) and not (ee eq 5)",
+        &FormatOptions {
+            indent: Some(4),
+            ..FormatOptions::default()
+        },
+    );
+
+    assert_eq!(
+        output,
+        "(\n    aa eq 1\n    or bb eq 2\n)\nand (\n    cc eq 3\n    or dd/any(item: item eq 1 or item eq 2)\n)\nand not (ee eq 5)"
+    );
+
+    assert_eq!(
+        formatted(
+            "aa eq 1 or (bb eq 2 and (cc eq 3 or dd eq 4))",
+            &FormatOptions::pretty()
+        ),
+        "aa eq 1\nor bb eq 2\nand (\n  cc eq 3\n  or dd eq 4\n)"
+    );
+}
+
+#[test]
+fn keyword_case() {
+    let options = FormatOptions {
+        keyword_case: KeywordCase::Upper,
+        ..FormatOptions::default()
+    };
+
+    assert_eq!(
+        formatted(
+            "not (aa in (1, 2)) or tags/all(tag: tag has 'x') and name eq null",
+            &options
+        ),
+        "NOT (aa IN (1, 2) OR tags/ALL(tag: tag HAS 'x') AND name EQ null)"
+    );
+}
+
+#[test]
+fn datetimes() {
+    let filter = "created gt 2024-03-01T10:00:00.123456+02:00";
+
+    assert_eq!(
+        formatted(filter, &FormatOptions::default()),
+        "created gt 2024-03-01T08:00:00.123Z"
+    );
+
+    let options = FormatOptions {
+        datetime_precision: SecondsFormat::Micros,
+        datetime_offset: FixedOffset::east_opt(2 * 3600),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        formatted(filter, &options),
+        "created gt 2024-03-01T10:00:00.123456+02:00"
+    );
+
+    let options = FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        formatted("created gt 2024-03-01T10:00:00Z", &options),
+        "created gt 2024-03-01T10:00:00Z"
+    );
+}
+
+#[test]
+fn quote_styles() {
+    let filter = "name eq 'It\\'s a \\\\ path'";
+
+    assert_eq!(
+        formatted(filter, &FormatOptions::default()),
+        "name eq 'It''s a \\ path'"
+    );
+
+    let options = FormatOptions {
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    };
+    assert_eq!(formatted(filter, &options), "name eq 'It\\'s a \\\\ path'");
+}
+
+#[test]
+fn percent_encoding() {
+    let options = FormatOptions {
+        percent_encode: true,
+        ..FormatOptions::pretty()
+    };
+
+    assert_eq!(
+        formatted(
+            "name eq 'Zoë & co' or created lt 2024-03-01T10:00:00+01:00",
+            &options
+        ),
+        "name%20eq%20%27Zo%C3%AB%20%26%20co%27%0Aor%20created%20lt%202024-03-01T09%3A00%3A00.000Z"
+    );
+
+    let items = orderby::parse_str("name, tolower(city) desc").expect("valid order by");
+    assert_eq!(
+        orderby::to_query_string_with(&items, &options).expect("valid order by"),
+        "name%2C%20tolower%28city%29%20desc"
+    );
+}
@@ -0,0 +1,202 @@
+use odata_params::filters::{parse_str, Implication};
+
+fn implies(premise: &str, conclusion: &str) -> Implication {
+    let premise = parse_str(premise).expect("valid filter tree");
+    let conclusion = parse_str(conclusion).expect("valid filter tree");
+
+    premise.implies(&conclusion)
+}
+
+#[test]
+fn numeric_ranges() {
+    assert_eq!(implies("age gt 21", "age gt 18"), Implication::Yes);
+    assert_eq!(implies("age ge 18", "age gt 18"), Implication::No);
+    assert_eq!(implies("age gt 18", "age ge 18"), Implication::Yes);
+    assert_eq!(
+        implies("age gt 18 and age lt 30", "age ge 10 and age le 30"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("age eq 20", "age gt 18 and age lt 21"),
+        Implication::Yes
+    );
+    assert_eq!(implies("age lt 18", "age ne 18"), Implication::Yes);
+    assert_eq!(implies("age gt 18.5", "age gt 18.4"), Implication::Yes);
+    assert_eq!(implies("age gt 18.4", "age gt 19"), Implication::No);
+}
+
+#[test]
+fn numbers_may_be_integers() {
+    assert_eq!(
+        implies("age gt 18 and age lt 19", "age eq 30"),
+        Implication::Unknown
+    );
+    assert_eq!(implies("age gt 18.4", "age gt 18.5"), Implication::Unknown);
+    assert_eq!(
+        implies("age gt 18 and age lt 20", "age eq 30"),
+        Implication::No
+    );
+    assert_eq!(implies("age gt 18", "age ge 19.5"), Implication::No);
+    assert_eq!(
+        implies("id ge 1 and id le 3", "id in (1, 2, 3)"),
+        Implication::Unknown
+    );
+}
+
+#[test]
+fn in_sets() {
+    assert_eq!(
+        implies(
+            "color in ('red', 'blue')",
+            "color in ('red', 'green', 'blue')"
+        ),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("color in ('red', 'pink')", "color in ('red', 'blue')"),
+        Implication::No
+    );
+    assert_eq!(
+        implies("color eq 'red' or color eq 'blue'", "color ne 'green'"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("id in (1, 2, 3)", "id ge 1 and id le 3"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("id ge 1 and id le 4", "id in (1, 2, 3)"),
+        Implication::No
+    );
+}
+
+#[test]
+fn dates_and_strings() {
+    assert_eq!(
+        implies(
+            "created ge 2024-03-01 and created lt 2024-04-01",
+            "created gt 2024-01-01"
+        ),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("created gt 2024-01-01", "created ge 2024-01-02"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies(
+            "created gt 2024-01-01T00:00:00Z",
+            "created ge 2024-01-01T00:00:01Z"
+        ),
+        Implication::No
+    );
+    assert_eq!(
+        implies(
+            "created gt 2024-01-01T00:00:00.0001Z",
+            "created gt 2024-01-01T00:00:00Z"
+        ),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies(
+            "created gt 2024-01-01T00:00:00Z",
+            "created gt 2024-01-01T00:00:00.0001Z"
+        ),
+        Implication::No
+    );
+    assert_eq!(
+        implies("name ge 'b' and name lt 'c'", "name gt 'a'"),
+        Implication::Yes
+    );
+    assert_eq!(implies("name gt 'b'", "name ge 'b'"), Implication::Yes);
+    assert_eq!(implies("name ge 'b'", "name gt 'b'"), Implication::No);
+}
+
+#[test]
+fn null_semantics() {
+    // A `null` age matches `age ne 5` but neither bound.
+    assert_eq!(implies("age ne 5", "age lt 5 or age gt 5"), Implication::No);
+    assert_eq!(
+        implies("age ne 5 and age ne null", "age lt 5 or age gt 5"),
+        Implication::Yes
+    );
+    assert_eq!(implies("age gt 5", "age ne null"), Implication::Yes);
+    assert_eq!(implies("not (age gt 5)", "age le 5"), Implication::No);
+    assert_eq!(implies("age le 5", "not (age gt 5)"), Implication::Yes);
+}
+
+#[test]
+fn conjunctions_and_disjunctions() {
+    let allowed = "tenant eq 7 and (status eq 'open' or status eq 'closed')";
+
+    assert_eq!(
+        implies("tenant eq 7 and status eq 'open' and price lt 10", allowed),
+        Implication::Yes
+    );
+    assert_eq!(implies("status eq 'open'", allowed), Implication::No);
+    assert_eq!(
+        implies("tenant eq 7 and status eq 'draft'", allowed),
+        Implication::No
+    );
+    assert_eq!(
+        implies("(aa eq 1 and bb eq 2) or (aa eq 1 and bb eq 3)", "aa eq 1"),
+        Implication::Yes
+    );
+    assert_eq!(implies("aa eq 1 or bb eq 2", "aa eq 1"), Implication::No);
+}
+
+#[test]
+fn contradictions_and_tautologies() {
+    assert_eq!(
+        implies("age gt 5 and age lt 3", "name eq 'x'"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("name eq 'x'", "age gt 5 or age le 5 or age eq null"),
+        Implication::Yes
+    );
+    assert_eq!(implies("true", "false"), Implication::No);
+}
+
+#[test]
+fn unknown_terms() {
+    assert_eq!(
+        implies("contains(name, 'a') and age gt 5", "contains(name, 'a')"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("contains(name, 'a') and age gt 5", "age gt 3"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("contains(name, 'a')", "contains(name, 'b')"),
+        Implication::Unknown
+    );
+    assert_eq!(
+        implies("age gt 5", "age gt 3 and tags/any(tag: tag eq 'x')"),
+        Implication::Unknown
+    );
+    assert_eq!(
+        implies("width gt height", "height lt width"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("tags/any(tag: tag eq 'x')", "tags/any(label: 'x' eq label)"),
+        Implication::Yes
+    );
+    assert_eq!(
+        implies("width gt height", "width ge height"),
+        Implication::Unknown
+    );
+    assert_eq!(implies("age gt 5", "age gt 'old'"), Implication::Unknown);
+}
+
+#[test]
+fn subsumption() {
+    let cached = parse_str("price lt 100 and category eq 'books'").expect("valid filter tree");
+    let requested =
+        parse_str("category eq 'books' and price lt 20 and stock gt 0").expect("valid filter tree");
+
+    assert_eq!(cached.subsumes(&requested), Implication::Yes);
+    assert_eq!(requested.subsumes(&cached), Implication::No);
+}
@@ -0,0 +1,194 @@
+use odata_params::filters::{parse_str, Expr, Field, FieldMap, MappingError};
+use odata_params::orderby;
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+fn fields() -> FieldMap {
+    FieldMap::new()
+        .with_renamed("createdAt", "created_at")
+        .with_renamed("owner", "users")
+        .with_renamed("owner/name", "users/display_name")
+        .with_renamed("tags", "labels")
+        .with_computed("fullName", parsed("concat(first, concat(' ', last))"))
+        .with_hidden("passwordHash")
+}
+
+fn identifier(name: &str) -> Expr {
+    Expr::Identifier(name.to_owned())
+}
+
+#[test]
+fn renames_identifiers_and_paths() {
+    assert_eq!(
+        fields().map_filter(parsed("createdAt gt 2024-01-01 and owner/name eq 'Bob'")),
+        Ok(parsed(
+            "created_at gt 2024-01-01 and users/display_name eq 'Bob'"
+        ))
+    );
+
+    // Paths not listed as a whole go through their longest listed prefix.
+    assert_eq!(
+        fields().map_filter(parsed("owner/email eq 'x'")),
+        Ok(parsed("users/email eq 'x'"))
+    );
+}
+
+#[test]
+fn computes_fields() {
+    assert_eq!(
+        fields().map_filter(parsed("fullName eq 'Ada Lovelace'")),
+        Ok(parsed("concat(first, concat(' ', last)) eq 'Ada Lovelace'"))
+    );
+    assert_eq!(
+        fields().map_filter(parsed("fullName/length eq 3")),
+        Err(vec![MappingError::UnknownField {
+            name: "fullName/length".to_owned(),
+        }])
+    );
+}
+
+#[test]
+fn rejects_unknown_and_hidden_fields() {
+    assert_eq!(
+        fields().map_filter(parsed(
+            "age gt 3 or passwordHash eq 'x' or age lt 1 or passwordHash/salt eq 'y'"
+        )),
+        Err(vec![
+            MappingError::UnknownField {
+                name: "age".to_owned(),
+            },
+            MappingError::HiddenField {
+                name: "passwordHash".to_owned(),
+            },
+        ])
+    );
+}
+
+#[test]
+fn maps_paths_from_lambda_variables() {
+    assert_eq!(
+        fields().map_filter(parsed("tags/any(tag: tag/name eq owner/name)")),
+        Ok(parsed("labels/any(tag: tag/name eq users/display_name)"))
+    );
+
+    let fields = fields()
+        .with_renamed("orders", "order_rows")
+        .with_renamed("orders/total", "order_rows/amount")
+        .with_renamed("orders/lines/sku", "order_rows/lines/product_code");
+
+    assert_eq!(
+        fields.map_filter(parsed(
+            "orders/any(ord: ord/total gt 5 and ord/lines/all(line: line/sku ne 'x'))"
+        )),
+        Ok(parsed(
+            "order_rows/any(ord: ord/amount gt 5 and ord/lines/all(line: line/product_code ne 'x'))"
+        ))
+    );
+}
+
+#[test]
+fn rejects_hidden_and_unknown_fields_through_lambdas() {
+    let fields = fields()
+        .with_renamed("orders", "order_rows")
+        .with_hidden("orders/internalNote")
+        .with_renamed("orders/status", "status_code");
+
+    assert_eq!(
+        fields.map_filter(parsed("orders/any(ord: ord/internalNote eq 'x')")),
+        Err(vec![MappingError::HiddenField {
+            name: "orders/internalNote".to_owned(),
+        }])
+    );
+    assert_eq!(
+        fields.map_filter(parsed("items/any(it: it/price gt 1)")),
+        Err(vec![
+            MappingError::UnknownField {
+                name: "items".to_owned(),
+            },
+            MappingError::UnknownField {
+                name: "items/price".to_owned(),
+            },
+        ])
+    );
+    assert_eq!(
+        fields.map_filter(parsed("orders/any(ord: ord/status eq 1)")),
+        Err(vec![MappingError::OutsideCollection {
+            name: "orders/status".to_owned(),
+        }])
+    );
+}
+
+#[test]
+fn rejects_computed_collections() {
+    let fields = fields().with_computed("tagsC", parsed("concat(aa, bb)"));
+
+    assert_eq!(
+        fields.map_filter(parsed("tagsC/any(tt: tt eq 'x')")),
+        Err(vec![MappingError::NotACollection {
+            name: "tagsC".to_owned(),
+        }])
+    );
+}
+
+#[test]
+fn renames_lambda_variables_capturing_mapped_identifiers() {
+    assert_eq!(
+        fields().map_filter(parsed("tags/any(first: fullName eq first/name)")),
+        Ok(parsed(
+            "labels/any(first1: concat(first, concat(' ', last)) eq first1/name)"
+        ))
+    );
+
+    // Names in use are skipped, and inner lambdas with the same variable
+    // shadow the renamed one.
+    assert_eq!(
+        fields().map_filter(parsed(
+            "tags/any(users: users eq owner/name and users/any(users1: users1 eq 1))"
+        )),
+        Ok(parsed(
+            "labels/any(users2: users2 eq users/display_name and users2/any(users1: users1 eq 1))"
+        ))
+    );
+    assert_eq!(
+        fields().map_filter(parsed(
+            "tags/any(last: last/items/any(last: last eq 1) and fullName eq last)"
+        )),
+        Ok(parsed(
+            "labels/any(last1: last1/items/any(last: last eq 1) and concat(first, concat(' ', last)) eq last1)"
+        ))
+    );
+}
+
+#[test]
+fn maps_order_by() {
+    let items = orderby::parse_str("createdAt desc, fullName").expect("valid order by");
+    let items = fields().map_order_by(items).expect("mapped order by");
+
+    assert_eq!(items[0].expr, identifier("created_at"));
+    assert_eq!(items[0].direction, orderby::Direction::Descending);
+    assert_eq!(items[1].expr, parsed("concat(first, concat(' ', last))"));
+
+    let items = orderby::parse_str("passwordHash").expect("valid order by");
+
+    assert_eq!(
+        fields().map_order_by(items),
+        Err(vec![MappingError::HiddenField {
+            name: "passwordHash".to_owned(),
+        }])
+    );
+}
+
+#[test]
+fn fields_are_plain_data() {
+    let mut map = FieldMap::default();
+    map.fields
+        .insert("id".to_owned(), Field::Renamed("user_id".to_owned()));
+
+    assert_eq!(
+        map.map_filter(parsed("id eq 1")),
+        Ok(parsed("user_id eq 1"))
+    );
+    assert_eq!(map, FieldMap::new().with_renamed("id", "user_id"));
+}
@@ -657,3 +657,33 @@ fn multiple_nested_functions() {
         )
     );
 }
+
+#[test]
+fn property_paths() {
+    let filter = "owner/name eq 'Bob' and owner/tags/any(tag: tag/label eq 'vip')";
+    let result = parse_str(filter).expect("valid filter tree");
+
+    assert_eq!(
+        result,
+        Expr::And(
+            Expr::Compare(
+                Expr::Identifier("owner/name".to_owned()).into(),
+                Equal,
+                Expr::Value(Value::String("Bob".to_owned())).into()
+            )
+            .into(),
+            Expr::Lambda(
+                Expr::Identifier("owner/tags".to_owned()).into(),
+                LambdaOperator::Any,
+                "tag".to_owned(),
+                Expr::Compare(
+                    Expr::Identifier("tag/label".to_owned()).into(),
+                    Equal,
+                    Expr::Value(Value::String("vip".to_owned())).into()
+                )
+                .into()
+            )
+            .into()
+        )
+    );
+}
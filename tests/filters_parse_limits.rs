@@ -0,0 +1,188 @@
+use odata_params::filters::{parse_str, parse_str_with, Expr, ParseError, ParseOptions};
+
+#[test]
+fn default_options_have_no_limits() {
+    let filter = format!("name in ({})", vec!["'x'"; 1000].join(", "));
+
+    assert!(parse_str_with(&filter, &ParseOptions::default()).is_ok());
+}
+
+#[test]
+fn input_too_long() {
+    let options = ParseOptions {
+        max_length: Some(10),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with("age gt 30", &options).is_ok());
+    assert_eq!(
+        parse_str_with("name eq 'John'", &options),
+        Err(ParseError::InputTooLong {
+            length: 14,
+            max: 10
+        })
+    );
+}
+
+#[test]
+fn nesting_too_deep() {
+    let options = ParseOptions {
+        max_depth: Some(3),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with("((age gt 30))", &options).is_ok());
+    assert_eq!(
+        parse_str_with("(((age gt 30)))", &options),
+        Err(ParseError::NestingTooDeep { max: 3 })
+    );
+    assert_eq!(
+        parse_str_with("not not not isActive", &options),
+        Err(ParseError::NestingTooDeep { max: 3 })
+    );
+    assert_eq!(
+        parse_str_with("aa eq 1 or bb eq 2 or cc eq 3 or dd eq 4", &options),
+        Err(ParseError::NestingTooDeep { max: 3 })
+    );
+    assert_eq!(
+        parse_str_with("length(trim(tolower(name))) gt 3", &options),
+        Err(ParseError::NestingTooDeep { max: 3 })
+    );
+}
+
+#[test]
+fn hostile_nesting_does_not_overflow_the_stack() {
+    let filter = format!("{}xx eq 1{}", "(".repeat(100_000), ")".repeat(100_000));
+
+    let options = ParseOptions {
+        max_depth: Some(32),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        parse_str_with(&filter, &options),
+        Err(ParseError::NestingTooDeep { max: 32 })
+    );
+}
+
+#[test]
+fn nested_groups_parse_in_linear_time() {
+    let filter = format!("{}xx eq 1{}", "(".repeat(30), ")".repeat(30));
+
+    assert!(parse_str(filter).is_ok());
+}
+
+#[test]
+fn too_many_nodes() {
+    let options = ParseOptions {
+        max_nodes: Some(7),
+        ..ParseOptions::default()
+    };
+
+    // Two comparisons of three nodes each joined by one `and` node.
+    assert!(parse_str_with("aa eq 1 and bb eq 2", &options).is_ok());
+    assert_eq!(
+        parse_str_with("aa eq 1 and not bb eq 2", &options),
+        Err(ParseError::TooManyNodes { max: 7 })
+    );
+    assert_eq!(
+        parse_str_with("concat(aa, bb, cc, dd, ee, ff, gg) eq 'x'", &options),
+        Err(ParseError::TooManyNodes { max: 7 })
+    );
+}
+
+#[test]
+fn syntax_errors_within_the_node_limit() {
+    let options = ParseOptions {
+        max_nodes: Some(10),
+        ..ParseOptions::default()
+    };
+
+    // The grammar runs again to locate the error, counting nodes afresh.
+    assert_eq!(
+        parse_str_with("aa eq 1 and bb eq", &options),
+        Err(ParseError::Parsing { position: 17 })
+    );
+    assert_eq!(
+        parse_str_with(
+            "aa eq 1 and bb eq 2 and",
+            &ParseOptions {
+                max_nodes: Some(14),
+                ..options
+            }
+        ),
+        Err(ParseError::Parsing { position: 23 })
+    );
+    assert_eq!(
+        parse_str_with("aa eq 1 and bb eq 2 and cc eq", &ParseOptions::untrusted()),
+        Err(ParseError::Parsing { position: 29 })
+    );
+}
+
+#[test]
+fn in_list_too_large() {
+    let options = ParseOptions {
+        max_in_list: Some(3),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with("id in (1, 2, 3)", &options).is_ok());
+    assert_eq!(
+        parse_str_with("id in (1, 2, 3, 4)", &options),
+        Err(ParseError::InListTooLarge { max: 3 })
+    );
+
+    // Function arguments are not `in` lists.
+    assert!(parse_str_with("concat(aa, bb, cc, dd) eq 'abcd'", &options).is_ok());
+}
+
+#[test]
+fn lambda_nesting_too_deep() {
+    let options = ParseOptions {
+        max_lambda_depth: Some(1),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with("tags/any(tt: tt eq 'a')", &options).is_ok());
+    assert_eq!(
+        parse_str_with("orders/any(oo: items/all(ii: ii gt 1))", &options),
+        Err(ParseError::LambdaNestingTooDeep { max: 1 })
+    );
+
+    let no_lambdas = ParseOptions {
+        max_lambda_depth: Some(0),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        parse_str_with("tags/any(tt: tt eq 'a')", &no_lambdas),
+        Err(ParseError::LambdaNestingTooDeep { max: 0 })
+    );
+}
+
+#[test]
+fn string_literal_too_long() {
+    let options = ParseOptions {
+        max_string_length: Some(4),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        parse_str_with(r"name eq 'J\'hn'", &options),
+        Ok(parse_str(r"name eq 'J\'hn'").expect("valid filter tree"))
+    );
+    assert_eq!(
+        parse_str_with("name eq 'Johnny'", &options),
+        Err(ParseError::StringLiteralTooLong { max: 4 })
+    );
+}
+
+#[test]
+fn untrusted_accepts_typical_filters() {
+    let filter =
+        "(name eq 'John' and isActive eq true) or (age gt 30 and tags/any(tag: tag eq 'vip'))";
+    let result = parse_str_with(filter, &ParseOptions::untrusted()).expect("valid filter tree");
+
+    assert!(matches!(result, Expr::Or(..)));
+    assert_eq!(result, parse_str(filter).expect("valid filter tree"));
+}
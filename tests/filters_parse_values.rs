@@ -223,3 +223,21 @@ fn escaped_string_comparison() {
         )
     );
 }
+
+#[test]
+fn doubled_quote_string_comparison() {
+    let filter = "name in ('O''Brien', '''quoted''', '')";
+    let result = parse_str(filter).expect("valid filter tree");
+
+    assert_eq!(
+        result,
+        Expr::In(
+            Expr::Identifier("name".to_owned()).into(),
+            vec![
+                Expr::Value(Value::String(String::from("O'Brien"))),
+                Expr::Value(Value::String(String::from("'quoted'"))),
+                Expr::Value(Value::String(String::new())),
+            ],
+        )
+    );
+}
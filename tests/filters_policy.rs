@@ -0,0 +1,221 @@
+use odata_params::filters::CompareOperator::*;
+use odata_params::filters::{parse_str, FilterPolicy, LambdaOperator, PolicyViolation};
+use std::collections::HashMap;
+
+fn check(filter: &str, policy: &FilterPolicy) -> Result<(), Vec<PolicyViolation>> {
+    parse_str(filter)
+        .expect("valid filter tree")
+        .check_policy(policy)
+}
+
+#[test]
+fn default_policy_allows_everything() {
+    let policy = FilterPolicy::default();
+
+    assert_eq!(
+        check(
+            "not contains(name, 'x') and tags/all(tag: tag has 1) or id in (1, 2)",
+            &policy
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn operators() {
+    let policy = FilterPolicy {
+        operators: Some([Equal, NotEqual].into()),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("name eq 'John' or age ne 3", &policy), Ok(()));
+    assert_eq!(check("id in (1, 2)", &policy), Ok(()));
+    assert_eq!(
+        check("age gt 3 and age le 10 and age gt 5", &policy),
+        Err(vec![
+            PolicyViolation::OperatorNotAllowed {
+                operator: GreaterThan
+            },
+            PolicyViolation::OperatorNotAllowed {
+                operator: LessOrEqual
+            },
+        ])
+    );
+
+    let no_equality = FilterPolicy {
+        operators: Some([GreaterThan].into()),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(
+        check("id in (1, 2)", &no_equality),
+        Err(vec![PolicyViolation::InNotAllowed])
+    );
+}
+
+#[test]
+fn functions() {
+    let policy = FilterPolicy {
+        functions: Some(["startswith".to_string(), "tolower".to_string()].into()),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("startswith(tolower(name), 'j')", &policy), Ok(()));
+    assert_eq!(
+        check(
+            "startswith(name, 'j') or contains(tolower(name), 'j')",
+            &policy
+        ),
+        Err(vec![PolicyViolation::FunctionNotAllowed {
+            name: "contains".to_string()
+        }])
+    );
+}
+
+#[test]
+fn lambda_operators() {
+    let policy = FilterPolicy {
+        lambda_operators: Some([].into()),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(
+        check("tags/any(tag: tag eq 'a')", &policy),
+        Err(vec![PolicyViolation::LambdaNotAllowed {
+            operator: LambdaOperator::Any
+        }])
+    );
+}
+
+#[test]
+fn identifier_operators() {
+    let policy = FilterPolicy {
+        identifier_operators: HashMap::from([("id".to_string(), [Equal].into())]),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("id eq 3 and name gt 'a'", &policy), Ok(()));
+    assert_eq!(check("id in (1, 2, 3)", &policy), Ok(()));
+    assert_eq!(
+        check("3 lt id", &policy),
+        Err(vec![PolicyViolation::OperatorNotAllowedOn {
+            identifier: "id".to_string(),
+            operator: LessThan
+        }])
+    );
+
+    let no_equality = FilterPolicy {
+        identifier_operators: HashMap::from([("id".to_string(), [GreaterThan].into())]),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(
+        check("id in (1, 2, 3)", &no_equality),
+        Err(vec![PolicyViolation::InNotAllowedOn {
+            identifier: "id".to_string()
+        }])
+    );
+}
+
+#[test]
+fn lambda_variables_use_collection_restrictions() {
+    let policy = FilterPolicy {
+        identifier_operators: HashMap::from([("tags".to_string(), [Equal].into())]),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("tags/any(tag: tag eq 'a')", &policy), Ok(()));
+    assert_eq!(
+        check("tags/any(tag: tag ne 'a')", &policy),
+        Err(vec![PolicyViolation::OperatorNotAllowedOn {
+            identifier: "tags".to_string(),
+            operator: NotEqual
+        }])
+    );
+}
+
+#[test]
+fn non_filterable_identifiers() {
+    let policy = FilterPolicy {
+        non_filterable: ["password".to_string()].into(),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("name eq 'x'", &policy), Ok(()));
+    assert_eq!(
+        check("startswith(password, 'a') or password eq 'b'", &policy),
+        Err(vec![PolicyViolation::IdentifierNotFilterable {
+            identifier: "password".to_string()
+        }])
+    );
+}
+
+#[test]
+fn paths_from_lambda_variables_use_collection_paths() {
+    let policy = FilterPolicy {
+        identifier_operators: HashMap::from([("orders/total".to_string(), [Equal].into())]),
+        non_filterable: ["orders/secret".to_string()].into(),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("orders/any(ord: ord/total eq 1)", &policy), Ok(()));
+    assert_eq!(
+        check("orders/any(ord: ord/total gt 1)", &policy),
+        Err(vec![PolicyViolation::OperatorNotAllowedOn {
+            identifier: "orders/total".to_string(),
+            operator: GreaterThan
+        }])
+    );
+    assert_eq!(
+        check("orders/any(ord: ord/secret eq 1)", &policy),
+        Err(vec![PolicyViolation::IdentifierNotFilterable {
+            identifier: "orders/secret".to_string()
+        }])
+    );
+}
+
+#[test]
+fn paths_under_non_filterable_identifiers() {
+    let policy = FilterPolicy {
+        non_filterable: ["owner".to_string()].into(),
+        ..FilterPolicy::default()
+    };
+
+    assert_eq!(check("ownerName eq 'x'", &policy), Ok(()));
+    assert_eq!(
+        check("owner/ssn eq 1", &policy),
+        Err(vec![PolicyViolation::IdentifierNotFilterable {
+            identifier: "owner/ssn".to_string()
+        }])
+    );
+    assert_eq!(
+        check("owner/pets/any(pet: pet/name eq 'x')", &policy),
+        Err(vec![
+            PolicyViolation::IdentifierNotFilterable {
+                identifier: "owner/pets".to_string()
+            },
+            PolicyViolation::IdentifierNotFilterable {
+                identifier: "owner/pets/name".to_string()
+            },
+        ])
+    );
+}
+
+#[test]
+fn violation_messages() {
+    assert_eq!(
+        PolicyViolation::OperatorNotAllowedOn {
+            identifier: "id".to_string(),
+            operator: GreaterThan
+        }
+        .to_string(),
+        "The 'gt' operator is not allowed on 'id'."
+    );
+    assert_eq!(
+        PolicyViolation::FunctionNotAllowed {
+            name: "contains".to_string()
+        }
+        .to_string(),
+        "The function 'contains' is not allowed."
+    );
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "proptest")]
+
+use odata_params::chrono::SecondsFormat;
+use odata_params::filters::{
+    borrowed, parse_str, parse_str_spanned, parse_str_with, to_query_string_with, Expr,
+    FormatOptions, FunctionsTypeMap, IdentifiersTypeMap, ParseError, ParseOptions, QuoteStyle,
+    Type, Value, VisitorMut,
+};
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+/// The options writing filters that `parse_str` reads back unchanged.
+fn round_trip_options() -> FormatOptions {
+    FormatOptions {
+        datetime_precision: SecondsFormat::AutoSi,
+        quote_style: QuoteStyle::Backslash,
+        ..FormatOptions::default()
+    }
+}
+
+/// Removes backslashes from strings, which doubled quotes leave unescaped.
+struct StripBackslashes;
+
+impl VisitorMut for StripBackslashes {
+    fn visit_value_mut(&mut self, value: &mut Value) {
+        if let Value::String(s) = value {
+            s.retain(|c| c != '\\');
+        }
+    }
+}
+
+fn type_maps() -> (IdentifiersTypeMap, FunctionsTypeMap) {
+    let identifiers = IdentifiersTypeMap::from(HashMap::from([
+        ("name".to_string(), Type::String),
+        ("age".to_string(), Type::Number),
+    ]));
+    let functions = FunctionsTypeMap::from(HashMap::from([(
+        "concat".to_string(),
+        (vec![Type::String], Some(Type::String), Type::String),
+    )]));
+
+    (identifiers, functions)
+}
+
+/// Strings made of the tokens of the grammar, more likely to get far into
+/// the parser than arbitrary strings.
+fn filter_like() -> impl Strategy<Value = String> {
+    let token = prop_oneof![
+        prop::sample::select(vec![
+            "(", ")", " ", ",", "'", "''", r"\", "@", "/", ":", "-", ".", "eq", "and", "or", "not",
+            "in", "any", "null", "true", "T", "Z", "u",
+        ])
+        .prop_map(str::to_string),
+        "[a-z]{1,4}",
+        "[0-9]{1,4}",
+    ];
+
+    prop::collection::vec(token, 0..48).prop_map(|tokens| tokens.concat())
+}
+
+proptest! {
+    #[test]
+    fn written_filters_parse_back(expr in any::<Expr>()) {
+        let filter = to_query_string_with(&expr, &round_trip_options()).unwrap();
+
+        prop_assert_eq!(parse_str(&filter), Ok(expr), "{}", filter);
+    }
+
+    #[test]
+    fn filters_with_doubled_quotes_parse_back(mut expr in any::<Expr>()) {
+        let options = FormatOptions {
+            quote_style: QuoteStyle::Doubled,
+            ..round_trip_options()
+        };
+        StripBackslashes.visit_expr_mut(&mut expr);
+        let filter = to_query_string_with(&expr, &options).unwrap();
+
+        prop_assert_eq!(parse_str(&filter), Ok(expr), "{}", filter);
+    }
+
+    #[test]
+    fn all_parsers_agree(expr in any::<Expr>()) {
+        let filter = to_query_string_with(&expr, &round_trip_options()).unwrap();
+
+        prop_assert_eq!(borrowed::parse_str(&filter).map(|e| e.into_owned()), Ok(expr.clone()));
+        prop_assert_eq!(parse_str_spanned(&filter).map(|e| e.into_expr()), Ok(expr));
+    }
+
+    #[test]
+    fn parsing_never_panics(filter in any::<String>()) {
+        let _ = parse_str(&filter);
+        let _ = borrowed::parse_str(&filter);
+    }
+
+    #[test]
+    fn parsing_filter_like_strings_never_panics(filter in filter_like()) {
+        let _ = parse_str(&filter);
+        let _ = parse_str_with(&filter, &ParseOptions::untrusted());
+        let _ = borrowed::parse_str(&filter);
+        let _ = parse_str_spanned(&filter);
+    }
+
+    #[test]
+    fn parsed_filters_are_written_parseably(filter in filter_like()) {
+        if let Ok(expr) = parse_str(&filter) {
+            let written = to_query_string_with(&expr, &round_trip_options()).unwrap();
+
+            prop_assert_eq!(parse_str(&written), Ok(expr), "{:?} was written as {:?}", filter, written);
+        }
+    }
+
+    #[test]
+    fn validation_never_panics(expr in any::<Expr>()) {
+        let (identifiers, functions) = type_maps();
+
+        let _ = expr.validate(&identifiers, &functions);
+    }
+
+    #[test]
+    fn validation_of_untrusted_input_never_overflows_the_stack(
+        depth in 0..20_000usize,
+        (open, close) in prop_oneof![
+            Just(("(", ")")),
+            Just(("not ", "")),
+            Just(("concat(", ")")),
+            Just(("tags/any(tag: ", ")")),
+        ],
+    ) {
+        let (identifiers, functions) = type_maps();
+        let filter = format!("{}name eq 'aa'{}", open.repeat(depth), close.repeat(depth));
+
+        match parse_str_with(&filter, &ParseOptions::untrusted()) {
+            Ok(expr) => {
+                let _ = expr.validate(&identifiers, &functions);
+            }
+            Err(error) => {
+                let limited = matches!(
+                    error,
+                    ParseError::InputTooLong { .. }
+                        | ParseError::NestingTooDeep { .. }
+                        | ParseError::LambdaNestingTooDeep { .. }
+                );
+                prop_assert!(limited, "{:?}", error);
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use odata_params::filters::{parse_str, to_query_string, Expr, RowFilter, SecurityViolation};
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+fn tenant_filter() -> RowFilter {
+    RowFilter::new(parsed("tenant eq 7"))
+}
+
+#[test]
+fn combinators_group_operands() {
+    let expr = parsed("aa eq 1 or bb eq 2").and_with(parsed("cc eq 3 or dd eq 4"));
+
+    assert_eq!(
+        to_query_string(&expr).expect("valid filter"),
+        "(aa eq 1 or bb eq 2) and (cc eq 3 or dd eq 4)"
+    );
+    assert_eq!(parsed(&to_query_string(&expr).expect("valid filter")), expr);
+
+    let expr = parsed("aa eq 1 and bb eq 2").or_with(parsed("cc eq 3"));
+
+    assert_eq!(
+        expr,
+        Expr::Or(
+            Box::new(parsed("aa eq 1 and bb eq 2")),
+            Box::new(parsed("cc eq 3"))
+        )
+    );
+    assert_eq!(
+        parsed("aa eq 1").negate(),
+        Expr::Not(Box::new(parsed("aa eq 1")))
+    );
+}
+
+#[test]
+fn merges_predicate_first() {
+    let merged = tenant_filter()
+        .merge(Some(parsed("name eq 'Bob' or true")))
+        .expect("allowed filter");
+
+    assert_eq!(
+        merged,
+        Expr::And(
+            Box::new(parsed("tenant eq 7")),
+            Box::new(parsed("name eq 'Bob' or true"))
+        )
+    );
+
+    // The user filter stays grouped once written out and parsed again.
+    for user in [
+        "name eq 'Bob' or true",
+        "not (name eq 'Bob')",
+        "age gt 3 and age lt 9 or vip eq true",
+    ] {
+        let merged = tenant_filter()
+            .merge(Some(parsed(user)))
+            .expect("allowed filter");
+        let written = to_query_string(&merged).expect("valid filter");
+
+        assert_eq!(parsed(&written), merged, "{written}");
+    }
+}
+
+#[test]
+fn merges_without_user_filter() {
+    assert_eq!(tenant_filter().merge(None), Ok(parsed("tenant eq 7")));
+}
+
+#[test]
+fn rejects_protected_identifiers() {
+    assert_eq!(
+        tenant_filter().merge(Some(parsed("tenant eq 8 or tenant eq 9"))),
+        Err(vec![SecurityViolation::ProtectedIdentifier {
+            identifier: "tenant".to_owned(),
+        }])
+    );
+
+    let row_filter =
+        RowFilter::new(parsed("owner eq 'bob' and deleted eq false")).with_protected("secret");
+
+    assert_eq!(
+        row_filter.merge(Some(parsed(
+            "contains(secret, 'x') or items/any(item: item eq deleted)"
+        ))),
+        Err(vec![
+            SecurityViolation::ProtectedIdentifier {
+                identifier: "secret".to_owned(),
+            },
+            SecurityViolation::ProtectedIdentifier {
+                identifier: "deleted".to_owned(),
+            },
+        ])
+    );
+}
+
+#[test]
+fn lambda_variables_shadow_protected_identifiers() {
+    let merged = tenant_filter()
+        .merge(Some(parsed("tenants/any(tenant: tenant eq 9)")))
+        .expect("allowed filter");
+
+    assert_eq!(
+        merged,
+        parsed("tenant eq 7").and_with(parsed("tenants/any(tenant: tenant eq 9)"))
+    );
+}
+
+#[test]
+fn rejects_paths_under_protected_identifiers() {
+    assert_eq!(
+        tenant_filter().merge(Some(parsed("tenant/id eq 8 or tenantName eq 'x'"))),
+        Err(vec![SecurityViolation::ProtectedIdentifier {
+            identifier: "tenant/id".to_owned(),
+        }])
+    );
+}
+
+#[test]
+fn rejects_protected_identifiers_reached_through_lambdas() {
+    let row_filter = tenant_filter()
+        .with_protected("secrets")
+        .with_protected("orders/margin");
+
+    assert_eq!(
+        row_filter.merge(Some(parsed("secrets/any(sec: sec eq 'x')"))),
+        Err(vec![SecurityViolation::ProtectedIdentifier {
+            identifier: "secrets".to_owned(),
+        }])
+    );
+    assert_eq!(
+        row_filter.merge(Some(parsed(
+            "orders/any(ord: ord/margin gt 10 and ord/lines/all(line: line/tenant eq 1))"
+        ))),
+        Err(vec![SecurityViolation::ProtectedIdentifier {
+            identifier: "orders/margin".to_owned(),
+        }])
+    );
+    assert!(row_filter
+        .merge(Some(parsed("orders/any(ord: ord/total gt 10)")))
+        .is_ok());
+}
+
+#[test]
+fn rejects_aliases() {
+    assert_eq!(
+        tenant_filter().merge(Some(parsed("name eq @name or age gt @age"))),
+        Err(vec![
+            SecurityViolation::AliasNotAllowed {
+                alias: "@name".to_owned(),
+            },
+            SecurityViolation::AliasNotAllowed {
+                alias: "@age".to_owned(),
+            },
+        ])
+    );
+}
@@ -0,0 +1,174 @@
+use odata_params::filters::{parse_str, CompareOperator, Expr, NormalFormError, Value};
+
+fn parsed(filter: &str) -> Expr {
+    parse_str(filter).expect("valid filter tree")
+}
+
+fn simplified(filter: &str) -> Expr {
+    parsed(filter).simplify()
+}
+
+fn boolean(value: bool) -> Expr {
+    Expr::Value(Value::Bool(value))
+}
+
+#[test]
+fn folds_constants() {
+    let age = Expr::Compare(
+        Box::new(Expr::Identifier("age".to_owned())),
+        CompareOperator::GreaterThan,
+        Box::new(Expr::Value(Value::Number(18.into()))),
+    );
+
+    assert_eq!(
+        Expr::And(Box::new(boolean(true)), Box::new(age.clone())).simplify(),
+        age
+    );
+    assert_eq!(
+        Expr::Or(Box::new(age.clone()), Box::new(boolean(true))).simplify(),
+        boolean(true)
+    );
+    assert_eq!(
+        Expr::Not(Box::new(boolean(false))).simplify(),
+        boolean(true)
+    );
+
+    assert_eq!(simplified("age gt 18 and 1 eq 2"), boolean(false));
+    assert_eq!(simplified("age gt 18 or 'b' gt 'a'"), boolean(true));
+    assert_eq!(simplified("null eq null and age eq age"), boolean(true));
+    assert_eq!(simplified("age ne age or name lt name"), boolean(false));
+
+    // Ordering with `null` is left to the data source.
+    assert_eq!(simplified("null lt 1"), parsed("null lt 1"));
+}
+
+#[test]
+fn keeps_constants_on_the_right() {
+    assert_eq!(simplified("18 lt age"), parsed("age gt 18"));
+    assert_eq!(simplified("'Bob' eq name"), parsed("name eq 'Bob'"));
+    assert_eq!(simplified("3 le length(name)"), parsed("length(name) ge 3"));
+}
+
+#[test]
+fn pushes_not_down() {
+    assert_eq!(simplified("not (not (age eq 18))"), parsed("age eq 18"));
+    assert_eq!(
+        simplified("not (name eq 'Bob' or city ne 'Paris')"),
+        parsed("name ne 'Bob' and city eq 'Paris'")
+    );
+
+    // `not (age gt 18)` also matches a null age, unlike `age le 18`.
+    assert_eq!(
+        simplified("not (age gt 18 and name eq 'Bob')"),
+        parsed("(not (age gt 18)) or name ne 'Bob'")
+    );
+
+    assert_eq!(
+        simplified("not tags/any(tag: tag eq 'vip')"),
+        parsed("tags/all(tag: tag ne 'vip')")
+    );
+}
+
+#[test]
+fn flattens_and_deduplicates() {
+    assert_eq!(
+        simplified("(aa eq 1 and (bb eq 2 and aa eq 1)) and (cc eq 3 and bb eq 2)"),
+        parsed("aa eq 1 and bb eq 2 and cc eq 3")
+    );
+    assert_eq!(
+        simplified("(aa eq 1 or bb eq 2) or (bb eq 2 or cc eq 3)"),
+        parsed("aa eq 1 or bb eq 2 or cc eq 3")
+    );
+    assert_eq!(
+        simplified("name eq 'Bob' and not (name eq 'Bob')"),
+        boolean(false)
+    );
+    assert_eq!(simplified("name eq 'Bob' or name ne 'Bob'"), boolean(true));
+    assert_eq!(
+        simplified("name eq 'Bob' and (age gt 18 or name eq 'Bob')"),
+        parsed("name eq 'Bob'")
+    );
+}
+
+#[test]
+fn merges_equalities_into_in_lists() {
+    assert_eq!(
+        simplified("color eq 'red' or size gt 3 or color eq 'blue' or color in ('red', 'green')"),
+        parsed("color in ('red', 'blue', 'green') or size gt 3")
+    );
+    assert_eq!(simplified("id in (1, 1)"), parsed("id eq 1"));
+    assert_eq!(simplified("id in (2, 3, 2)"), parsed("id in (2, 3)"));
+    assert_eq!(simplified("id in ()"), boolean(false));
+    assert_eq!(simplified("id eq 1 or id eq 1"), parsed("id eq 1"));
+}
+
+#[test]
+fn collapses_ranges() {
+    assert_eq!(simplified("age gt 18 and age ge 21"), parsed("age ge 21"));
+    assert_eq!(simplified("age gt 18 and age ge 18"), parsed("age gt 18"));
+    assert_eq!(
+        simplified("age lt 65 and age gt 18 and age le 70"),
+        parsed("age gt 18 and age lt 65")
+    );
+    assert_eq!(simplified("age ge 18 and age le 18"), parsed("age eq 18"));
+    assert_eq!(simplified("age gt 18 and age lt 18"), boolean(false));
+    assert_eq!(simplified("age eq 30 and age gt 18"), parsed("age eq 30"));
+    assert_eq!(simplified("age eq 10 and age gt 18"), boolean(false));
+    assert_eq!(simplified("age eq 10 and age eq 11"), boolean(false));
+    assert_eq!(
+        simplified("birthday gt 2000-01-01 and birthday lt 1990-01-01"),
+        boolean(false)
+    );
+
+    assert_eq!(simplified("age gt 18 or age ge 21"), parsed("age gt 18"));
+    assert_eq!(simplified("age lt 5 or age le 5"), parsed("age le 5"));
+    assert_eq!(
+        simplified("age gt 18 or age eq 30 or age eq 3"),
+        parsed("age eq 3 or age gt 18")
+    );
+
+    // Constants of different types are left alone.
+    assert_eq!(
+        simplified("age gt 18 and age lt 'old'"),
+        parsed("age gt 18 and age lt 'old'")
+    );
+}
+
+#[test]
+fn simplifies_inside_functions_and_lambdas() {
+    assert_eq!(
+        simplified("tags/any(tag: tag eq 'a' or tag eq 'b') and contains(name, 'x')"),
+        parsed("tags/any(tag: tag in ('a', 'b')) and contains(name, 'x')")
+    );
+    assert_eq!(simplified("tags/any(tag: 1 eq 2)"), boolean(false));
+    assert_eq!(simplified("tags/all(tag: 1 eq 1)"), boolean(true));
+}
+
+#[test]
+fn normal_forms() {
+    let expr =
+        parse_str("(aa eq 1 or bb eq 2) and (cc eq 3 or dd eq 4)").expect("valid filter tree");
+
+    assert_eq!(
+        expr.clone().to_cnf(4),
+        Ok(parsed("(aa eq 1 or bb eq 2) and (cc eq 3 or dd eq 4)"))
+    );
+    assert_eq!(
+        expr.clone().to_dnf(4),
+        Ok(parsed(
+            "(aa eq 1 and cc eq 3) or (aa eq 1 and dd eq 4) or (bb eq 2 and cc eq 3) or (bb eq 2 and dd eq 4)"
+        ))
+    );
+    assert_eq!(
+        expr.to_dnf(3),
+        Err(NormalFormError::TooManyClauses { max: 3 })
+    );
+
+    let expr =
+        parse_str("(aa eq 1 and bb eq 2) or (aa eq 1 and cc eq 3)").expect("valid filter tree");
+
+    assert_eq!(
+        expr.to_cnf(8),
+        Ok(parsed("aa eq 1 and (bb eq 2 or cc eq 3)"))
+    );
+}
@@ -0,0 +1,174 @@
+use odata_params::filters::{
+    parse_str, parse_str_spanned, parse_str_spanned_with, FunctionsTypeMap, IdentifiersTypeMap,
+    ParseError, ParseOptions, Span, SpannedExpr, SpannedExprKind, Type, ValidationError,
+};
+use odata_params::query::ODataError;
+use std::collections::HashMap;
+
+fn type_maps() -> (IdentifiersTypeMap, FunctionsTypeMap) {
+    let identifiers = IdentifiersTypeMap::from(HashMap::from([
+        ("name".to_string(), Type::String),
+        ("age".to_string(), Type::Number),
+        ("tags".to_string(), Type::String),
+    ]));
+    let functions = FunctionsTypeMap::from(HashMap::from([(
+        "startswith".to_string(),
+        (vec![Type::String, Type::String], None, Type::Boolean),
+    )]));
+
+    (identifiers, functions)
+}
+
+fn text(filter: &str, expr: &SpannedExpr) -> String {
+    filter[expr.span.range()].to_string()
+}
+
+#[test]
+fn test_spans_of_logical_and_comparison_nodes() {
+    let filter = "name eq 'Bob' or age gt 30";
+    let expr = parse_str_spanned(filter).unwrap();
+
+    assert_eq!(expr.span, Span { start: 0, end: 26 });
+
+    let SpannedExprKind::Or(lhs, rhs) = &expr.kind else {
+        panic!("expected or, got {expr:?}");
+    };
+    assert_eq!(text(filter, lhs), "name eq 'Bob'");
+    assert_eq!(text(filter, rhs), "age gt 30");
+
+    let SpannedExprKind::Compare(name, _, value) = &lhs.kind else {
+        panic!("expected comparison, got {lhs:?}");
+    };
+    assert_eq!(text(filter, name), "name");
+    assert_eq!(text(filter, value), "'Bob'");
+}
+
+#[test]
+fn test_spans_of_groups_include_parentheses() {
+    let filter = "(name eq 'Bob') and not (age gt 30)";
+    let expr = parse_str_spanned(filter).unwrap();
+
+    let SpannedExprKind::And(lhs, rhs) = &expr.kind else {
+        panic!("expected and, got {expr:?}");
+    };
+    assert_eq!(text(filter, &expr), filter);
+    assert_eq!(text(filter, lhs), "(name eq 'Bob')");
+    assert_eq!(text(filter, rhs), "not (age gt 30)");
+}
+
+#[test]
+fn test_spans_of_functions_lambdas_and_in_lists() {
+    let filter = "startswith(name, 'B') and tags/any(tag: tag in ('aa', 'bb'))";
+    let expr = parse_str_spanned(filter).unwrap();
+
+    let SpannedExprKind::And(function, lambda) = &expr.kind else {
+        panic!("expected and, got {expr:?}");
+    };
+    assert_eq!(text(filter, function), "startswith(name, 'B')");
+    assert_eq!(text(filter, lambda), "tags/any(tag: tag in ('aa', 'bb'))");
+
+    let SpannedExprKind::Lambda(collection, _, _, body) = &lambda.kind else {
+        panic!("expected lambda, got {lambda:?}");
+    };
+    assert_eq!(text(filter, collection), "tags");
+    assert_eq!(text(filter, body), "tag in ('aa', 'bb')");
+}
+
+#[test]
+fn test_spans_are_relative_to_the_untrimmed_filter() {
+    let filter = "  \t@p1 eq 'Bob'  ";
+    let expr = parse_str_spanned(filter).unwrap();
+
+    assert_eq!(text(filter, &expr), "@p1 eq 'Bob'");
+    assert_eq!(expr.span.to_string(), "3..15");
+}
+
+#[test]
+fn test_into_expr_matches_plain_parsing() {
+    let filters = [
+        "name eq 'Bob' or age gt 30 and not (age lt 10)",
+        "startswith(name, 'B') and tags/all(tag: tag ne 'vip')",
+        "age in (1, 2, 3) and @p1 eq null",
+    ];
+
+    for filter in filters {
+        assert_eq!(
+            parse_str_spanned(filter).unwrap().into_expr(),
+            parse_str(filter).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_spanned_parsing_enforces_limits() {
+    let options = ParseOptions {
+        max_nodes: Some(2),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        parse_str_spanned_with("aa eq 1 and bb eq 2", &options),
+        Err(ParseError::TooManyNodes { max: 2 }),
+    );
+}
+
+#[test]
+fn test_validation_errors_point_at_the_failing_node() {
+    let (identifiers, functions) = type_maps();
+
+    let cases = [
+        ("name eq 'Bob' and agee gt 30", "agee"),
+        ("name eq 'Bob' and age eq 'Bob'", "age eq 'Bob'"),
+        ("age gt 1 and lower(name) eq 'bob'", "lower(name)"),
+        ("startswith(name, age)", "age"),
+        ("startswith(name) or age gt 1", "startswith(name)"),
+        ("age in (1, 'aa')", "age in (1, 'aa')"),
+        ("not (age)", "not (age)"),
+        ("tags/any(tag: tag eq @p1)", "@p1"),
+    ];
+
+    for (filter, expected) in cases {
+        let expr = parse_str_spanned(filter).unwrap();
+        let error = expr.validate(&identifiers, &functions).unwrap_err();
+
+        assert_eq!(&filter[error.span.range()], expected, "{filter}");
+        assert_eq!(
+            Err(error.error),
+            expr.into_expr().validate(&identifiers, &functions),
+        );
+    }
+}
+
+#[test]
+fn test_validation_of_valid_spanned_filters() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str_spanned("startswith(name, 'B') and tags/any(tag: tag eq 'vip')").unwrap();
+
+    assert_eq!(expr.validate(&identifiers, &functions), Ok(Type::Boolean));
+}
+
+#[test]
+fn test_spanned_validation_error_reporting() {
+    let (identifiers, functions) = type_maps();
+    let error = parse_str_spanned("name eq 'Bob' and agee gt 30")
+        .unwrap()
+        .validate(&identifiers, &functions)
+        .unwrap_err();
+
+    assert_eq!(
+        error.error,
+        ValidationError::UndefinedIdentifier {
+            name: "agee".to_string()
+        },
+    );
+    assert_eq!(
+        error.to_string(),
+        "Undefined identifier 'agee'. (at 18..22)"
+    );
+    assert_eq!(error.code(), "UndefinedIdentifier");
+    assert_eq!(error.target(), Some("agee"));
+
+    let odata = ODataError::from(error);
+    assert_eq!(odata.code, "UndefinedIdentifier");
+    assert_eq!(odata.target.as_deref(), Some("agee"));
+}
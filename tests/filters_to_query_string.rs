@@ -1,6 +1,6 @@
 use odata_params::bigdecimal::BigDecimal;
 use odata_params::chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
-use odata_params::filters::{to_query_string, CompareOperator, Expr, Value};
+use odata_params::filters::{parse_str, to_query_string, CompareOperator, Expr, Value};
 
 #[test]
 fn or_grouping() {
@@ -303,3 +303,16 @@ fn null_value() {
     let result = to_query_string(&expr).expect("valid filter");
     assert_eq!(result, "description eq null");
 }
+
+#[test]
+fn quoted_string_parses_back() {
+    let expr = Expr::Compare(
+        Box::new(Expr::Identifier("lastName".to_owned())),
+        CompareOperator::Equal,
+        Box::new(Expr::Value(Value::String("O'Brien".to_owned()))),
+    );
+
+    let result = to_query_string(&expr).expect("valid filter");
+    assert_eq!(result, "lastName eq 'O''Brien'");
+    assert_eq!(parse_str(&result), Ok(expr));
+}
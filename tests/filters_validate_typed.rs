@@ -0,0 +1,266 @@
+use bigdecimal::BigDecimal;
+use odata_params::filters::{
+    parse_str, CompareOperator, FunctionSignature, FunctionsTypeMap, IdentifiersTypeMap, Type,
+    TypedExpr, TypedExprKind, ValidationError, Value,
+};
+use std::collections::HashMap;
+
+fn type_maps() -> (IdentifiersTypeMap, FunctionsTypeMap) {
+    let mut id_map = HashMap::new();
+    id_map.insert("age".to_string(), Type::Number);
+    id_map.insert("name".to_string(), Type::String);
+    id_map.insert("labels".to_string(), Type::String);
+    id_map.insert("deletedAt".to_string(), Type::DateTime);
+
+    let mut func_map = HashMap::new();
+    func_map.insert(
+        "startswith".to_string(),
+        (vec![Type::String, Type::String], None, Type::Boolean),
+    );
+    func_map.insert(
+        "concat".to_string(),
+        (vec![Type::String], Some(Type::String), Type::String),
+    );
+
+    (id_map.into(), func_map.into())
+}
+
+fn node(ty: Type, kind: TypedExprKind) -> TypedExpr {
+    TypedExpr { ty, kind }
+}
+
+#[test]
+fn comparison_types_every_node() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str("age gt 30").expect("valid filter tree");
+
+    assert_eq!(
+        expr.validate_typed(&identifiers, &functions),
+        Ok(node(
+            Type::Boolean,
+            TypedExprKind::Compare(
+                node(Type::Number, TypedExprKind::Identifier("age".to_string())).into(),
+                CompareOperator::GreaterThan,
+                node(
+                    Type::Number,
+                    TypedExprKind::Value(Value::Number(BigDecimal::from(30)))
+                )
+                .into(),
+            )
+        ))
+    );
+}
+
+#[test]
+fn null_operand_is_converted() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str("null ne deletedAt").expect("valid filter tree");
+
+    assert_eq!(
+        expr.validate_typed(&identifiers, &functions),
+        Ok(node(
+            Type::Boolean,
+            TypedExprKind::Compare(
+                node(
+                    Type::DateTime,
+                    TypedExprKind::Convert(
+                        node(Type::Null, TypedExprKind::Value(Value::Null)).into()
+                    )
+                )
+                .into(),
+                CompareOperator::NotEqual,
+                node(
+                    Type::DateTime,
+                    TypedExprKind::Identifier("deletedAt".to_string())
+                )
+                .into(),
+            )
+        ))
+    );
+}
+
+#[test]
+fn in_operator_converts_to_common_type() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str("name in (null, 'Jane')").expect("valid filter tree");
+    let typed = expr
+        .validate_typed(&identifiers, &functions)
+        .expect("valid types");
+
+    let TypedExprKind::In(lhs, values) = typed.kind else {
+        panic!("expected an in expression");
+    };
+
+    assert!(matches!(lhs.kind, TypedExprKind::Identifier(_)));
+    assert!(matches!(values[0].kind, TypedExprKind::Convert(_)));
+    assert!(matches!(values[0].ty, Type::String));
+    assert!(matches!(values[1].kind, TypedExprKind::Value(_)));
+}
+
+#[test]
+fn function_records_signature() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str("concat(name, ' ', name)").expect("valid filter tree");
+    let typed = expr
+        .validate_typed(&identifiers, &functions)
+        .expect("valid types");
+
+    assert!(matches!(typed.ty, Type::String));
+
+    let TypedExprKind::Function(name, signature, args) = typed.kind else {
+        panic!("expected a function call");
+    };
+
+    assert_eq!(name, "concat");
+    assert_eq!(
+        signature,
+        FunctionSignature {
+            arguments: vec![Type::String],
+            variadic: Some(Type::String),
+            returns: Type::String,
+        }
+    );
+    assert_eq!(args.len(), 3);
+}
+
+#[test]
+fn lambda_variable_is_distinguished_from_identifier() {
+    let (identifiers, functions) = type_maps();
+    let expr = parse_str("labels/any(label: startswith(label, name))").expect("valid filter tree");
+    let typed = expr
+        .validate_typed(&identifiers, &functions)
+        .expect("valid types");
+
+    let TypedExprKind::Lambda(_, _, var, body) = typed.kind else {
+        panic!("expected a lambda");
+    };
+    let TypedExprKind::Function(_, _, args) = body.kind else {
+        panic!("expected a function call");
+    };
+
+    assert_eq!(var, "label");
+    assert_eq!(
+        args[0],
+        node(
+            Type::String,
+            TypedExprKind::Convert(
+                node(
+                    Type::Null,
+                    TypedExprKind::LambdaVariable("label".to_string())
+                )
+                .into()
+            )
+        )
+    );
+    assert_eq!(
+        args[1],
+        node(Type::String, TypedExprKind::Identifier("name".to_string()))
+    );
+}
+
+#[test]
+fn lambda_variable_is_out_of_scope_after_lambda() {
+    let (identifiers, functions) = type_maps();
+    let expr =
+        parse_str("labels/any(label: label eq 'x') and label eq 'y'").expect("valid filter tree");
+
+    assert_eq!(
+        expr.validate_typed(&identifiers, &functions),
+        Err(ValidationError::UndefinedIdentifier {
+            name: "label".to_string()
+        })
+    );
+}
+
+#[test]
+fn lambda_variable_paths_are_in_scope() {
+    let (identifiers, functions) = type_maps();
+    let expr =
+        parse_str("labels/any(label: label/text eq name and label/tags/any(tag: tag/id eq 1))")
+            .expect("valid filter tree");
+    let typed = expr
+        .validate_typed(&identifiers, &functions)
+        .expect("valid types");
+
+    let TypedExprKind::Lambda(_, _, _, body) = typed.kind else {
+        panic!("expected a lambda");
+    };
+    let TypedExprKind::And(compare, _) = body.kind else {
+        panic!("expected and");
+    };
+    let TypedExprKind::Compare(lhs, _, _) = compare.kind else {
+        panic!("expected a comparison");
+    };
+    assert_eq!(
+        lhs.kind,
+        TypedExprKind::Convert(
+            node(
+                Type::Null,
+                TypedExprKind::LambdaVariable("label/text".to_string())
+            )
+            .into()
+        )
+    );
+
+    for filter in [
+        "labels/any(label: labelled/text eq 'x')",
+        "labels/any(label: label eq 'x') and label/text eq 'y'",
+    ] {
+        let expr = parse_str(filter).expect("valid filter tree");
+
+        assert!(
+            matches!(
+                expr.validate_typed(&identifiers, &functions),
+                Err(ValidationError::UndefinedIdentifier { .. })
+            ),
+            "{filter}"
+        );
+    }
+}
+
+#[test]
+fn errors_match_validate() {
+    let (identifiers, functions) = type_maps();
+
+    for filter in [
+        "age eq 'thirty'",
+        "unknown eq 1",
+        "startswith(name)",
+        "not age",
+        "age and name",
+    ] {
+        let expr = parse_str(filter).expect("valid filter tree");
+
+        assert_eq!(
+            expr.validate_typed(&identifiers, &functions)
+                .map(|typed| typed.ty),
+            expr.validate(&identifiers, &functions),
+            "{filter}"
+        );
+        assert!(expr.validate(&identifiers, &functions).is_err(), "{filter}");
+    }
+}
+
+#[test]
+fn types_match_validate() {
+    let (identifiers, functions) = type_maps();
+
+    for (filter, ty) in [
+        ("age gt 30 and not startswith(name, 'a')", Type::Boolean),
+        ("age in (1, null, 3)", Type::Boolean),
+        ("concat(name, null, labels)", Type::String),
+        ("labels/any(label: label/size gt 2)", Type::Boolean),
+        ("deletedAt", Type::DateTime),
+        ("null", Type::Null),
+    ] {
+        let expr = parse_str(filter).expect("valid filter tree");
+
+        assert_eq!(expr.validate(&identifiers, &functions), Ok(ty), "{filter}");
+        assert_eq!(
+            expr.validate_typed(&identifiers, &functions)
+                .map(|typed| typed.ty),
+            Ok(ty),
+            "{filter}"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use odata_params::filters::{
+    parse_str, to_query_string, walk_expr, CompareOperator, Expr, Fold, LambdaOperator, Value,
+    Visitor, VisitorMut,
+};
+
+/// Records the variant of every node in visiting order.
+#[derive(Default)]
+struct Trace(Vec<&'static str>);
+
+impl Visitor<'_> for Trace {
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.0.push(match expr {
+            Expr::Or(..) => "or",
+            Expr::And(..) => "and",
+            Expr::Not(..) => "not",
+            Expr::Compare(..) => "compare",
+            Expr::In(..) => "in",
+            Expr::Function(..) => "function",
+            Expr::Lambda(..) => "lambda",
+            Expr::Identifier(..) => "identifier",
+            Expr::Alias(..) => "alias",
+            Expr::Value(..) => "value",
+        });
+
+        walk_expr(self, expr);
+    }
+}
+
+#[test]
+fn visitor_reaches_every_node() {
+    let expr = parse_str(
+        "(not (name in ('Bob', @other))) or tags/any(tag: contains(tag, 'vip')) and age gt 3",
+    )
+    .expect("valid filter tree");
+
+    let mut trace = Trace::default();
+    trace.visit_expr(&expr);
+
+    assert_eq!(
+        trace.0,
+        [
+            "or",
+            "not",
+            "in",
+            "identifier",
+            "value",
+            "alias",
+            "and",
+            "lambda",
+            "identifier",
+            "function",
+            "identifier",
+            "value",
+            "compare",
+            "identifier",
+            "value",
+        ]
+    );
+}
+
+#[test]
+fn visitor_override_can_skip_children() {
+    /// Counts comparisons outside of lambdas.
+    #[derive(Default)]
+    struct TopLevel(usize);
+
+    impl Visitor<'_> for TopLevel {
+        fn visit_compare(&mut self, _lhs: &Expr, _op: &CompareOperator, _rhs: &Expr) {
+            self.0 += 1;
+        }
+
+        fn visit_lambda(&mut self, _: &Expr, _: &LambdaOperator, _: &str, _: &Expr) {}
+    }
+
+    let expr = parse_str("age gt 3 and items/all(item: item gt 1 and item lt 9) or age lt 99")
+        .expect("valid filter tree");
+
+    let mut counter = TopLevel::default();
+    counter.visit_expr(&expr);
+
+    assert_eq!(counter.0, 2);
+}
+
+#[test]
+fn visitor_mut_rewrites_in_place() {
+    struct Redact;
+
+    impl VisitorMut for Redact {
+        fn visit_value_mut(&mut self, value: &mut Value) {
+            if let Value::String(string) = value {
+                *string = "*".repeat(string.len());
+            }
+        }
+
+        fn visit_compare_mut(&mut self, lhs: &mut Expr, op: &mut CompareOperator, rhs: &mut Expr) {
+            if *op == CompareOperator::Equal {
+                *op = CompareOperator::NotEqual;
+            }
+
+            self.visit_expr_mut(lhs);
+            self.visit_expr_mut(rhs);
+        }
+    }
+
+    let mut expr = parse_str("name eq 'Bob' and remarks/any(remark: contains(remark, 'secret'))")
+        .expect("valid filter tree");
+
+    Redact.visit_expr_mut(&mut expr);
+
+    assert_eq!(
+        to_query_string(&expr).expect("valid filter"),
+        "name ne '***' and remarks/any(remark:contains(remark, '******'))"
+    );
+}
+
+#[test]
+fn fold_default_rebuilds_same_tree() {
+    struct Identity;
+
+    impl Fold for Identity {}
+
+    let expr =
+        parse_str("not (name in ('Bob', @other)) or tags/all(tag: length(tag) lt 10) and age gt 3")
+            .expect("valid filter tree");
+
+    assert_eq!(Identity.fold_expr(expr.clone()), expr);
+}
+
+#[test]
+fn fold_replaces_nodes() {
+    /// Expands `x in (a, b)` into `x eq a or x eq b`.
+    struct ExpandIn;
+
+    impl Fold for ExpandIn {
+        fn fold_in(&mut self, lhs: Expr, values: Vec<Expr>) -> Expr {
+            let lhs = self.fold_expr(lhs);
+
+            values
+                .into_iter()
+                .map(|value| {
+                    Expr::Compare(
+                        Box::new(lhs.clone()),
+                        CompareOperator::Equal,
+                        Box::new(self.fold_expr(value)),
+                    )
+                })
+                .reduce(|acc, compare| Expr::Or(Box::new(acc), Box::new(compare)))
+                .unwrap_or(Expr::Value(Value::Bool(false)))
+        }
+    }
+
+    let expr = parse_str("name in ('Bob', 'Alice') and age in ()").expect("valid filter tree");
+
+    assert_eq!(
+        to_query_string(&ExpandIn.fold_expr(expr)).expect("valid filter"),
+        "(name eq 'Bob' or name eq 'Alice') and false"
+    );
+}
+
+#[test]
+fn identifiers_skip_lambda_variables() {
+    let expr = parse_str("orders/any(order: order eq name and items/all(item: item ne order))")
+        .expect("valid filter tree");
+
+    assert_eq!(
+        expr.identifiers().collect::<Vec<_>>(),
+        ["orders", "name", "items"]
+    );
+}
+
+#[test]
+fn functions_and_values() {
+    let expr = parse_str(
+        "startswith(tolower(name), 'b') and length(name) gt 2 and code in (1, 2) and flag eq null",
+    )
+    .expect("valid filter tree");
+
+    assert_eq!(
+        expr.functions().collect::<Vec<_>>(),
+        ["startswith", "tolower", "length"]
+    );
+
+    let values = expr.values().collect::<Vec<_>>();
+
+    assert_eq!(values.len(), 5);
+    assert_eq!(values[0], &Value::String("b".to_owned()));
+    assert_eq!(values[4], &Value::Null);
+}
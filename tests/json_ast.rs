@@ -0,0 +1,185 @@
+#![cfg(feature = "serde")]
+
+use odata_params::filters::{parse_str, Expr, Value};
+use odata_params::json_ast::{JsonAstError, JsonExpr, JsonFilter, VERSION};
+use serde_json::json;
+
+const FIXTURE_FILTER: &str = "name eq 'Bob' and score gt 2.50 and score ge 1 \
+    and id ne da820b39-5ad2-4441-b664-c902dbd377d8 and flags has 4 and kind in ('aa', 'bb') \
+    and startswith(name, @prefix) and tags/any(tag: tag eq null) \
+    and items/all(item: item/active eq true) and created lt 2024-01-02T03:04:05.5+01:00 \
+    and born eq 2000-02-29 and at gt 12:30:00 and not (age lt 18 or age le 20)";
+
+fn fixture() -> serde_json::Value {
+    serde_json::from_str(include_str!("fixtures/json_ast/v1.json")).unwrap()
+}
+
+fn to_json(filter: &str) -> serde_json::Value {
+    let expr = parse_str(filter).expect("valid filter tree");
+    serde_json::to_value(JsonExpr::from(&expr)).unwrap()
+}
+
+fn from_json(json: serde_json::Value) -> Result<Expr, JsonAstError> {
+    Expr::try_from(serde_json::from_value::<JsonExpr>(json).unwrap())
+}
+
+#[test]
+fn pinned_format() {
+    let expr = parse_str(FIXTURE_FILTER).expect("valid filter tree");
+
+    assert_eq!(
+        serde_json::to_value(JsonFilter::new(&expr)).unwrap(),
+        fixture()
+    );
+    assert_eq!(
+        serde_json::from_value::<JsonFilter>(fixture())
+            .unwrap()
+            .into_expr(),
+        Ok(expr)
+    );
+}
+
+#[test]
+fn nodes() {
+    assert_eq!(
+        to_json("not (age lt 18)"),
+        json!({
+            "op": "not",
+            "operand": {
+                "op": "lt",
+                "left": { "op": "identifier", "name": "age" },
+                "right": { "op": "number", "value": "18" },
+            },
+        })
+    );
+    assert_eq!(
+        to_json("id in (1, @p1)"),
+        json!({
+            "op": "in",
+            "left": { "op": "identifier", "name": "id" },
+            "values": [
+                { "op": "number", "value": "1" },
+                { "op": "alias", "name": "@p1" },
+            ],
+        })
+    );
+    assert_eq!(
+        to_json("tags/any(tag: contains(tag, 'vip'))"),
+        json!({
+            "op": "any",
+            "collection": { "op": "identifier", "name": "tags" },
+            "variable": "tag",
+            "body": {
+                "op": "call",
+                "name": "contains",
+                "args": [
+                    { "op": "identifier", "name": "tag" },
+                    { "op": "string", "value": "vip" },
+                ],
+            },
+        })
+    );
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        to_json("created eq 2024-01-02T03:04:05.123456789-05:00")["right"],
+        json!({ "op": "datetime", "value": "2024-01-02T08:04:05.123456789Z" })
+    );
+    assert_eq!(
+        to_json("at eq 12:30:00.250")["right"],
+        json!({ "op": "time", "value": "12:30:00.250" })
+    );
+    assert_eq!(
+        to_json("price eq 12345678901234567890.000000000001")["right"],
+        json!({ "op": "number", "value": "12345678901234567890.000000000001" })
+    );
+    assert_eq!(
+        to_json("active eq false")["right"],
+        json!({ "op": "boolean", "value": false })
+    );
+    assert_eq!(to_json("owner eq null")["right"], json!({ "op": "null" }));
+}
+
+#[test]
+fn reads_other_literal_spellings() {
+    assert_eq!(
+        from_json(json!({ "op": "datetime", "value": "2024-01-02T04:04:05+01:00" })),
+        Ok(Expr::Value(Value::DateTime(
+            "2024-01-02T03:04:05Z".parse().unwrap()
+        )))
+    );
+    assert_eq!(
+        from_json(json!({ "op": "number", "value": "-1.5e3" })),
+        Ok(Expr::Value(Value::Number("-1500".parse().unwrap())))
+    );
+}
+
+#[test]
+fn invalid_literals() {
+    for (op, value) in [
+        ("number", "twelve"),
+        ("uuid", "da820b39"),
+        ("datetime", "2024-01-02"),
+        ("date", "2024-02-30"),
+        ("time", "25:00:00"),
+    ] {
+        assert_eq!(
+            from_json(json!({
+                "op": "eq",
+                "left": { "op": "identifier", "name": "field" },
+                "right": { "op": op, "value": value },
+            })),
+            Err(JsonAstError::InvalidLiteral {
+                op,
+                value: value.to_string()
+            })
+        );
+    }
+}
+
+#[test]
+fn versions() {
+    assert_eq!(VERSION, 1);
+
+    let mut document = fixture();
+    document["version"] = json!(2);
+
+    assert_eq!(
+        serde_json::from_value::<JsonFilter>(document)
+            .unwrap()
+            .into_expr(),
+        Err(JsonAstError::UnsupportedVersion { version: 2 })
+    );
+    assert!(serde_json::from_value::<JsonFilter>(json!({ "expr": { "op": "null" } })).is_err());
+}
+
+#[test]
+fn rejects_unknown_members() {
+    assert!(serde_json::from_value::<JsonExpr>(json!({ "op": "like", "name": "x" })).is_err());
+    assert!(serde_json::from_value::<JsonExpr>(json!({
+        "op": "identifier",
+        "name": "age",
+        "type": "number",
+    }))
+    .is_err());
+    assert!(serde_json::from_value::<JsonFilter>(json!({
+        "version": 1,
+        "expr": { "op": "null" },
+        "meta": {},
+    }))
+    .is_err());
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn pinned_schema() {
+    let schema: serde_json::Value =
+        serde_json::from_str(include_str!("../schema/json-ast-v1.schema.json")).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(odata_params::json_ast::json_schema()).unwrap(),
+        schema
+    );
+}
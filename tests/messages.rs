@@ -0,0 +1,297 @@
+use odata_params::filters::{
+    parse_str, CompareOperator, LambdaOperator, ParseError, PolicyViolation, Type, ValidationError,
+};
+use odata_params::messages::{Localize, MessageCatalog};
+use odata_params::query::{QueryError, QueryOptions};
+
+fn french() -> MessageCatalog {
+    MessageCatalog::new().with_messages(
+        "fr",
+        [
+            ("ParseError.Parsing", "Erreur de syntaxe."),
+            (
+                "ParseError.InputTooLong",
+                "Le filtre fait {length} octets, au-delà du maximum de {max}.",
+            ),
+            (
+                "ValidationError.ComparingIncompatibleTypes",
+                "Types incompatibles : {lhs} et {rhs}.",
+            ),
+            (
+                "ValidationError.IncorrectFunctionArgumentsCount",
+                "La fonction '{name}' attend {expected} arguments, {given} donnés.",
+            ),
+            (
+                "ValidationError.IncorrectFunctionArgumentsCountVariadic",
+                "La fonction '{name}' attend au moins {expected} arguments, {given} donnés.",
+            ),
+            (
+                "PolicyViolation.OperatorNotAllowedOn",
+                "L'opérateur '{operator}' est interdit sur '{identifier}'.",
+            ),
+            (
+                "PolicyViolation.FunctionNotAllowed",
+                "La fonction '{name}' est interdite.",
+            ),
+            ("QueryError.Filter", "$filter invalide : {error}"),
+            (
+                "QueryError.FilterNotAllowed",
+                "Expression $filter refusée : {violations}",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn english_by_default() {
+    let name = || "concat".to_string();
+    let errors: Vec<Box<dyn Localize>> = vec![
+        Box::new(ParseError::Parsing { position: 4 }),
+        Box::new(ParseError::ParsingUuid),
+        Box::new(ParseError::ParsingNumber),
+        Box::new(ParseError::ParsingDate),
+        Box::new(ParseError::ParsingTime),
+        Box::new(ParseError::ParsingDateTime),
+        Box::new(ParseError::ParsingTimeZone),
+        Box::new(ParseError::ParsingTimeZoneNamed),
+        Box::new(ParseError::ParsingUnicodeCodePoint),
+        Box::new(ParseError::InputTooLong { length: 10, max: 5 }),
+        Box::new(ParseError::NestingTooDeep { max: 5 }),
+        Box::new(ParseError::TooManyNodes { max: 5 }),
+        Box::new(ParseError::InListTooLarge { max: 5 }),
+        Box::new(ParseError::LambdaNestingTooDeep { max: 5 }),
+        Box::new(ParseError::StringLiteralTooLong { max: 5 }),
+        Box::new(ValidationError::LogicalJoinRequiresBooleans {
+            lhs: Type::Number,
+            rhs: Type::Boolean,
+        }),
+        Box::new(ValidationError::LogicalNotRequiresBoolean {
+            given: Type::String,
+        }),
+        Box::new(ValidationError::ComparingIncompatibleTypes {
+            lhs: Type::Number,
+            rhs: Type::String,
+        }),
+        Box::new(ValidationError::UndefinedIdentifier { name: name() }),
+        Box::new(ValidationError::UndefinedFunction { name: name() }),
+        Box::new(ValidationError::IncorrectFunctionArgumentsCount {
+            name: name(),
+            is_variadic: false,
+            expected: 2,
+            given: 1,
+        }),
+        Box::new(ValidationError::IncorrectFunctionArgumentsCount {
+            name: name(),
+            is_variadic: true,
+            expected: 2,
+            given: 1,
+        }),
+        Box::new(ValidationError::IncorrectFunctionArgumentType {
+            name: name(),
+            position: 1,
+            expected: Type::String,
+            given: Type::Number,
+        }),
+        Box::new(PolicyViolation::OperatorNotAllowed {
+            operator: CompareOperator::Has,
+        }),
+        Box::new(PolicyViolation::OperatorNotAllowedOn {
+            identifier: name(),
+            operator: CompareOperator::Has,
+        }),
+        Box::new(PolicyViolation::InNotAllowed),
+        Box::new(PolicyViolation::InNotAllowedOn { identifier: name() }),
+        Box::new(PolicyViolation::FunctionNotAllowed { name: name() }),
+        Box::new(PolicyViolation::LambdaNotAllowed {
+            operator: LambdaOperator::All,
+        }),
+        Box::new(PolicyViolation::IdentifierNotFilterable { identifier: name() }),
+        Box::new(QueryOptions::parse("$filter=name eq").unwrap_err()),
+        Box::new(QueryOptions::parse("$orderby=name up").unwrap_err()),
+        Box::new(QueryOptions::parse("$top=x").unwrap_err()),
+        Box::new(QueryOptions::parse("$skip=x").unwrap_err()),
+        Box::new(QueryOptions::parse("$count=x").unwrap_err()),
+        Box::new(QueryOptions::parse("$select=1").unwrap_err()),
+        Box::new(QueryOptions::parse("$top=1&$top=1").unwrap_err()),
+        Box::new(QueryOptions::parse("$search=NOT").unwrap_err()),
+        Box::new(QueryOptions::parse("$expands=x").unwrap_err()),
+        Box::new(QueryError::Validation {
+            option: "$filter".to_string(),
+            error: ValidationError::UndefinedFunction { name: name() },
+        }),
+        Box::new(QueryError::FilterNotBoolean {
+            given: Type::String,
+        }),
+        Box::new(QueryError::FilterNotAllowed {
+            violations: vec![
+                PolicyViolation::InNotAllowed,
+                PolicyViolation::FunctionNotAllowed { name: name() },
+            ],
+        }),
+    ];
+
+    for error in errors {
+        assert_eq!(
+            MessageCatalog::new().render(error.as_ref(), "en"),
+            error.to_string()
+        );
+        assert_eq!(french().render(error.as_ref(), "de"), error.to_string());
+    }
+}
+
+#[test]
+fn interpolates_fields() {
+    let catalog = french();
+
+    assert_eq!(
+        catalog.render(&ParseError::InputTooLong { length: 10, max: 5 }, "fr"),
+        "Le filtre fait 10 octets, au-delà du maximum de 5."
+    );
+    assert_eq!(
+        catalog.render(
+            &ValidationError::ComparingIncompatibleTypes {
+                lhs: Type::Number,
+                rhs: Type::String,
+            },
+            "fr"
+        ),
+        "Types incompatibles : Number et String."
+    );
+    assert_eq!(
+        catalog.render(
+            &PolicyViolation::OperatorNotAllowedOn {
+                identifier: "id".to_string(),
+                operator: CompareOperator::GreaterThan,
+            },
+            "fr"
+        ),
+        "L'opérateur 'gt' est interdit sur 'id'."
+    );
+}
+
+#[test]
+fn variadic_key() {
+    let catalog = french();
+    let error = |is_variadic| ValidationError::IncorrectFunctionArgumentsCount {
+        name: "concat".to_string(),
+        is_variadic,
+        expected: 2,
+        given: 1,
+    };
+
+    assert_eq!(
+        catalog.render(&error(false), "fr"),
+        "La fonction 'concat' attend 2 arguments, 1 donnés."
+    );
+    assert_eq!(
+        catalog.render(&error(true), "fr"),
+        "La fonction 'concat' attend au moins 2 arguments, 1 donnés."
+    );
+}
+
+#[test]
+fn renders_nested_errors() {
+    let catalog = french();
+
+    assert_eq!(
+        catalog.render(&QueryOptions::parse("$filter=name eq").unwrap_err(), "fr"),
+        "$filter invalide : Erreur de syntaxe."
+    );
+    assert_eq!(
+        catalog.render(
+            &QueryError::FilterNotAllowed {
+                violations: vec![
+                    PolicyViolation::FunctionNotAllowed {
+                        name: "contains".to_string(),
+                    },
+                    PolicyViolation::InNotAllowed,
+                ],
+            },
+            "fr"
+        ),
+        "Expression $filter refusée : La fonction 'contains' est interdite. The 'in' operator is not allowed."
+    );
+    assert_eq!(
+        catalog.render(&QueryOptions::parse("$orderby=name up").unwrap_err(), "fr"),
+        "Invalid $orderby: Erreur de syntaxe."
+    );
+    assert_eq!(
+        catalog.render(
+            &QueryError::Validation {
+                option: "$filter".to_string(),
+                error: ValidationError::ComparingIncompatibleTypes {
+                    lhs: Type::Number,
+                    rhs: Type::String,
+                },
+            },
+            "fr-CA"
+        ),
+        "Invalid $filter: Types incompatibles : Number et String."
+    );
+}
+
+#[test]
+fn locale_fallback() {
+    let catalog = french().with_message("fr-CA", "ParseError.Parsing", "Erreur de syntaxe, là.");
+    let error = parse_str("name eq").unwrap_err();
+
+    assert_eq!(catalog.render(&error, "fr-CA"), "Erreur de syntaxe, là.");
+    assert_eq!(catalog.render(&error, "fr-FR"), "Erreur de syntaxe.");
+    assert_eq!(catalog.render(&error, "fr_BE"), "Erreur de syntaxe.");
+    assert_eq!(
+        catalog.render(&error, "ja"),
+        "Error during general parsing at position 7."
+    );
+    assert_eq!(
+        catalog.template("fr-CH", "ParseError.Parsing"),
+        Some("Erreur de syntaxe.")
+    );
+    assert_eq!(catalog.template("ja", "ParseError.Parsing"), None);
+}
+
+#[test]
+fn overrides() {
+    let catalog = french()
+        .with_message("fr", "ParseError.Parsing", "Filtre illisible.")
+        .with_message("en", "ParseError.Parsing", "The filter could not be read.");
+    let error = parse_str("name eq").unwrap_err();
+
+    assert_eq!(catalog.render(&error, "fr"), "Filtre illisible.");
+    assert_eq!(
+        catalog.render(&error, "en-GB"),
+        "The filter could not be read."
+    );
+}
+
+#[test]
+fn braces() {
+    let catalog = MessageCatalog::new().with_messages(
+        "ja",
+        [
+            (
+                "ValidationError.UndefinedIdentifier",
+                "{{{name}}} は未定義の識別子です。{unknown} }",
+            ),
+            ("ValidationError.UndefinedFunction", "関数 {name"),
+        ],
+    );
+
+    assert_eq!(
+        catalog.render(
+            &ValidationError::UndefinedIdentifier {
+                name: "height".to_string()
+            },
+            "ja"
+        ),
+        "{height} は未定義の識別子です。{unknown} }"
+    );
+    assert_eq!(
+        catalog.render(
+            &ValidationError::UndefinedFunction {
+                name: "soundex".to_string()
+            },
+            "ja"
+        ),
+        "関数 {name"
+    );
+}
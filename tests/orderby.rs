@@ -0,0 +1,88 @@
+use odata_params::filters::{Expr, ParseError, ParseOptions, Value};
+use odata_params::orderby::{parse_str, parse_str_with, to_query_string, Direction, OrderBy};
+
+fn identifier(name: &str) -> Expr {
+    Expr::Identifier(name.to_owned())
+}
+
+#[test]
+fn directions() {
+    assert_eq!(
+        parse_str("name, age desc, createdAt asc"),
+        Ok(vec![
+            OrderBy {
+                expr: identifier("name"),
+                direction: Direction::Ascending,
+            },
+            OrderBy {
+                expr: identifier("age"),
+                direction: Direction::Descending,
+            },
+            OrderBy {
+                expr: identifier("createdAt"),
+                direction: Direction::Ascending,
+            },
+        ])
+    );
+}
+
+#[test]
+fn expressions() {
+    assert_eq!(
+        parse_str("owner/name desc,length(title)"),
+        Ok(vec![
+            OrderBy {
+                expr: identifier("owner/name"),
+                direction: Direction::Descending,
+            },
+            OrderBy {
+                expr: Expr::Function("length".to_owned(), vec![identifier("title")]),
+                direction: Direction::Ascending,
+            },
+        ])
+    );
+
+    let items = parse_str("price gt 10 desc").expect("valid order by");
+    assert_eq!(items[0].direction, Direction::Descending);
+    assert!(matches!(items[0].expr, Expr::Compare(..)));
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(parse_str(""), Err(ParseError::Parsing { position: 0 }));
+    assert_eq!(parse_str("name,"), Err(ParseError::Parsing { position: 5 }));
+    assert_eq!(
+        parse_str("name desc desc"),
+        Err(ParseError::Parsing { position: 10 })
+    );
+}
+
+#[test]
+fn limits() {
+    let options = ParseOptions {
+        max_length: Some(8),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        parse_str_with("name, age desc", &options),
+        Err(ParseError::InputTooLong { length: 14, max: 8 })
+    );
+}
+
+#[test]
+fn round_trip() {
+    for query in ["name", "name desc, age", "concat(first, last) desc, id"] {
+        let items = parse_str(query).expect("valid order by");
+        assert_eq!(to_query_string(&items).expect("valid order by"), query);
+    }
+
+    let items = vec![OrderBy {
+        expr: Expr::Value(Value::Bool(true)),
+        direction: Direction::Descending,
+    }];
+    assert_eq!(
+        to_query_string(&items).expect("valid order by"),
+        "true desc"
+    );
+}
@@ -0,0 +1,260 @@
+use odata_params::filters::{
+    parse_str, CompareOperator, FilterPolicy, FunctionsTypeMap, IdentifiersTypeMap, ParseError,
+    ParseOptions, PolicyViolation, Type, ValidationError,
+};
+use odata_params::orderby;
+use odata_params::query::{QueryError, QueryOptions, QuerySchema};
+use odata_params::search;
+use std::collections::HashMap;
+
+fn schema() -> QuerySchema {
+    QuerySchema::new(
+        IdentifiersTypeMap::from(HashMap::from([
+            ("name".to_string(), Type::String),
+            ("age".to_string(), Type::Number),
+            ("owner/name".to_string(), Type::String),
+        ])),
+        FunctionsTypeMap::from(HashMap::from([(
+            "tolower".to_string(),
+            (vec![Type::String], None, Type::String),
+        )])),
+    )
+}
+
+#[test]
+fn all_options() {
+    let options = QueryOptions::parse(
+        "$filter=name%20eq%20'J%26J'&$orderby=age desc,name&$top=5&$skip=10&$select=name, owner/name&$count=false&$search=blue%20OR%20green",
+    )
+    .expect("valid query options");
+
+    assert_eq!(
+        options,
+        QueryOptions {
+            filter: Some(parse_str("name eq 'J&J'").expect("valid filter tree")),
+            order_by: orderby::parse_str("age desc, name").expect("valid order by"),
+            top: Some(5),
+            skip: Some(10),
+            select: vec!["name".to_string(), "owner/name".to_string()],
+            count: false,
+            search: Some(search::parse_str("blue OR green").expect("valid search")),
+            unhandled: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn unhandled_options() {
+    let options = QueryOptions::parse("$expand=orders($top=2)&$top=1&$format=json")
+        .expect("valid query options");
+
+    assert_eq!(options.top, Some(1));
+    assert_eq!(
+        options.unhandled,
+        [
+            ("$expand".to_string(), "orders($top=2)".to_string()),
+            ("$format".to_string(), "json".to_string()),
+        ]
+    );
+    assert_eq!(
+        QueryOptions::parse("$expand=orders&$expand=items"),
+        Err(QueryError::DuplicateOption {
+            name: "$expand".to_string()
+        })
+    );
+}
+
+#[test]
+fn defaults_and_custom_options() {
+    assert_eq!(QueryOptions::parse(""), Ok(QueryOptions::default()));
+    assert_eq!(
+        QueryOptions::parse("apiVersion=2&@p1=5&$top=1"),
+        Ok(QueryOptions {
+            top: Some(1),
+            ..QueryOptions::default()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$select=*&$count=true"),
+        Ok(QueryOptions {
+            select: vec!["*".to_string()],
+            count: true,
+            ..QueryOptions::default()
+        })
+    );
+}
+
+#[test]
+fn invalid_values() {
+    assert_eq!(
+        QueryOptions::parse("$filter=name eq"),
+        Err(QueryError::Filter {
+            error: ParseError::Parsing { position: 7 }
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$orderby=name sideways"),
+        Err(QueryError::OrderBy {
+            error: ParseError::Parsing { position: 5 }
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$search=blue%20AND"),
+        Err(QueryError::Search {
+            error: ParseError::Parsing { position: 8 }
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$top=-1"),
+        Err(QueryError::InvalidTop {
+            value: "-1".to_string()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$skip=%2B3"),
+        Err(QueryError::InvalidSkip {
+            value: "+3".to_string()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$count=yes"),
+        Err(QueryError::InvalidCount {
+            value: "yes".to_string()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$select=name,,age"),
+        Err(QueryError::InvalidSelect {
+            value: String::new()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$select=name;drop"),
+        Err(QueryError::InvalidSelect {
+            value: "name;drop".to_string()
+        })
+    );
+}
+
+#[test]
+fn duplicate_and_unsupported_options() {
+    assert_eq!(
+        QueryOptions::parse("$top=1&$top=2"),
+        Err(QueryError::DuplicateOption {
+            name: "$top".to_string()
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$expands=orders"),
+        Err(QueryError::UnsupportedOption {
+            name: "$expands".to_string()
+        })
+    );
+}
+
+#[test]
+fn limits() {
+    let limits = ParseOptions {
+        max_in_list: Some(2),
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        QueryOptions::parse_with("$filter=age in (1, 2, 3)", &limits),
+        Err(QueryError::Filter {
+            error: ParseError::InListTooLarge { max: 2 }
+        })
+    );
+}
+
+#[test]
+fn validation() {
+    let schema = schema();
+    let validate = |query: &str| {
+        QueryOptions::parse(query)
+            .expect("valid query options")
+            .validate(&schema)
+    };
+
+    assert_eq!(
+        validate("$filter=tolower(owner/name) eq 'bob'&$orderby=age&$select=*,name"),
+        Ok(())
+    );
+    assert_eq!(
+        validate("$filter=height gt 3"),
+        Err(QueryError::Validation {
+            option: "$filter".to_string(),
+            error: ValidationError::UndefinedIdentifier {
+                name: "height".to_string()
+            },
+        })
+    );
+    assert_eq!(
+        validate("$filter=tolower(name)"),
+        Err(QueryError::FilterNotBoolean {
+            given: Type::String
+        })
+    );
+    assert_eq!(
+        validate("$orderby=length(name)"),
+        Err(QueryError::Validation {
+            option: "$orderby".to_string(),
+            error: ValidationError::UndefinedFunction {
+                name: "length".to_string()
+            },
+        })
+    );
+}
+
+#[test]
+fn codes_and_targets() {
+    let error = QueryError::Validation {
+        option: "$select".to_string(),
+        error: ValidationError::UndefinedIdentifier {
+            name: "email".to_string(),
+        },
+    };
+
+    assert_eq!(error.code(), "ValidationFailed");
+    assert_eq!(error.target(), "$select");
+    assert_eq!(
+        error.to_string(),
+        "Invalid $select: Undefined identifier 'email'."
+    );
+
+    let error = QueryError::UnsupportedOption {
+        name: "$levels".to_string(),
+    };
+
+    assert_eq!(error.code(), "UnsupportedQueryOption");
+    assert_eq!(error.target(), "$levels");
+
+    let error = QueryOptions::parse("$search=NOT").unwrap_err();
+
+    assert_eq!(error.code(), "InvalidSearch");
+    assert_eq!(error.target(), "$search");
+}
+
+#[test]
+fn policy() {
+    let schema = schema().with_policy(FilterPolicy {
+        operators: Some([CompareOperator::Equal].into()),
+        ..FilterPolicy::default()
+    });
+    let options = QueryOptions::parse("$filter=age gt 3 and name eq 'Bob'").expect("valid options");
+
+    assert_eq!(
+        options.validate(&schema),
+        Err(QueryError::FilterNotAllowed {
+            violations: vec![PolicyViolation::OperatorNotAllowed {
+                operator: CompareOperator::GreaterThan
+            }]
+        })
+    );
+    assert_eq!(
+        QueryOptions::parse("$filter=name eq 'Bob'")
+            .expect("valid options")
+            .validate(&schema),
+        Ok(())
+    );
+}
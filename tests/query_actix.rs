@@ -0,0 +1,203 @@
+#![cfg(feature = "actix")]
+
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use odata_params::filters::{
+    parse_str, FilterPolicy, FunctionsTypeMap, IdentifiersTypeMap, ParseError, Type,
+    ValidationError,
+};
+use odata_params::query::{QueryOptions, QuerySchema};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+async fn list(options: QueryOptions) -> String {
+    format!(
+        "{:?} {:?} {}",
+        options.top,
+        options.skip,
+        options.filter.is_some()
+    )
+}
+
+async fn parse(query: web::Query<HashMap<String, String>>) -> Result<String, ParseError> {
+    parse_str(&query["filter"]).map(|_| "parsed".to_string())
+}
+
+async fn validate(query: web::Query<HashMap<String, String>>) -> Result<String, ValidationError> {
+    let expr = parse_str(&query["filter"]).expect("valid filter tree");
+    let given = expr.validate(
+        &IdentifiersTypeMap::from(HashMap::new()),
+        &FunctionsTypeMap::from(HashMap::new()),
+    )?;
+
+    Ok(format!("{given:?}"))
+}
+
+fn schema() -> QuerySchema {
+    QuerySchema::new(
+        IdentifiersTypeMap::from(HashMap::from([
+            ("name".to_string(), Type::String),
+            ("age".to_string(), Type::Number),
+        ])),
+        FunctionsTypeMap::from(HashMap::from([(
+            "contains".to_string(),
+            (vec![Type::String, Type::String], None, Type::Boolean),
+        )])),
+    )
+}
+
+macro_rules! app {
+    () => {
+        test::init_service(
+            App::new()
+                .service(
+                    web::resource("/users")
+                        .app_data(web::Data::new(schema()))
+                        .route(web::get().to(list)),
+                )
+                .service(
+                    web::resource("/restricted")
+                        .app_data(web::Data::new(schema().with_policy(FilterPolicy {
+                            functions: Some(Default::default()),
+                            ..FilterPolicy::default()
+                        })))
+                        .route(web::get().to(list)),
+                )
+                .route("/unvalidated", web::get().to(list))
+                .route("/parse", web::get().to(parse))
+                .route("/validate", web::get().to(validate)),
+        )
+        .await
+    };
+}
+
+macro_rules! get {
+    ($app:expr, $uri:expr) => {
+        test::call_service(&$app, test::TestRequest::get().uri($uri).to_request()).await
+    };
+}
+
+#[actix_rt::test]
+async fn extracts_options() {
+    let app = app!();
+
+    let response = get!(app, "/users?$top=5&$skip=2&$filter=age%20gt%2030");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(test::read_body(response).await, "Some(5) Some(2) true");
+
+    let response = get!(app, "/unvalidated?$filter=anything%20eq%201");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(test::read_body(response).await, "None None true");
+}
+
+#[actix_rt::test]
+async fn rejects_invalid_options() {
+    let app = app!();
+
+    let response = get!(app, "/unvalidated?$top=ten");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(response.headers().get("odata-version").unwrap(), "4.0");
+    assert_eq!(
+        test::read_body_json::<Value, _>(response).await,
+        json!({
+            "error": {
+                "code": "InvalidTop",
+                "message": "Invalid $top 'ten', expected a non-negative integer.",
+                "target": "$top",
+            }
+        })
+    );
+}
+
+#[actix_rt::test]
+async fn validates_against_route_schema() {
+    let app = app!();
+
+    let response = get!(app, "/users?$orderby=height");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::read_body_json::<Value, _>(response).await,
+        json!({
+            "error": {
+                "code": "ValidationFailed",
+                "message": "Invalid $orderby: Undefined identifier 'height'.",
+                "target": "$orderby",
+                "details": [{
+                    "code": "UndefinedIdentifier",
+                    "message": "Undefined identifier 'height'.",
+                    "target": "height",
+                }],
+            }
+        })
+    );
+
+    let response = get!(app, "/unvalidated?$orderby=height");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn applies_route_policy() {
+    let app = app!();
+
+    let response = get!(app, "/users?$filter=contains(name,%20'J')");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = get!(app, "/restricted?$filter=contains(name,%20'J')");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::read_body_json::<Value, _>(response).await,
+        json!({
+            "error": {
+                "code": "FilterNotAllowed",
+                "message": "The $filter expression is not allowed: The function 'contains' is not allowed.",
+                "target": "$filter",
+                "details": [{
+                    "code": "FunctionNotAllowed",
+                    "message": "The function 'contains' is not allowed.",
+                    "target": "contains",
+                }],
+            }
+        })
+    );
+}
+
+#[actix_rt::test]
+async fn parse_error_response() {
+    let app = app!();
+
+    let response = get!(app, "/parse?filter=age%20gt");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers().get("odata-version").unwrap(), "4.0");
+    assert_eq!(
+        test::read_body_json::<Value, _>(response).await,
+        json!({
+            "error": {
+                "code": "SyntaxError",
+                "message": "Error during general parsing at position 6.",
+                "target": "6",
+            }
+        })
+    );
+}
+
+#[actix_rt::test]
+async fn validation_error_response() {
+    let app = app!();
+
+    let response = get!(app, "/validate?filter=height%20gt%203");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        test::read_body_json::<Value, _>(response).await,
+        json!({
+            "error": {
+                "code": "UndefinedIdentifier",
+                "message": "Undefined identifier 'height'.",
+                "target": "height",
+            }
+        })
+    );
+}
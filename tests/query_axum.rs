@@ -0,0 +1,152 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use http_body_util::BodyExt;
+use odata_params::filters::{FunctionsTypeMap, IdentifiersTypeMap, Type};
+use odata_params::query::axum::{ODataQuery, ValidatedODataQuery};
+use odata_params::query::QuerySchema;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+async fn unvalidated(ODataQuery(options): ODataQuery) -> String {
+    format!(
+        "{:?} {:?} {}",
+        options.top,
+        options.skip,
+        options.filter.is_some()
+    )
+}
+
+async fn validated(ValidatedODataQuery(options): ValidatedODataQuery) -> String {
+    format!("{} items", options.order_by.len())
+}
+
+fn app() -> Router {
+    let schema = QuerySchema::new(
+        IdentifiersTypeMap::from(HashMap::from([
+            ("name".to_string(), Type::String),
+            ("age".to_string(), Type::Number),
+        ])),
+        FunctionsTypeMap::from(HashMap::new()),
+    );
+
+    Router::new()
+        .route("/unvalidated", get(unvalidated))
+        .route("/validated", get(validated))
+        .with_state(Arc::new(schema))
+}
+
+async fn get_response(uri: &str) -> (StatusCode, Option<String>, String) {
+    let response = app()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|value| value.to_str().unwrap().to_owned());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn extracts_options() {
+    let (status, _, body) =
+        get_response("/unvalidated?$top=5&$skip=2&$filter=whatever%20eq%201").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "Some(5) Some(2) true");
+
+    let (status, _, body) = get_response("/unvalidated").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "None None false");
+}
+
+#[tokio::test]
+async fn validates_against_state() {
+    let (status, _, body) =
+        get_response("/validated?$filter=age%20gt%2030&$orderby=name,age%20desc").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "2 items");
+}
+
+#[tokio::test]
+async fn rejects_syntax_errors() {
+    let (status, content_type, body) = get_response("/unvalidated?$filter=age%20gt").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({
+            "error": {
+                "code": "InvalidFilter",
+                "message": "Invalid $filter: Error during general parsing at position 6.",
+                "target": "$filter",
+                "details": [{
+                    "code": "SyntaxError",
+                    "message": "Error during general parsing at position 6.",
+                    "target": "6",
+                }],
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn rejects_invalid_options() {
+    let (status, _, body) = get_response("/unvalidated?$top=ten").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap()["error"]["code"],
+        "InvalidTop"
+    );
+}
+
+#[tokio::test]
+async fn rejects_validation_errors() {
+    let (status, _, body) = get_response("/validated?$filter=height%20gt%203").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({
+            "error": {
+                "code": "ValidationFailed",
+                "message": "Invalid $filter: Undefined identifier 'height'.",
+                "target": "$filter",
+                "details": [{
+                    "code": "UndefinedIdentifier",
+                    "message": "Undefined identifier 'height'.",
+                    "target": "height",
+                }],
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn applies_untrusted_limits() {
+    let filter = format!("{}age%20gt%201{}", "(".repeat(40), ")".repeat(40));
+    let (status, _, body) = get_response(&format!("/unvalidated?$filter={filter}")).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&body).unwrap()["error"]["message"],
+        "Invalid $filter: Filter exceeds the maximum nesting depth of 32."
+    );
+}
@@ -0,0 +1,224 @@
+use odata_params::filters::{
+    parse_str, CompareOperator, ParseError, PolicyViolation, Type, ValidationError,
+};
+use odata_params::query::{ODataError, ODataErrorDetail, QueryError, QueryOptions};
+
+fn detail(code: &str, message: &str, target: Option<&str>) -> ODataErrorDetail {
+    ODataErrorDetail {
+        code: code.to_string(),
+        message: message.to_string(),
+        target: target.map(str::to_string),
+    }
+}
+
+#[test]
+fn parse_error_codes() {
+    assert_eq!(parse_str("name eq").unwrap_err().code(), "SyntaxError");
+    assert_eq!(
+        parse_str("id eq 2024-13-01").unwrap_err().code(),
+        "InvalidDate"
+    );
+    assert_eq!(
+        ParseError::ParsingTimeZoneNamed.code(),
+        "InvalidTimeZoneName"
+    );
+    assert_eq!(
+        ParseError::NestingTooDeep { max: 8 }.code(),
+        "NestingTooDeep"
+    );
+    assert_eq!(ParseError::Parsing { position: 3 }.target(), None);
+    assert_eq!(ParseError::Parsing { position: 3 }.position(), Some(3));
+    assert_eq!(ParseError::ParsingUuid.position(), None);
+}
+
+#[test]
+fn validation_error_codes_and_targets() {
+    let errors = [
+        (
+            ValidationError::LogicalJoinRequiresBooleans {
+                lhs: Type::Number,
+                rhs: Type::Boolean,
+            },
+            "LogicalJoinRequiresBooleans",
+            None,
+        ),
+        (
+            ValidationError::ComparingIncompatibleTypes {
+                lhs: Type::Number,
+                rhs: Type::String,
+            },
+            "IncompatibleTypes",
+            None,
+        ),
+        (
+            ValidationError::UndefinedIdentifier {
+                name: "height".to_string(),
+            },
+            "UndefinedIdentifier",
+            Some("height"),
+        ),
+        (
+            ValidationError::IncorrectFunctionArgumentsCount {
+                name: "concat".to_string(),
+                is_variadic: false,
+                expected: 2,
+                given: 1,
+            },
+            "IncorrectArgumentCount",
+            Some("concat"),
+        ),
+        (
+            ValidationError::IncorrectFunctionArgumentType {
+                name: "length".to_string(),
+                position: 0,
+                expected: Type::String,
+                given: Type::Number,
+            },
+            "IncorrectArgumentType",
+            Some("length"),
+        ),
+    ];
+
+    for (error, code, target) in errors {
+        assert_eq!(error.code(), code);
+        assert_eq!(error.target(), target);
+    }
+}
+
+#[test]
+fn policy_violation_codes_and_targets() {
+    let violation = PolicyViolation::OperatorNotAllowedOn {
+        identifier: "id".to_string(),
+        operator: CompareOperator::GreaterThan,
+    };
+
+    assert_eq!(violation.code(), "OperatorNotAllowed");
+    assert_eq!(violation.target(), Some("id"));
+    assert_eq!(
+        PolicyViolation::OperatorNotAllowed {
+            operator: CompareOperator::Has
+        }
+        .target(),
+        None
+    );
+}
+
+#[test]
+fn from_single_error() {
+    assert_eq!(
+        ODataError::from(ParseError::InListTooLarge { max: 2 }),
+        ODataError {
+            code: "InListTooLarge".to_string(),
+            message: "An 'in' list exceeds the maximum of 2 items.".to_string(),
+            target: None,
+            details: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn from_query_error() {
+    let error = QueryOptions::parse("$filter=name eq").unwrap_err();
+
+    assert_eq!(
+        ODataError::from(error),
+        ODataError::new(
+            "InvalidFilter",
+            "Invalid $filter: Error during general parsing at position 7."
+        )
+        .with_target("$filter")
+        .with_details([detail(
+            "SyntaxError",
+            "Error during general parsing at position 7.",
+            Some("7"),
+        )])
+    );
+
+    let error = QueryError::FilterNotAllowed {
+        violations: vec![
+            PolicyViolation::InNotAllowed,
+            PolicyViolation::IdentifierNotFilterable {
+                identifier: "secret".to_string(),
+            },
+        ],
+    };
+
+    assert_eq!(
+        ODataError::from(error).details,
+        [
+            detail("InNotAllowed", "The 'in' operator is not allowed.", None),
+            detail(
+                "IdentifierNotFilterable",
+                "Filtering on 'secret' is not allowed.",
+                Some("secret")
+            ),
+        ]
+    );
+
+    let error = QueryOptions::parse("$top=x").unwrap_err();
+
+    assert_eq!(
+        ODataError::from(error),
+        ODataError::new(
+            "InvalidTop",
+            "Invalid $top 'x', expected a non-negative integer."
+        )
+        .with_target("$top")
+    );
+}
+
+#[test]
+fn from_errors() {
+    let error = ODataError::from_errors([
+        ValidationError::UndefinedIdentifier {
+            name: "height".to_string(),
+        },
+        ValidationError::UndefinedFunction {
+            name: "soundex".to_string(),
+        },
+    ]);
+
+    assert_eq!(
+        error,
+        ODataError::new("MultipleErrors", "2 errors occurred.").with_details([
+            detail(
+                "UndefinedIdentifier",
+                "Undefined identifier 'height'.",
+                Some("height")
+            ),
+            detail(
+                "UndefinedFunction",
+                "Undefined function 'soundex'.",
+                Some("soundex")
+            ),
+        ])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize() {
+    let error = ODataError::from(QueryOptions::parse("$orderby=name sideways").unwrap_err());
+
+    assert_eq!(
+        serde_json::to_value(&error).unwrap(),
+        serde_json::json!({
+            "code": "InvalidOrderBy",
+            "message": "Invalid $orderby: Error during general parsing at position 5.",
+            "target": "$orderby",
+            "details": [{
+                "code": "SyntaxError",
+                "message": "Error during general parsing at position 5.",
+                "target": "5",
+            }],
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<ODataError>(serde_json::json!({
+            "code": "InvalidTop",
+            "message": "Bad $top.",
+        }))
+        .unwrap(),
+        ODataError::new("InvalidTop", "Bad $top.")
+    );
+}
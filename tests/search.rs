@@ -0,0 +1,222 @@
+use odata_params::filters::{ParseError, ParseOptions};
+use odata_params::search::{
+    parse_str, parse_str_with, to_query_string, SearchExpr, TranslateError,
+};
+
+fn term(word: &str) -> SearchExpr {
+    SearchExpr::Term(word.to_owned())
+}
+
+fn phrase(words: &str) -> SearchExpr {
+    SearchExpr::Phrase(words.to_owned())
+}
+
+fn and(lhs: SearchExpr, rhs: SearchExpr) -> SearchExpr {
+    SearchExpr::And(Box::new(lhs), Box::new(rhs))
+}
+
+fn or(lhs: SearchExpr, rhs: SearchExpr) -> SearchExpr {
+    SearchExpr::Or(Box::new(lhs), Box::new(rhs))
+}
+
+fn not(expr: SearchExpr) -> SearchExpr {
+    SearchExpr::Not(Box::new(expr))
+}
+
+#[test]
+fn terms_and_phrases() {
+    assert_eq!(parse_str("blue"), Ok(term("blue")));
+    assert_eq!(parse_str("  café-au-lait  "), Ok(term("café-au-lait")));
+    assert_eq!(parse_str(r#""dark blue""#), Ok(phrase("dark blue")));
+    assert_eq!(
+        parse_str(r#""say \"hi\" \\ bye""#),
+        Ok(phrase(r#"say "hi" \ bye"#))
+    );
+    assert_eq!(parse_str("ANDROID"), Ok(term("ANDROID")));
+    assert_eq!(parse_str("and"), Ok(term("and")));
+}
+
+#[test]
+fn precedence() {
+    assert_eq!(
+        parse_str("a OR b AND c"),
+        Ok(or(term("a"), and(term("b"), term("c"))))
+    );
+    assert_eq!(
+        parse_str("a AND b OR c"),
+        Ok(or(and(term("a"), term("b")), term("c")))
+    );
+    assert_eq!(parse_str("NOT a AND b"), Ok(and(not(term("a")), term("b"))));
+    assert_eq!(
+        parse_str("a OR b OR c"),
+        Ok(or(or(term("a"), term("b")), term("c")))
+    );
+}
+
+#[test]
+fn implicit_and() {
+    assert_eq!(
+        parse_str("blue green\tNOT red"),
+        Ok(and(and(term("blue"), term("green")), not(term("red"))))
+    );
+    assert_eq!(
+        parse_str(r#"blue "dark red" OR green"#),
+        Ok(or(and(term("blue"), phrase("dark red")), term("green")))
+    );
+}
+
+#[test]
+fn grouping() {
+    assert_eq!(
+        parse_str("(a OR b) c"),
+        Ok(and(or(term("a"), term("b")), term("c")))
+    );
+    assert_eq!(
+        parse_str("NOT ( a OR b )"),
+        Ok(not(or(term("a"), term("b"))))
+    );
+    assert_eq!(parse_str("((a))"), Ok(term("a")));
+}
+
+#[test]
+fn invalid() {
+    let searches = [
+        ("", 0),
+        ("AND", 3),
+        ("a OR", 4),
+        ("NOT", 3),
+        ("a AND AND b", 9),
+        ("(a", 2),
+        ("a)", 1),
+        (r#""""#, 1),
+        (r#""open"#, 5),
+        ("NOT(a)", 3),
+    ];
+
+    for (search, position) in searches {
+        assert_eq!(
+            parse_str(search),
+            Err(ParseError::Parsing { position }),
+            "{search}"
+        );
+    }
+}
+
+#[test]
+fn limits() {
+    let options = ParseOptions {
+        max_depth: Some(3),
+        max_nodes: Some(5),
+        max_string_length: Some(4),
+        ..ParseOptions::default()
+    };
+
+    assert!(parse_str_with("NOT (a OR b)", &options).is_ok());
+    assert_eq!(
+        parse_str_with("NOT NOT NOT a", &options),
+        Err(ParseError::NestingTooDeep { max: 3 })
+    );
+    assert_eq!(
+        parse_str_with("a b c d", &options),
+        Err(ParseError::TooManyNodes { max: 5 })
+    );
+    assert_eq!(
+        parse_str_with(r#""long phrase""#, &options),
+        Err(ParseError::StringLiteralTooLong { max: 4 })
+    );
+    assert_eq!(
+        parse_str_with(
+            "a b",
+            &ParseOptions {
+                max_length: Some(2),
+                ..options
+            }
+        ),
+        Err(ParseError::InputTooLong { length: 3, max: 2 })
+    );
+}
+
+#[test]
+fn writes_query_strings() {
+    let cases = [
+        ("a OR b c", "a OR b AND c"),
+        ("(a OR b) AND c", "(a OR b) AND c"),
+        ("a OR (b OR c)", "a OR (b OR c)"),
+        ("NOT NOT (a b)", "NOT NOT (a AND b)"),
+        (r#""x \"y\"""#, r#""x \"y\"""#),
+    ];
+
+    for (search, expected) in cases {
+        let expr = parse_str(search).unwrap();
+        let written = to_query_string(&expr).unwrap();
+
+        assert_eq!(written, expected, "{search}");
+        assert_eq!(parse_str(&written), Ok(expr), "{search}");
+    }
+
+    assert_eq!(
+        to_query_string(&and(term("OR"), term("two words"))).unwrap(),
+        r#""OR" AND "two words""#
+    );
+}
+
+#[test]
+fn translates_to_tsquery() {
+    let cases = [
+        ("blue", "'blue'"),
+        ("a b OR c", "'a' & 'b' | 'c'"),
+        ("a (b OR c)", "'a' & ('b' | 'c')"),
+        ("NOT (a b)", "!('a' & 'b')"),
+        (r#"it's "back\\slash""#, r#"'it\'s' & 'back\\slash'"#),
+        ("a:* & !b", r"'a:*' & '&' & '!b'"),
+    ];
+
+    for (search, expected) in cases {
+        assert_eq!(
+            parse_str(search).unwrap().to_tsquery(),
+            expected,
+            "{search}"
+        );
+    }
+}
+
+#[test]
+fn translates_to_fts5() {
+    let cases = [
+        ("blue", r#""blue""#),
+        (r#"a "b c" OR d"#, r#"("a" AND "b c") OR "d""#),
+        ("NOT a b NOT c", r#""b" NOT "a" NOT "c""#),
+        ("a NOT (b OR c)", r#""a" NOT ("b" OR "c")"#),
+        ("NOT NOT a", r#""a""#),
+        ("a AND NOT NOT b", r#""a" AND "b""#),
+        (r#""say \"hi\"""#, r#""say ""hi""""#),
+    ];
+
+    for (search, expected) in cases {
+        assert_eq!(
+            parse_str(search).unwrap().to_fts5(),
+            Ok(expected.to_owned()),
+            "{search}"
+        );
+    }
+}
+
+#[test]
+fn fts5_rejects_unbound_not() {
+    let cases = [
+        ("NOT a", "NOT a"),
+        ("NOT a NOT b", "NOT a AND NOT b"),
+        ("a OR NOT b", "NOT b"),
+        ("a (b OR NOT c)", "NOT c"),
+    ];
+
+    for (search, expr) in cases {
+        assert_eq!(
+            parse_str(search).unwrap().to_fts5(),
+            Err(TranslateError::UnboundNot {
+                expr: expr.to_owned()
+            }),
+            "{search}"
+        );
+    }
+}